@@ -0,0 +1,8 @@
+mod element;
+mod reader;
+mod tune;
+mod writer;
+
+pub use element::{AbcBar, AbcElement, AbcWarning};
+pub use tune::{AbcScore, AbcTune};
+pub use writer::AbcExport;
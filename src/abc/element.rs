@@ -0,0 +1,89 @@
+use crate::chord::ChordSymbol;
+use crate::note::{Duration, NoteEvent};
+use crate::scale::Key;
+use crate::score::{Tempo, TimeSignature};
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum AbcBar {
+    #[default]
+    Single,
+    Double,
+    Start,
+    Final,
+    RepeatStart,
+    RepeatEnd,
+    RepeatBoth,
+}
+
+impl AbcBar {
+    pub const fn symbol(&self) -> &'static str {
+        match self {
+            AbcBar::Single => "|",
+            AbcBar::Double => "||",
+            AbcBar::Start => "[|",
+            AbcBar::Final => "|]",
+            AbcBar::RepeatStart => "|:",
+            AbcBar::RepeatEnd => ":|",
+            AbcBar::RepeatBoth => "::",
+        }
+    }
+    pub const fn is_repeat_end(&self) -> bool {
+        matches!(self, AbcBar::RepeatEnd | AbcBar::RepeatBoth)
+    }
+    pub const fn is_repeat_start(&self) -> bool {
+        matches!(self, AbcBar::RepeatStart | AbcBar::RepeatBoth)
+    }
+}
+
+impl fmt::Display for AbcBar {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AbcElement {
+    Event(NoteEvent),
+    ChordSymbol(ChordSymbol),
+    Annotation(String),
+    Bar(AbcBar),
+    Ending(Vec<usize>),
+    Key(Key),
+    Meter(TimeSignature),
+    Tempo(Tempo),
+    Voice(String),
+}
+
+impl AbcElement {
+    pub const fn event(&self) -> Option<&NoteEvent> {
+        match self {
+            AbcElement::Event(event) => Some(event),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AbcWarning {
+    KeyChange { position: Duration, key: Key },
+    Voice { part: String, voice: usize },
+    SkippedVoice { id: String },
+}
+
+impl fmt::Display for AbcWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AbcWarning::KeyChange { position, key } => write!(
+                f,
+                "key change to {key:#} at {}/{} is not kept",
+                position.length,
+                1usize << position.unit.exponent()
+            ),
+            AbcWarning::Voice { part, voice } => {
+                write!(f, "voice {voice} of part `{part}` is not kept")
+            }
+            AbcWarning::SkippedVoice { id } => write!(f, "voice `{id}` is not kept"),
+        }
+    }
+}
@@ -0,0 +1,798 @@
+use crate::abc::{AbcBar, AbcElement, AbcTune};
+use crate::chord::ChordSymbol;
+use crate::note::{
+    Accidental, Diatonic, Duration, NoteEvent, Octave, Pitch, PitchClass, Subdivision, Tie, Tuplet,
+};
+use crate::scale::{Key, Mode};
+use crate::score::{Tempo, TimeSignature};
+use crate::ParseError;
+use std::str::FromStr;
+
+const BARS: [(&str, AbcBar); 11] = [
+    (":||:", AbcBar::RepeatBoth),
+    (":|:", AbcBar::RepeatBoth),
+    (":|]", AbcBar::RepeatEnd),
+    (":||", AbcBar::RepeatEnd),
+    ("::", AbcBar::RepeatBoth),
+    (":|", AbcBar::RepeatEnd),
+    ("|:", AbcBar::RepeatStart),
+    ("||", AbcBar::Double),
+    ("|]", AbcBar::Final),
+    ("[|", AbcBar::Start),
+    ("|", AbcBar::Single),
+];
+
+const MUSIC: &[&str] = &["note", "rest", "bar line"];
+
+const MAX_EXPONENT: usize = 16;
+const MAX_BROKEN: usize = 3;
+
+fn scale(duration: Duration, (numerator, exponent): (usize, usize)) -> Duration {
+    Duration::new(
+        duration.length * numerator,
+        Subdivision::new(duration.unit.exponent() + exponent),
+    )
+    .reduce()
+}
+
+fn field(line: &str) -> Option<(char, &str)> {
+    let mut chars = line.chars();
+    let name = chars.next().filter(char::is_ascii_alphabetic)?;
+    let value = chars.as_str().strip_prefix(':')?;
+    match value.starts_with(['|', ':']) {
+        true => None,
+        false => Some((name, value)),
+    }
+}
+
+fn strip_comment(value: &str) -> &str {
+    value.split('%').next().unwrap_or_default().trim()
+}
+
+fn fraction(s: &str, at: usize) -> Result<Duration, ParseError> {
+    let (numerator, denominator) = s.split_once('/').unwrap_or((s, "1"));
+    let numerator: usize = numerator
+        .trim()
+        .parse()
+        .map_err(|_| ParseError::at(s, 0, &["fraction"]).offset(at))?;
+    match denominator.trim().parse::<usize>() {
+        Ok(value) if value.trailing_zeros() as usize > MAX_EXPONENT => {
+            let start = s.len() - denominator.len();
+            Err(ParseError::out_of_range(s, start..s.len(), 1, 1 << MAX_EXPONENT).offset(at))
+        }
+        Ok(denominator) if denominator.is_power_of_two() => Ok(Duration::new(
+            numerator,
+            Subdivision::new(denominator.trailing_zeros() as usize),
+        )
+        .reduce()),
+        _ => Err(ParseError::at(s, numerator.to_string().len(), &["power of two"]).offset(at)),
+    }
+}
+
+fn meter(value: &str, at: usize) -> Result<Option<TimeSignature>, ParseError> {
+    match value {
+        "" | "none" => Ok(None),
+        value => TimeSignature::from_str(value)
+            .map(Some)
+            .map_err(|error| error.offset(at)),
+    }
+}
+
+fn tempo(value: &str, at: usize, unit: Duration) -> Result<Tempo, ParseError> {
+    let mut rest = value;
+    let mut offset = at;
+    while let Some(start) = rest.find('"') {
+        let end = rest[start + 1..]
+            .find('"')
+            .ok_or(ParseError::UnexpectedEnd {
+                at: offset + rest.len(),
+                expected: &["\""],
+            })?;
+        let (before, after) = (&rest[..start], &rest[start + end + 2..]);
+        if !before.trim().is_empty() {
+            break;
+        }
+        offset += start + end + 2;
+        rest = after;
+    }
+    let rest = rest.split('"').next().unwrap_or_default();
+    let Some((beats, bpm)) = rest.split_once('=') else {
+        let bpm = rest
+            .trim()
+            .parse()
+            .map_err(|_| ParseError::at(rest, 0, &["bpm"]).offset(offset))?;
+        return Ok(Tempo::new(bpm, unit));
+    };
+    let bpm = bpm
+        .trim()
+        .parse()
+        .map_err(|_| ParseError::at(rest, beats.len() + 1, &["bpm"]).offset(offset))?;
+    let mut beat = Duration::ZERO;
+    for part in beats.split_whitespace() {
+        beat += match part {
+            "C" | "c" => unit,
+            part => fraction(
+                part,
+                offset + part.as_ptr() as usize - rest.as_ptr() as usize,
+            )?,
+        };
+    }
+    match beat.is_zero() {
+        true => Err(ParseError::at(rest, 0, &["beat length"]).offset(offset)),
+        false => Ok(Tempo::new(bpm, beat)),
+    }
+}
+
+fn key(value: &str, at: usize) -> Result<Key, ParseError> {
+    let mut tokens = value
+        .split_whitespace()
+        .filter(|token| !token.contains('='));
+    let Some(tonic) = tokens.next() else {
+        return Ok(Key::C_MAJOR);
+    };
+    let start = at + tonic.as_ptr() as usize - value.as_ptr() as usize;
+    match tonic {
+        "none" | "HP" => return Ok(Key::C_MAJOR),
+        "Hp" => return Ok(Key::from_fifths(2, Mode::MAJOR).relative(Mode::Mixolydian)),
+        _ => {}
+    }
+    let base = Diatonic::from_str(tonic.get(..1).unwrap_or_default())
+        .map_err(|_| ParseError::char_at(tonic, 0, &["key tonic"]).offset(start))?;
+    let (accidental, mut mode) = match tonic.as_bytes().get(1) {
+        Some(b'#') => (Accidental::SHARP, &tonic[2..]),
+        Some(b'b') => (Accidental::FLAT, &tonic[2..]),
+        _ => (Accidental::NATURAL, &tonic[1..]),
+    };
+    let mut mode_at = start + tonic.len() - mode.len();
+    if mode.is_empty() {
+        if let Some(token) = tokens.next() {
+            mode = token;
+            mode_at = at + token.as_ptr() as usize - value.as_ptr() as usize;
+        }
+    }
+    let abbreviation = match mode.to_ascii_lowercase() {
+        mode if mode == "m" => mode,
+        mode => mode.chars().take(3).collect(),
+    };
+    let mode = Mode::from_str(&abbreviation).map_err(|error| error.offset(mode_at))?;
+    if let Some(token) = tokens.next() {
+        let start = at + token.as_ptr() as usize - value.as_ptr() as usize;
+        return Err(ParseError::Unexpected {
+            span: start..start + token.len(),
+            found: token.to_string(),
+            expected: &["clef", "end of key"],
+        });
+    }
+    Ok(Key::new(PitchClass::new(base, accidental), mode))
+}
+
+struct TupletGroup {
+    tuplet: Tuplet,
+    remaining: usize,
+    events: Vec<NoteEvent>,
+    start: usize,
+}
+
+struct Reader<'a> {
+    text: &'a str,
+    at: usize,
+    tune: AbcTune,
+    accidentals: Vec<(Diatonic, Octave, Accidental)>,
+    tie: bool,
+    broken: Option<(usize, usize)>,
+    tuplet: Option<TupletGroup>,
+}
+
+impl<'a> Reader<'a> {
+    fn error(&self, expected: &'static [&'static str]) -> ParseError {
+        ParseError::char_at(self.text, self.at, expected)
+    }
+    fn peek(&self) -> Option<char> {
+        self.text[self.at..].chars().next()
+    }
+    fn eat(&mut self, c: char) -> bool {
+        let eaten = self.peek() == Some(c);
+        if eaten {
+            self.at += c.len_utf8();
+        }
+        eaten
+    }
+    fn number(&mut self) -> Option<usize> {
+        let digits = self.text[self.at..]
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(self.text.len() - self.at);
+        let number = self.text[self.at..self.at + digits].parse().ok()?;
+        self.at += digits;
+        Some(number)
+    }
+    fn header(&mut self) -> Result<(), ParseError> {
+        let mut unit = None;
+        let mut pending = None;
+        let mut seen = false;
+        while self.at < self.text.len() {
+            let start = self.at;
+            let line = self.text[start..].lines().next().unwrap_or_default();
+            self.at += self.text[start..]
+                .find('\n')
+                .map_or(line.len(), |end| end + 1);
+            let line = line.trim_end();
+            if line.starts_with('%') || line.is_empty() && !seen {
+                continue;
+            }
+            let Some((name, value)) = field(line) else {
+                return Err(ParseError::char_at(self.text, start, &["header field"]));
+            };
+            seen = true;
+            let at = start + 2 + value.len() - value.trim_start().len();
+            let value = strip_comment(value);
+            match name {
+                'X' => {
+                    self.tune.number = value
+                        .parse()
+                        .map_err(|_| ParseError::at(value, 0, &["tune number"]).offset(at))?
+                }
+                'T' => self.tune.titles.push(value.to_string()),
+                'M' => self.tune.meter = meter(value, at)?,
+                'L' => unit = Some(fraction(value, at)?),
+                'Q' => pending = Some((value, at)),
+                'K' => {
+                    self.tune.key = key(value, at)?;
+                    self.tune.unit =
+                        unit.unwrap_or_else(|| AbcTune::default_unit(self.tune.meter.as_ref()));
+                    if let Some((value, at)) = pending {
+                        self.tune.tempo = Some(tempo(value, at, self.tune.unit)?);
+                    }
+                    return Ok(());
+                }
+                name => self.tune.fields.push((name, value.to_string())),
+            }
+        }
+        Err(ParseError::UnexpectedEnd {
+            at: self.text.len(),
+            expected: &["K:"],
+        })
+    }
+    fn body(&mut self) -> Result<(), ParseError> {
+        let mut unit = self.tune.unit;
+        while self.at < self.text.len() {
+            let start = self.at;
+            let line = self.text[start..].lines().next().unwrap_or_default();
+            let next = start
+                + self.text[start..]
+                    .find('\n')
+                    .map_or(line.len(), |end| end + 1);
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            match field(line) {
+                Some((name, value)) => {
+                    let at = start + 2 + value.len() - value.trim_start().len();
+                    self.field(name, strip_comment(value), at, &mut unit)?;
+                }
+                None if line.starts_with('%') => {}
+                None => self.music(start + line.len(), &mut unit)?,
+            }
+            self.at = next;
+        }
+        if let Some(group) = &self.tuplet {
+            return Err(ParseError::UnexpectedEnd {
+                at: self.text.len().max(group.start),
+                expected: &["tuplet note"],
+            });
+        }
+        if self.broken.is_some() {
+            return Err(ParseError::UnexpectedEnd {
+                at: self.text.len(),
+                expected: &["note"],
+            });
+        }
+        Ok(())
+    }
+    fn field(
+        &mut self,
+        name: char,
+        value: &str,
+        at: usize,
+        unit: &mut Duration,
+    ) -> Result<(), ParseError> {
+        let started = self.tune.events().next().is_some();
+        match name {
+            'K' => {
+                let key = key(value, at)?;
+                match started {
+                    true => self.tune.elements.push(AbcElement::Key(key)),
+                    false => self.tune.key = key,
+                }
+                self.accidentals.clear();
+            }
+            'M' => match (meter(value, at)?, started) {
+                (Some(meter), true) => self.tune.elements.push(AbcElement::Meter(meter)),
+                (meter, false) => self.tune.meter = meter,
+                (None, true) => {}
+            },
+            'L' => *unit = fraction(value, at)?,
+            'Q' => {
+                let tempo = tempo(value, at, *unit)?;
+                match started {
+                    true => self.tune.elements.push(AbcElement::Tempo(tempo)),
+                    false => self.tune.tempo = Some(tempo),
+                }
+            }
+            'V' => {
+                let id = value.split_whitespace().next().unwrap_or_default();
+                if id.is_empty() {
+                    return Err(ParseError::at(value, 0, &["voice id"]).offset(at));
+                }
+                self.tune.elements.push(AbcElement::Voice(id.to_string()));
+                self.accidentals.clear();
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+    fn current_key(&self) -> Key {
+        self.tune
+            .elements
+            .iter()
+            .rev()
+            .find_map(|element| match element {
+                AbcElement::Key(key) => Some(*key),
+                _ => None,
+            })
+            .unwrap_or(self.tune.key)
+    }
+    fn last_event(&mut self) -> Option<&mut NoteEvent> {
+        if let Some(group) = &mut self.tuplet {
+            return group.events.last_mut();
+        }
+        for element in self.tune.elements.iter_mut().rev() {
+            match element {
+                AbcElement::Event(event) => return Some(event),
+                AbcElement::ChordSymbol(_) | AbcElement::Annotation(_) => {}
+                _ => return None,
+            }
+        }
+        None
+    }
+    fn push(&mut self, mut event: NoteEvent) -> Result<(), ParseError> {
+        if std::mem::take(&mut self.tie) {
+            event.set_tie(Tie::new(event.tie().start, true));
+        }
+        if let Some(factor) = self.broken.take() {
+            event.set_value(scale(event.value(), factor));
+        }
+        let Some(mut group) = self.tuplet.take() else {
+            self.tune.elements.push(AbcElement::Event(event));
+            return Ok(());
+        };
+        group.events.push(event);
+        group.remaining -= 1;
+        if group.remaining > 0 {
+            self.tuplet = Some(group);
+            return Ok(());
+        }
+        let tuplet =
+            NoteEvent::tuplet(group.tuplet, group.events).ok_or(ParseError::unexpected(
+                self.text,
+                group.start..self.at,
+                &["tuplet of whole subdivisions"],
+            ))?;
+        self.tune.elements.push(AbcElement::Event(tuplet));
+        Ok(())
+    }
+    fn factor(&mut self) -> Result<(usize, usize), ParseError> {
+        let start = self.at;
+        let numerator = self.number().unwrap_or(1);
+        let mut exponent = 0;
+        while self.eat('/') {
+            let at = self.at;
+            match self.number() {
+                None => exponent += 1,
+                Some(denominator) if denominator.is_power_of_two() => {
+                    exponent += denominator.trailing_zeros() as usize
+                }
+                Some(_) => return Err(ParseError::char_at(self.text, at, &["power of two"])),
+            }
+        }
+        match numerator {
+            0 => Err(ParseError::unexpected(
+                self.text,
+                start..self.at,
+                &["note length"],
+            )),
+            _ if exponent > MAX_EXPONENT => Err(ParseError::out_of_range(
+                self.text,
+                start..self.at,
+                0,
+                MAX_EXPONENT as isize,
+            )),
+            _ => Ok((numerator, exponent)),
+        }
+    }
+    fn pitch(&mut self) -> Result<Pitch, ParseError> {
+        let mut accidental = None;
+        for (token, shift) in [("^^", 2), ("__", -2), ("^", 1), ("_", -1), ("=", 0)] {
+            if self.text[self.at..].starts_with(token) {
+                accidental = Some(Accidental::new(shift));
+                self.at += token.len();
+                break;
+            }
+        }
+        let letter = self
+            .peek()
+            .filter(|c| matches!(c, 'A'..='G' | 'a'..='g'))
+            .ok_or(self.error(&["note name"]))?;
+        self.at += 1;
+        let base = Diatonic::from_str(&letter.to_ascii_uppercase().to_string())
+            .map_err(|_| self.error(&["note name"]))?;
+        let mut octave = if letter.is_ascii_uppercase() { 4 } else { 5 };
+        loop {
+            match self.peek() {
+                Some('\'') => octave += 1,
+                Some(',') => octave -= 1,
+                _ => break,
+            }
+            self.at += 1;
+        }
+        let octave = Octave::new(octave);
+        let accidental = match accidental {
+            Some(accidental) => {
+                self.accidentals
+                    .retain(|&(other, at, _)| (other, at) != (base, octave));
+                self.accidentals.push((base, octave, accidental));
+                accidental
+            }
+            None => self
+                .accidentals
+                .iter()
+                .find(|&&(other, at, _)| (other, at) == (base, octave))
+                .map_or(
+                    self.current_key().accidental(base),
+                    |&(_, _, accidental)| accidental,
+                ),
+        };
+        Ok(Pitch::new(PitchClass::new(base, accidental), octave))
+    }
+    fn skip_to(&mut self, close: char, end: usize) -> Result<usize, ParseError> {
+        let start = self.at;
+        self.at += 1;
+        let length = self.text[self.at..end]
+            .find(close)
+            .ok_or(ParseError::UnexpectedEnd {
+                at: end,
+                expected: &["closing delimiter"],
+            })?;
+        self.at += length + close.len_utf8();
+        Ok(start)
+    }
+    fn ending(&mut self) -> Result<(), ParseError> {
+        let mut numbers = Vec::new();
+        loop {
+            let first = self.number().ok_or(self.error(&["ending number"]))?;
+            if self.eat('-') {
+                let last = self.number().ok_or(self.error(&["ending number"]))?;
+                numbers.extend(first..=last);
+            } else {
+                numbers.push(first);
+            }
+            if !self.eat(',') {
+                break;
+            }
+        }
+        self.tune.elements.push(AbcElement::Ending(numbers));
+        Ok(())
+    }
+    fn music(&mut self, end: usize, unit: &mut Duration) -> Result<(), ParseError> {
+        while self.at < end {
+            let Some(c) = self.peek() else {
+                break;
+            };
+            match c {
+                '%' => break,
+                ' ' | '\t' | '\\' | '`' | 'y' | '$' | ')' | '.' | '~' | 'H' | 'L' | 'M' | 'O'
+                | 'P' | 'S' | 'T' | 'u' | 'v' => self.at += 1,
+                '!' | '+' => {
+                    self.skip_to(c, end)?;
+                }
+                '{' => {
+                    self.skip_to('}', end)?;
+                }
+                '"' => {
+                    let start = self.skip_to('"', end)?;
+                    let text = &self.text[start + 1..self.at - 1];
+                    let element = match text.starts_with(['^', '_', '<', '>', '@']) {
+                        true => AbcElement::Annotation(text.to_string()),
+                        false => match ChordSymbol::from_str(text) {
+                            Ok(chord) => AbcElement::ChordSymbol(chord),
+                            Err(_) => AbcElement::Annotation(text.to_string()),
+                        },
+                    };
+                    self.tune.elements.push(element);
+                }
+                '(' => {
+                    let start = self.at;
+                    self.at += 1;
+                    let Some(actual) = self.number() else {
+                        continue;
+                    };
+                    let mut normal = None;
+                    let mut count = None;
+                    if self.eat(':') {
+                        normal = self.number();
+                        if self.eat(':') {
+                            count = self.number();
+                        }
+                    }
+                    let compound = self
+                        .tune
+                        .meter
+                        .as_ref()
+                        .is_some_and(TimeSignature::is_compound);
+                    let normal = normal.unwrap_or(match actual {
+                        3 | 6 => 2,
+                        2 | 4 | 8 => 3,
+                        _ if compound => 3,
+                        _ => 2,
+                    });
+                    if actual < 2 || normal == 0 || self.tuplet.is_some() {
+                        return Err(ParseError::unexpected(
+                            self.text,
+                            start..self.at,
+                            &["tuplet"],
+                        ));
+                    }
+                    self.tuplet = Some(TupletGroup {
+                        tuplet: Tuplet::new(actual, normal),
+                        remaining: count.unwrap_or(actual),
+                        events: Vec::new(),
+                        start,
+                    });
+                }
+                '[' => {
+                    let rest = &self.text[self.at + 1..end];
+                    if let Some((name, value)) = field(rest) {
+                        let close = value.find(']').ok_or(ParseError::UnexpectedEnd {
+                            at: end,
+                            expected: &["]"],
+                        })?;
+                        let at = self.at + 3;
+                        self.field(name, value[..close].trim(), at, unit)?;
+                        self.at = at + close + 1;
+                    } else if rest.starts_with(|c: char| c.is_ascii_digit()) {
+                        self.at += 1;
+                        self.ending()?;
+                    } else if rest.starts_with("|]") {
+                        self.at += 3;
+                    } else if rest.starts_with('|') {
+                        self.at += 2;
+                        self.accidentals.clear();
+                        self.tune.elements.push(AbcElement::Bar(AbcBar::Start));
+                    } else {
+                        self.chord(end, *unit)?;
+                    }
+                }
+                '|' | ':' => {
+                    let (token, bar) = BARS
+                        .iter()
+                        .find(|(token, _)| self.text[self.at..end].starts_with(token))
+                        .ok_or(self.error(MUSIC))?;
+                    self.at += token.len();
+                    self.accidentals.clear();
+                    self.tune.elements.push(AbcElement::Bar(*bar));
+                    if self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                        self.ending()?;
+                    }
+                }
+                '-' => {
+                    let error = self.error(&["note before tie"]);
+                    self.at += 1;
+                    let event = self
+                        .last_event()
+                        .filter(|event| !event.is_rest())
+                        .ok_or(error)?;
+                    event.set_tie(Tie::new(true, event.tie().stop));
+                    self.tie = true;
+                }
+                '>' | '<' => {
+                    let start = self.at;
+                    let count = self.text[self.at..end]
+                        .find(|other| other != c)
+                        .unwrap_or(end - self.at);
+                    self.at += count;
+                    if count > MAX_BROKEN {
+                        return Err(ParseError::out_of_range(
+                            self.text,
+                            start..self.at,
+                            1,
+                            MAX_BROKEN as isize,
+                        ));
+                    }
+                    let long = ((2 << count) - 1, count);
+                    let short = (1, count);
+                    let (previous, next) = if c == '>' {
+                        (long, short)
+                    } else {
+                        (short, long)
+                    };
+                    let error = ParseError::unexpected(self.text, start..self.at, &["note"]);
+                    let event = self.last_event().ok_or(error)?;
+                    event.set_value(scale(event.value(), previous));
+                    self.broken = Some(next);
+                }
+                '^' | '_' | '=' | 'A'..='G' | 'a'..='g' => {
+                    let pitch = self.pitch()?;
+                    let value = scale(*unit, self.factor()?);
+                    self.push(NoteEvent::single(pitch, value))?;
+                }
+                'z' | 'x' => {
+                    self.at += 1;
+                    let value = scale(*unit, self.factor()?);
+                    self.push(NoteEvent::rest(value))?;
+                }
+                'Z' | 'X' => {
+                    self.at += 1;
+                    let bars = self.number().unwrap_or(1);
+                    let meter = self
+                        .tune
+                        .elements
+                        .iter()
+                        .rev()
+                        .find_map(|element| match element {
+                            AbcElement::Meter(meter) => Some(meter),
+                            _ => None,
+                        })
+                        .or(self.tune.meter.as_ref())
+                        .map_or(
+                            Subdivision::WHOLE_NOTE.to_duration(),
+                            TimeSignature::bar_duration,
+                        );
+                    self.push(NoteEvent::rest(meter.scale(bars)))?;
+                }
+                _ => return Err(self.error(MUSIC)),
+            }
+        }
+        self.at = end;
+        Ok(())
+    }
+    fn chord(&mut self, end: usize, unit: Duration) -> Result<(), ParseError> {
+        self.at += 1;
+        let mut pitches = Vec::new();
+        let mut value = None;
+        let mut tie = false;
+        loop {
+            match self.peek() {
+                Some(']') => break,
+                Some('-') => {
+                    self.at += 1;
+                    tie = true;
+                }
+                Some('!' | '+') if self.at < end => {
+                    let c = self.peek().unwrap_or_default();
+                    self.skip_to(c, end)?;
+                }
+                Some('.' | '~' | ' ') => self.at += 1,
+                Some(_) if self.at < end => {
+                    let pitch = self.pitch()?;
+                    let factor = self.factor()?;
+                    value.get_or_insert(scale(unit, factor));
+                    if !pitches.contains(&pitch) {
+                        pitches.push(pitch);
+                    }
+                }
+                _ => {
+                    return Err(ParseError::UnexpectedEnd {
+                        at: end,
+                        expected: &["]"],
+                    })
+                }
+            }
+        }
+        self.at += 1;
+        let value = value.ok_or(ParseError::char_at(self.text, self.at - 1, &["note"]))?;
+        let value = scale(value, self.factor()?);
+        let event = match pitches.len() {
+            1 => NoteEvent::single(pitches[0], value),
+            _ => NoteEvent::chord(pitches, value),
+        };
+        self.push(event)?;
+        if tie {
+            if let Some(event) = self.last_event() {
+                event.set_tie(Tie::new(true, event.tie().stop));
+            }
+            self.tie = true;
+        }
+        Ok(())
+    }
+}
+
+impl AbcTune {
+    pub fn parse_all(s: &str) -> Result<Vec<Self>, ParseError> {
+        let mut starts: Vec<usize> = s
+            .split_inclusive('\n')
+            .scan(0, |at, line| {
+                let start = *at;
+                *at += line.len();
+                Some((start, line))
+            })
+            .filter(|(_, line)| line.starts_with("X:"))
+            .map(|(start, _)| start)
+            .collect();
+        starts.push(s.len());
+        starts
+            .windows(2)
+            .map(|window| {
+                AbcTune::from_str(&s[window[0]..window[1]]).map_err(|error| error.offset(window[0]))
+            })
+            .collect()
+    }
+}
+
+impl FromStr for AbcTune {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut reader = Reader {
+            text: s,
+            at: 0,
+            tune: AbcTune::new(Key::C_MAJOR),
+            accidentals: Vec::new(),
+            tie: false,
+            broken: None,
+            tuplet: None,
+        };
+        reader.header()?;
+        reader.body()?;
+        Ok(reader.tune)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::abc::{AbcScore, AbcWarning};
+    use crate::score::Voice;
+
+    #[test]
+    fn round_trip() {
+        let tune: AbcTune = "X:1\nT:Test\nM:4/4\nL:1/8\nK:G\nGABc d2 e2|[K:D] f4 ^g4|]\n"
+            .parse()
+            .unwrap();
+        assert_eq!(tune.to_string().parse::<AbcTune>(), Ok(tune.clone()));
+        let AbcScore { score, warnings } = tune.to_score();
+        assert_eq!(score.voices().next().map(Voice::len), Some(8));
+        assert!(matches!(warnings[..], [AbcWarning::KeyChange { .. }]));
+    }
+
+    #[test]
+    fn body_voice_is_skipped_with_warning() {
+        let tune: AbcTune =
+            "X:1\nM:4/4\nL:1/4\nK:C\nV:1\nCDEF|\nV:2 clef=bass\nC,4|\nV:1\nGABc|]\n"
+                .parse()
+                .unwrap();
+        assert_eq!(tune.to_string().parse::<AbcTune>(), Ok(tune.clone()));
+        let AbcScore { score, warnings } = tune.to_score();
+        assert_eq!(score.voices().next().map(Voice::len), Some(8));
+        assert_eq!(
+            warnings,
+            [AbcWarning::SkippedVoice {
+                id: "2".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn rejects_overlong_broken_rhythm() {
+        let error = "X:1\nK:C\nC>>>>D|\n".parse::<AbcTune>().unwrap_err();
+        assert_eq!(
+            error,
+            ParseError::out_of_range("X:1\nK:C\nC>>>>D|\n", 9..13, 1, 3)
+        );
+    }
+
+    #[test]
+    fn rejects_overlong_note_length() {
+        let text = "X:1\nK:C\nC//////////////////|\n";
+        let error = text.parse::<AbcTune>().unwrap_err();
+        assert_eq!(error, ParseError::out_of_range(text, 9..27, 0, 16));
+    }
+}
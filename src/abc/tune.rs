@@ -0,0 +1,190 @@
+use crate::abc::{AbcBar, AbcElement, AbcWarning};
+use crate::note::{Duration, NoteEvent, Subdivision};
+use crate::scale::Key;
+use crate::score::{Clef, Instrument, Part, Score, Staff, Tempo, TimeSignature, Voice};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AbcScore {
+    pub score: Score,
+    pub warnings: Vec<AbcWarning>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AbcTune {
+    pub number: usize,
+    pub titles: Vec<String>,
+    pub meter: Option<TimeSignature>,
+    pub unit: Duration,
+    pub tempo: Option<Tempo>,
+    pub key: Key,
+    pub fields: Vec<(char, String)>,
+    pub elements: Vec<AbcElement>,
+}
+
+impl AbcTune {
+    pub fn new(key: Key) -> Self {
+        AbcTune {
+            number: 1,
+            titles: Vec::new(),
+            meter: None,
+            unit: Subdivision::X8TH_NOTE.to_duration(),
+            tempo: None,
+            key,
+            fields: Vec::new(),
+            elements: Vec::new(),
+        }
+    }
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.titles.push(title.into());
+        self
+    }
+    pub fn with_meter(mut self, meter: TimeSignature) -> Self {
+        self.unit = AbcTune::default_unit(Some(&meter));
+        self.meter = Some(meter);
+        self
+    }
+    pub const fn with_tempo(mut self, tempo: Tempo) -> Self {
+        self.tempo = Some(tempo);
+        self
+    }
+    pub fn with_field(mut self, name: char, value: impl Into<String>) -> Self {
+        self.fields.push((name, value.into()));
+        self
+    }
+    pub fn title(&self) -> Option<&str> {
+        self.titles.first().map(String::as_str)
+    }
+    pub fn field(&self, name: char) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field, _)| *field == name)
+            .map(|(_, value)| value.as_str())
+    }
+    pub fn default_unit(meter: Option<&TimeSignature>) -> Duration {
+        match meter {
+            Some(meter) if meter.bar_duration().as_f64() < 0.75 => {
+                Subdivision::X16TH_NOTE.to_duration()
+            }
+            _ => Subdivision::X8TH_NOTE.to_duration(),
+        }
+    }
+    pub fn events(&self) -> impl Iterator<Item = &NoteEvent> {
+        self.elements.iter().filter_map(AbcElement::event)
+    }
+    pub fn duration(&self) -> Duration {
+        NoteEvent::total_duration(self.events())
+    }
+    fn passes(elements: &[AbcElement]) -> usize {
+        elements
+            .iter()
+            .take_while(|element| !matches!(element, AbcElement::Bar(bar) if bar.is_repeat_start()))
+            .filter_map(|element| match element {
+                AbcElement::Ending(numbers) => numbers.iter().max().copied(),
+                _ => None,
+            })
+            .fold(2, usize::max)
+    }
+    pub fn unfolded(&self) -> Self {
+        let elements = &self.elements;
+        let mut unfolded: Vec<AbcElement> = Vec::new();
+        let bar = |unfolded: &mut Vec<AbcElement>, bar: AbcBar| {
+            if !matches!(unfolded.last(), None | Some(AbcElement::Bar(_))) {
+                unfolded.push(AbcElement::Bar(bar));
+            }
+        };
+        let mut start = 0;
+        let mut pass = 1;
+        let mut skipping = false;
+        let mut i = 0;
+        while i < elements.len() {
+            let element = &elements[i];
+            i += 1;
+            match element {
+                AbcElement::Ending(numbers) => skipping = !numbers.contains(&pass),
+                AbcElement::Bar(repeat) if repeat.is_repeat_end() => {
+                    if !skipping && pass < AbcTune::passes(&elements[start..]) {
+                        pass += 1;
+                        i = start;
+                        bar(&mut unfolded, AbcBar::Single);
+                        continue;
+                    }
+                    if !skipping {
+                        start = i;
+                        if !matches!(elements.get(i), Some(AbcElement::Ending(_))) {
+                            pass = 1;
+                        }
+                    }
+                    skipping = false;
+                    bar(&mut unfolded, AbcBar::Single);
+                }
+                AbcElement::Bar(AbcBar::RepeatStart) => {
+                    start = i;
+                    pass = 1;
+                    skipping = false;
+                    bar(&mut unfolded, AbcBar::Single);
+                }
+                AbcElement::Bar(AbcBar::Double | AbcBar::Final) if skipping => {
+                    skipping = false;
+                    unfolded.push(element.clone());
+                }
+                _ if skipping => {}
+                _ => unfolded.push(element.clone()),
+            }
+        }
+        AbcTune {
+            elements: unfolded,
+            ..self.clone()
+        }
+    }
+    pub fn to_score(&self) -> AbcScore {
+        let meter = self
+            .meter
+            .clone()
+            .unwrap_or_else(TimeSignature::common_time);
+        let mut score = Score::new(meter, self.tempo.unwrap_or_default());
+        score.title = self.title().map(str::to_string);
+        score.composer = self.field('C').map(str::to_string);
+        let mut part = Part::new("Music", Instrument::new("Piano", 0), self.key);
+        let mut voice = Voice::new();
+        let mut warnings = Vec::new();
+        let mut position = Duration::ZERO;
+        let mut main = None;
+        let mut current = "";
+        for element in &self.elements {
+            if let AbcElement::Voice(id) = element {
+                current = id;
+                continue;
+            }
+            if main.is_none() && element.event().is_some() {
+                main = Some(current);
+            }
+            if main.is_some_and(|main| main != current) {
+                let warning = AbcWarning::SkippedVoice {
+                    id: current.to_string(),
+                };
+                if !warnings.contains(&warning) {
+                    warnings.push(warning);
+                }
+                continue;
+            }
+            match element {
+                AbcElement::Event(event) => {
+                    voice.push(event.clone());
+                    position += event.value();
+                }
+                AbcElement::ChordSymbol(chord) => part.set_chord(position, chord.clone()),
+                AbcElement::Meter(meter) => score.set_time_signature(position, meter.clone()),
+                AbcElement::Tempo(tempo) => score.tempo.set_tempo(position, *tempo),
+                AbcElement::Key(key) => warnings.push(AbcWarning::KeyChange {
+                    position,
+                    key: *key,
+                }),
+                _ => {}
+            }
+        }
+        AbcScore {
+            score: score.with_part(part.with_staff(Staff::new(Clef::Treble).with_voice(voice))),
+            warnings,
+        }
+    }
+}
@@ -0,0 +1,454 @@
+use crate::abc::{AbcBar, AbcElement, AbcTune, AbcWarning};
+use crate::note::{Accidental, Diatonic, Duration, NoteEvent, Octave, Pitch, Subdivision};
+use crate::scale::{Key, Mode};
+use crate::score::{Clef, Instrument, Part, Score, Staff, Tempo, TimeSignature};
+use std::fmt;
+
+const BARS_PER_LINE: usize = 4;
+
+fn length(value: Duration, unit: Duration) -> String {
+    let exponent = value.unit.exponent().max(unit.unit.exponent());
+    let value = value.to_unit(Subdivision::new(exponent)).unwrap_or(value);
+    let unit = unit.to_unit(Subdivision::new(exponent)).unwrap_or(unit);
+    let numerator = value.length;
+    let mut denominator = unit.length;
+    let divisor = gcd(numerator, denominator);
+    denominator /= divisor;
+    match (numerator / divisor, denominator) {
+        (1, 1) => String::new(),
+        (numerator, 1) => numerator.to_string(),
+        (1, 2) => "/".to_string(),
+        (1, denominator) => format!("/{denominator}"),
+        (numerator, denominator) => format!("{numerator}/{denominator}"),
+    }
+}
+
+const fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn fraction(value: Duration) -> String {
+    let value = value.reduce();
+    format!("{}/{}", value.length, value.unit.nth())
+}
+
+fn key(key: &Key) -> String {
+    let accidental = match key.tonic.accidental.pitch_shift() {
+        1 => "#",
+        -1 => "b",
+        _ => "",
+    };
+    let mode = match key.mode {
+        Mode::Ionian => "",
+        Mode::Aeolian => "m",
+        mode => &mode.name()[..3],
+    };
+    format!("{}{accidental}{mode}", key.tonic.base)
+}
+
+fn tempo(tempo: &Tempo) -> String {
+    format!("{}={}", fraction(tempo.beat), tempo.bpm)
+}
+
+struct Writer<'a> {
+    tune: &'a AbcTune,
+    out: String,
+    key: Key,
+    meter: TimeSignature,
+    accidentals: Vec<(Diatonic, Octave, Accidental)>,
+    position: Duration,
+    beam: Option<usize>,
+    bars: usize,
+}
+
+impl Writer<'_> {
+    fn separate(&mut self) {
+        if !self.out.is_empty() && !self.out.ends_with([' ', '\n']) {
+            self.out.push(' ');
+        }
+    }
+    fn group(&self, position: Duration) -> usize {
+        let mut end = Duration::ZERO;
+        for (i, beat) in self.meter.beats().into_iter().enumerate() {
+            end += beat;
+            if position < end {
+                return i;
+            }
+        }
+        usize::MAX
+    }
+    fn pitch(&mut self, pitch: Pitch) {
+        let base = pitch.pitch_class.base;
+        let octave = pitch.octave;
+        let expected = self
+            .accidentals
+            .iter()
+            .find(|&&(other, at, _)| (other, at) == (base, octave))
+            .map_or(self.key.accidental(base), |&(_, _, accidental)| accidental);
+        let accidental = pitch.pitch_class.accidental;
+        if accidental != expected {
+            self.accidentals
+                .retain(|&(other, at, _)| (other, at) != (base, octave));
+            self.accidentals.push((base, octave, accidental));
+            let shift = accidental.pitch_shift();
+            let symbol = match shift > 0 {
+                true => "^",
+                false if shift < 0 => "_",
+                false => "=",
+            };
+            for _ in 0..shift.abs().max(1) {
+                self.out.push_str(symbol);
+            }
+        }
+        let name = base.to_string();
+        match octave.numeric() {
+            octave if octave <= 4 => {
+                self.out.push_str(&name);
+                for _ in octave..4 {
+                    self.out.push(',');
+                }
+            }
+            octave => {
+                self.out.push_str(&name.to_ascii_lowercase());
+                for _ in 5..octave {
+                    self.out.push('\'');
+                }
+            }
+        }
+    }
+    fn event(&mut self, event: &NoteEvent) {
+        let unit = self.tune.unit;
+        match event {
+            NoteEvent::Rest { value } => {
+                self.out.push('z');
+                self.out.push_str(&length(*value, unit));
+            }
+            NoteEvent::Single { pitch, value, tie } => {
+                self.pitch(*pitch);
+                self.out.push_str(&length(*value, unit));
+                if tie.start {
+                    self.out.push('-');
+                }
+            }
+            NoteEvent::Chord {
+                pitches,
+                value,
+                tie,
+            } => {
+                self.out.push('[');
+                for pitch in pitches {
+                    self.pitch(*pitch);
+                }
+                self.out.push(']');
+                self.out.push_str(&length(*value, unit));
+                if tie.start {
+                    self.out.push('-');
+                }
+            }
+            NoteEvent::Tuplet { tuplet, events, .. } => {
+                let compound = self
+                    .tune
+                    .meter
+                    .as_ref()
+                    .is_some_and(TimeSignature::is_compound);
+                let normal = match tuplet.actual {
+                    3 | 6 => 2,
+                    2 | 4 | 8 => 3,
+                    _ if compound => 3,
+                    _ => 2,
+                };
+                self.out.push_str(&format!("({}", tuplet.actual));
+                if tuplet.normal != normal || events.len() != tuplet.actual {
+                    self.out.push_str(&format!(":{}", tuplet.normal));
+                }
+                if events.len() != tuplet.actual {
+                    self.out.push_str(&format!(":{}", events.len()));
+                }
+                for event in events {
+                    self.event(event);
+                }
+            }
+        }
+    }
+    fn element(&mut self, element: &AbcElement) {
+        match element {
+            AbcElement::Event(event) => {
+                let group = self.group(self.position);
+                let beamable = matches!(event, NoteEvent::Single { .. } | NoteEvent::Chord { .. })
+                    && event.value() < Subdivision::QUARTER_NOTE.to_duration();
+                if !beamable || self.beam != Some(group) {
+                    self.separate();
+                }
+                self.event(event);
+                self.position += event.value();
+                self.beam = match beamable && self.group(self.position) == group {
+                    true => Some(group),
+                    false => None,
+                };
+            }
+            AbcElement::ChordSymbol(chord) => {
+                if self.beam.is_none() {
+                    self.separate();
+                }
+                self.out.push_str(&format!("\"{chord:#}\""));
+            }
+            AbcElement::Annotation(text) => {
+                if self.beam.is_none() {
+                    self.separate();
+                }
+                self.out.push_str(&format!("\"{text}\""));
+            }
+            AbcElement::Bar(bar) => {
+                self.separate();
+                self.out.push_str(bar.symbol());
+                self.accidentals.clear();
+                self.position = Duration::ZERO;
+                self.beam = None;
+                self.bars += 1;
+                if self.bars.is_multiple_of(BARS_PER_LINE) {
+                    self.out.push('\n');
+                }
+            }
+            AbcElement::Ending(numbers) => {
+                self.separate();
+                self.out.push('[');
+                for (i, number) in numbers.iter().enumerate() {
+                    if i > 0 {
+                        self.out.push(',');
+                    }
+                    self.out.push_str(&number.to_string());
+                }
+                self.beam = None;
+            }
+            AbcElement::Key(new) => {
+                self.separate();
+                self.out.push_str(&format!("[K:{}]", key(new)));
+                self.key = *new;
+                self.accidentals.clear();
+                self.beam = None;
+            }
+            AbcElement::Meter(meter) => {
+                self.separate();
+                self.out.push_str(&format!("[M:{meter}]"));
+                self.meter = meter.clone();
+                self.beam = None;
+            }
+            AbcElement::Tempo(new) => {
+                self.separate();
+                self.out.push_str(&format!("[Q:{}]", tempo(new)));
+                self.beam = None;
+            }
+            AbcElement::Voice(id) => {
+                self.separate();
+                self.out.push_str(&format!("[V:{id}]"));
+                self.accidentals.clear();
+                self.beam = None;
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AbcExport {
+    pub tune: AbcTune,
+    pub warnings: Vec<AbcWarning>,
+}
+
+impl AbcTune {
+    pub fn common_unit<'a>(events: impl IntoIterator<Item = &'a NoteEvent>) -> Duration {
+        let mut counts: Vec<(Subdivision, usize)> = Vec::new();
+        let mut count = |value: Duration| {
+            if value.is_zero() {
+                return;
+            }
+            let unit = value
+                .as_dotted()
+                .map_or(value.reduce().unit, |(unit, _)| unit);
+            match counts.iter_mut().find(|(other, _)| *other == unit) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((unit, 1)),
+            }
+        };
+        for event in events {
+            match event {
+                NoteEvent::Tuplet { events, .. } => {
+                    events.iter().for_each(|event| count(event.value()))
+                }
+                event => count(event.value()),
+            }
+        }
+        counts
+            .into_iter()
+            .max_by_key(|&(unit, count)| {
+                (
+                    count,
+                    unit == Subdivision::X8TH_NOTE,
+                    std::cmp::Reverse(unit),
+                )
+            })
+            .map_or(Subdivision::X8TH_NOTE, |(unit, _)| unit)
+            .to_duration()
+    }
+    pub fn from_events(
+        events: impl IntoIterator<Item = NoteEvent>,
+        meter: TimeSignature,
+        key: Key,
+    ) -> Self {
+        let part = Part::new("Music", Instrument::new("Piano", 0), key)
+            .with_staff(Staff::new(Clef::Treble).with_voice(events.into_iter().collect()));
+        AbcTune::from_score(&Score::new(meter, Tempo::default()).with_part(part)).tune
+    }
+    pub fn from_score(score: &Score) -> AbcExport {
+        let warnings = score
+            .parts
+            .iter()
+            .enumerate()
+            .flat_map(|(i, part)| {
+                (1..=part.voices().count())
+                    .skip(usize::from(i == 0))
+                    .map(|voice| AbcWarning::Voice {
+                        part: part.name.clone(),
+                        voice,
+                    })
+            })
+            .collect();
+        let part = score.parts.first();
+        let mut tune = AbcTune::new(part.map_or(Key::C_MAJOR, |part| part.key));
+        tune.titles.extend(score.title.clone());
+        if let Some(composer) = &score.composer {
+            tune.fields.push(('C', composer.clone()));
+        }
+        tune.meter = score
+            .time_signatures
+            .first()
+            .map(|(_, time_signature)| time_signature.clone());
+        tune.tempo = score.tempo.changes().first().map(|change| change.tempo);
+        let Some(voice) = part.and_then(|part| part.voices().next()) else {
+            return AbcExport { tune, warnings };
+        };
+        let mut tempos = score.tempo.changes().iter().skip(1).peekable();
+        let mut chords = part
+            .map_or(&[][..], |part| &part.chords[..])
+            .iter()
+            .peekable();
+        let barlines = score.barlines();
+        let measures = score.measures(voice);
+        for (i, (measure, start)) in measures.iter().zip(&barlines).enumerate() {
+            if i > 0
+                && score
+                    .time_signatures
+                    .iter()
+                    .any(|(position, _)| position == start)
+            {
+                tune.elements
+                    .push(AbcElement::Meter(measure.time_signature.clone()));
+            }
+            let mut position = *start;
+            for event in &measure.events {
+                let end = position + event.value();
+                while let Some(change) = tempos.next_if(|change| change.position < end) {
+                    tune.elements.push(AbcElement::Tempo(change.tempo));
+                }
+                while let Some((_, chord)) = chords.next_if(|(at, _)| *at < end) {
+                    tune.elements.push(AbcElement::ChordSymbol(chord.clone()));
+                }
+                tune.elements.push(AbcElement::Event(event.clone()));
+                position = end;
+            }
+            let bar = if i + 1 == measures.len() {
+                AbcBar::Final
+            } else {
+                AbcBar::Single
+            };
+            tune.elements.push(AbcElement::Bar(bar));
+        }
+        tune.unit = AbcTune::common_unit(tune.events());
+        AbcExport { tune, warnings }
+    }
+}
+
+impl fmt::Display for AbcTune {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "X:{}", self.number)?;
+        for title in &self.titles {
+            writeln!(f, "T:{title}")?;
+        }
+        for (name, value) in &self.fields {
+            writeln!(f, "{name}:{value}")?;
+        }
+        match &self.meter {
+            Some(meter) => writeln!(f, "M:{meter}")?,
+            None => writeln!(f, "M:none")?,
+        }
+        writeln!(f, "L:{}", fraction(self.unit))?;
+        if let Some(initial) = &self.tempo {
+            writeln!(f, "Q:{}", tempo(initial))?;
+        }
+        writeln!(f, "K:{}", key(&self.key))?;
+        let mut writer = Writer {
+            tune: self,
+            out: String::new(),
+            key: self.key,
+            meter: self
+                .meter
+                .clone()
+                .unwrap_or_else(TimeSignature::common_time),
+            accidentals: Vec::new(),
+            position: Duration::ZERO,
+            beam: None,
+            bars: 0,
+        };
+        for element in &self.elements {
+            writer.element(element);
+        }
+        let body = writer.out.trim_end();
+        match body.is_empty() {
+            true => Ok(()),
+            false => writeln!(f, "{body}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::score::Voice;
+
+    #[test]
+    fn from_score_round_trip() {
+        let quarter = Subdivision::QUARTER_NOTE.to_duration();
+        let events: Vec<NoteEvent> = ["C4", "E4", "G4", "C5"]
+            .iter()
+            .map(|pitch| NoteEvent::single(pitch.parse().unwrap(), quarter))
+            .collect();
+        let tune = AbcTune::from_events(events.clone(), TimeSignature::common_time(), Key::C_MAJOR);
+        let parsed: AbcTune = tune.to_string().parse().unwrap();
+        assert_eq!(parsed.events().cloned().collect::<Vec<_>>(), events);
+    }
+
+    #[test]
+    fn from_score_warns_on_extra_voices() {
+        let quarter = Subdivision::QUARTER_NOTE.to_duration();
+        let voice =
+            |pitch: &str| Voice::from(vec![NoteEvent::single(pitch.parse().unwrap(), quarter)]);
+        let part = Part::new("Piano", Instrument::new("Piano", 0), Key::C_MAJOR).with_staff(
+            Staff::new(Clef::Treble)
+                .with_voice(voice("C5"))
+                .with_voice(voice("E4")),
+        );
+        let score = Score::new(TimeSignature::common_time(), Tempo::default()).with_part(part);
+        let AbcExport { tune, warnings } = AbcTune::from_score(&score);
+        assert_eq!(tune.events().count(), 1);
+        assert_eq!(
+            warnings,
+            [AbcWarning::Voice {
+                part: "Piano".to_string(),
+                voice: 2
+            }]
+        );
+    }
+}
@@ -0,0 +1,9 @@
+mod bar;
+mod nashville;
+mod progression;
+mod section;
+
+pub use bar::{ChartBar, ChartBarline, ChartChord, ChartItem};
+pub use nashville::NashvilleNumber;
+pub use progression::{ChordEvent, Progression};
+pub use section::ChartSection;
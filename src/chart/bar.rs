@@ -0,0 +1,193 @@
+use crate::chart::NashvilleNumber;
+use crate::chord::ChordSymbol;
+use crate::ParseError;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum ChartBarline {
+    #[default]
+    Single,
+    Double,
+    Final,
+}
+
+impl ChartBarline {
+    pub const fn symbol(&self) -> &'static str {
+        match self {
+            ChartBarline::Single => "|",
+            ChartBarline::Double => "||",
+            ChartBarline::Final => "|]",
+        }
+    }
+}
+
+impl fmt::Display for ChartBarline {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ChartItem {
+    Chord(ChordSymbol),
+    Number(NashvilleNumber),
+    NoChord,
+    Simile,
+}
+
+impl ChartItem {
+    pub const fn chord(&self) -> Option<&ChordSymbol> {
+        match self {
+            ChartItem::Chord(chord) => Some(chord),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ChartItem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChartItem::Chord(chord) if f.alternate() => write!(f, "{chord:#}"),
+            ChartItem::Chord(chord) => write!(f, "{chord}"),
+            ChartItem::Number(number) if f.alternate() => write!(f, "{number:#}"),
+            ChartItem::Number(number) => write!(f, "{number}"),
+            ChartItem::NoChord => f.write_str("N.C."),
+            ChartItem::Simile => f.write_str("%"),
+        }
+    }
+}
+
+impl FromStr for ChartItem {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "%" => Ok(ChartItem::Simile),
+            "N.C." | "N.C" | "NC" => Ok(ChartItem::NoChord),
+            s if s
+                .trim_start_matches(['#', 'b', '\u{266f}', '\u{266d}'])
+                .starts_with(|c: char| c.is_ascii_digit()) =>
+            {
+                NashvilleNumber::from_str(s).map(ChartItem::Number)
+            }
+            s => ChordSymbol::from_str(s).map(ChartItem::Chord),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ChartChord {
+    pub item: ChartItem,
+    pub beats: Option<usize>,
+    pub diamond: bool,
+    pub push: bool,
+}
+
+impl ChartChord {
+    pub const fn new(item: ChartItem) -> Self {
+        ChartChord {
+            item,
+            beats: None,
+            diamond: false,
+            push: false,
+        }
+    }
+    pub const fn with_beats(mut self, beats: usize) -> Self {
+        self.beats = Some(beats);
+        self
+    }
+    pub const fn with_diamond(mut self) -> Self {
+        self.diamond = true;
+        self
+    }
+    pub const fn with_push(mut self) -> Self {
+        self.push = true;
+        self
+    }
+}
+
+impl fmt::Display for ChartChord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.push {
+            f.write_str("^")?;
+        }
+        if self.diamond {
+            f.write_str("<")?;
+        }
+        match f.alternate() {
+            true => write!(f, "{:#}", self.item)?,
+            false => write!(f, "{}", self.item)?,
+        }
+        if self.diamond {
+            f.write_str(">")?;
+        }
+        for _ in 1..self.beats.unwrap_or(1) {
+            f.write_str(" /")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ChartChord {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let item = s.strip_prefix('^').unwrap_or(s);
+        let push = item.len() < s.len();
+        let (item, diamond) = match item.strip_prefix('<') {
+            Some(item) => match item.strip_suffix('>') {
+                Some(item) => (item, true),
+                None => {
+                    return Err(ParseError::UnexpectedEnd {
+                        at: s.len(),
+                        expected: &[">"],
+                    })
+                }
+            },
+            None => (item, false),
+        };
+        let at = s.len() - item.len() - usize::from(diamond);
+        Ok(ChartChord {
+            item: ChartItem::from_str(item).map_err(|error| error.offset(at))?,
+            beats: None,
+            diamond,
+            push,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ChartBar {
+    pub chords: Vec<ChartChord>,
+    pub ending: Vec<usize>,
+    pub repeat_start: bool,
+    pub repeat_end: bool,
+    pub barline: ChartBarline,
+}
+
+impl ChartBar {
+    pub fn new(chords: impl Into<Vec<ChartChord>>) -> Self {
+        ChartBar {
+            chords: chords.into(),
+            ..ChartBar::default()
+        }
+    }
+    pub fn with_ending(mut self, ending: impl Into<Vec<usize>>) -> Self {
+        self.ending = ending.into();
+        self
+    }
+    pub const fn with_repeat_start(mut self) -> Self {
+        self.repeat_start = true;
+        self
+    }
+    pub const fn with_repeat_end(mut self) -> Self {
+        self.repeat_end = true;
+        self
+    }
+    pub const fn with_barline(mut self, barline: ChartBarline) -> Self {
+        self.barline = barline;
+        self
+    }
+    pub fn is_simile(&self) -> bool {
+        matches!(self.chords.as_slice(), [chord] if chord.item == ChartItem::Simile)
+    }
+}
@@ -0,0 +1,189 @@
+use crate::chord::{ChordDegree, ChordQuality, ChordSymbol};
+use crate::note::{Accidental, Diatonic, Notation, PitchClass};
+use crate::scale::{Key, Mode};
+use crate::ParseError;
+use std::fmt;
+use std::str::FromStr;
+
+const ACCIDENTALS: [(&str, isize); 4] = [("\u{266f}", 1), ("\u{266d}", -1), ("#", 1), ("b", -1)];
+
+fn split_number(s: &str) -> Result<((usize, Accidental), &str), ParseError> {
+    let mut rest = s;
+    let mut shift = 0;
+    while let Some((token, step)) = ACCIDENTALS
+        .into_iter()
+        .find(|(token, _)| rest.starts_with(token))
+    {
+        shift += step;
+        rest = &rest[token.len()..];
+    }
+    let at = s.len() - rest.len();
+    match rest.as_bytes().first() {
+        Some(digit @ b'1'..=b'7') => Ok((
+            ((digit - b'0') as usize, Accidental::new(shift)),
+            &rest[1..],
+        )),
+        Some(b'0'..=b'9') => Err(ParseError::out_of_range(s, at..at + 1, 1, 7)),
+        _ => Err(ParseError::char_at(s, at, &["scale degree"])),
+    }
+}
+
+fn number(key: &Key, pitch_class: PitchClass) -> (usize, Accidental) {
+    let steps =
+        (pitch_class.base.index() - key.tonic.base.index()).rem_euclid(Diatonic::NOTES as isize);
+    let expected = key.parallel(Mode::MAJOR).degree(steps);
+    let shift = pitch_class.accidental.pitch_shift() - expected.accidental.pitch_shift();
+    (steps as usize + 1, Accidental::new(shift))
+}
+
+fn pitch_class(key: &Key, (degree, accidental): (usize, Accidental)) -> PitchClass {
+    let expected = key.parallel(Mode::MAJOR).degree(degree as isize - 1);
+    PitchClass::new(
+        expected.base,
+        Accidental::new(expected.accidental.pitch_shift() + accidental.pitch_shift()),
+    )
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct NashvilleNumber {
+    pub degree: usize,
+    pub accidental: Accidental,
+    pub quality: ChordQuality,
+    pub degrees: Vec<ChordDegree>,
+    pub bass: Option<(usize, Accidental)>,
+}
+
+impl NashvilleNumber {
+    pub const fn new(degree: usize, quality: ChordQuality) -> Self {
+        NashvilleNumber {
+            degree,
+            accidental: Accidental::NATURAL,
+            quality,
+            degrees: Vec::new(),
+            bass: None,
+        }
+    }
+    pub const fn with_accidental(mut self, accidental: Accidental) -> Self {
+        self.accidental = accidental;
+        self
+    }
+    pub fn with_degree(mut self, degree: ChordDegree) -> Self {
+        self.degrees.push(degree);
+        self
+    }
+    pub const fn with_bass(mut self, degree: usize, accidental: Accidental) -> Self {
+        self.bass = Some((degree, accidental));
+        self
+    }
+    pub fn from_chord(chord: &ChordSymbol, key: &Key) -> Self {
+        let (degree, accidental) = number(key, chord.root);
+        NashvilleNumber {
+            degree,
+            accidental,
+            quality: chord.quality,
+            degrees: chord.degrees.clone(),
+            bass: chord.bass.map(|bass| number(key, bass)),
+        }
+    }
+    pub fn to_chord(&self, key: &Key) -> ChordSymbol {
+        ChordSymbol {
+            root: pitch_class(key, (self.degree, self.accidental)),
+            quality: self.quality,
+            degrees: self.degrees.clone(),
+            bass: self.bass.map(|bass| pitch_class(key, bass)),
+        }
+    }
+}
+
+impl fmt::Display for NashvilleNumber {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let notation = Notation::of(f);
+        let number = |(degree, accidental): (usize, Accidental)| match accidental {
+            Accidental::NATURAL => degree.to_string(),
+            accidental => format!("{}{degree}", notation.accidental(accidental)),
+        };
+        let chord = ChordSymbol {
+            root: PitchClass::new(Diatonic::C, Accidental::NATURAL),
+            quality: self.quality,
+            degrees: self.degrees.clone(),
+            bass: None,
+        };
+        let chord = if f.alternate() {
+            format!("{chord:#}")
+        } else {
+            chord.to_string()
+        };
+        write!(
+            f,
+            "{}{}",
+            number((self.degree, self.accidental)),
+            &chord[1..]
+        )?;
+        if let Some(bass) = self.bass {
+            write!(f, "/{}", number(bass))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for NashvilleNumber {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let ((degree, accidental), rest) = split_number(s)?;
+        let at = s.len() - rest.len();
+        let (suffix, bass) = match rest
+            .rsplit_once('/')
+            .map(|(suffix, bass)| (suffix, split_number(bass)))
+        {
+            Some((suffix, Ok((bass, "")))) => (suffix, Some(bass)),
+            _ => (rest, None),
+        };
+        let chord =
+            ChordSymbol::from_str(&format!("C{suffix}")).map_err(|error| error.offset(at - 1))?;
+        Ok(NashvilleNumber {
+            degree,
+            accidental,
+            quality: chord.quality,
+            degrees: chord.degrees,
+            bass,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(s: &str) -> Key {
+        Key::major(s.parse().unwrap())
+    }
+
+    #[test]
+    fn round_trip() {
+        for s in ["1", "4", "5/7", "b7", "2m", "#4m7b5", "5sus4/1"] {
+            let number = NashvilleNumber::from_str(s).unwrap();
+            assert_eq!(NashvilleNumber::from_str(&number.to_string()), Ok(number));
+        }
+    }
+
+    #[test]
+    fn chord_round_trip() {
+        let key = key("G");
+        for s in ["D", "Em", "F", "C/E", "Bb7"] {
+            let chord = ChordSymbol::from_str(s).unwrap();
+            let number = NashvilleNumber::from_chord(&chord, &key);
+            assert_eq!(number.to_chord(&key), chord);
+        }
+        let number = NashvilleNumber::from_chord(&"D".parse().unwrap(), &key);
+        assert_eq!(number, NashvilleNumber::new(5, ChordQuality::Major));
+    }
+
+    #[test]
+    fn rejects_invalid_numbers() {
+        assert!(NashvilleNumber::from_str("").is_err());
+        assert!(NashvilleNumber::from_str("8").is_err());
+        assert!(NashvilleNumber::from_str("0m").is_err());
+        assert!(NashvilleNumber::from_str("bm").is_err());
+        assert!(NashvilleNumber::from_str("4q").is_err());
+    }
+}
@@ -0,0 +1,519 @@
+use crate::chart::{ChartBar, ChartBarline, ChartChord, ChartItem, ChartSection, NashvilleNumber};
+use crate::chord::ChordSymbol;
+use crate::note::{Duration, Subdivision, Transpose};
+use crate::ratio::Ratio;
+use crate::scale::Key;
+use crate::score::TimeSignature;
+use crate::ParseError;
+use std::fmt;
+use std::str::FromStr;
+
+const BARS_PER_LINE: usize = 4;
+
+fn tokens(line: &str) -> Vec<(usize, &str)> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    let mut chars = line.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '|' | ':' => {
+                if let Some(start) = start.take() {
+                    tokens.push((start, &line[start..i]));
+                }
+                let mut end = i + 1;
+                while let Some(&(j, c)) = chars.peek() {
+                    match c {
+                        '|' | ':' => end = j + 1,
+                        ']' if line[..j].ends_with('|') => end = j + 1,
+                        _ => break,
+                    }
+                    chars.next();
+                }
+                tokens.push((i, &line[i..end]));
+            }
+            c if c.is_whitespace() => {
+                if let Some(start) = start.take() {
+                    tokens.push((start, &line[start..i]));
+                }
+            }
+            _ => {
+                start.get_or_insert(i);
+            }
+        }
+    }
+    if let Some(start) = start {
+        tokens.push((start, &line[start..]));
+    }
+    tokens
+}
+
+fn ending(token: &str) -> Option<Vec<usize>> {
+    let numbers = token.strip_suffix('.')?;
+    numbers
+        .split([',', '.'])
+        .map(|number| number.parse().ok().filter(|&number| number > 0))
+        .collect()
+}
+
+fn barline(previous: Option<&ChartBar>, next: Option<&ChartBar>) -> String {
+    let mut barline = String::new();
+    if previous.is_some_and(|bar| bar.repeat_end) {
+        barline.push(':');
+    }
+    barline.push_str(previous.map_or("|", |bar| bar.barline.symbol()));
+    if let Some(next) = next {
+        if next.repeat_start {
+            barline.push(':');
+        }
+        if !next.ending.is_empty() {
+            let numbers: Vec<String> = next.ending.iter().map(usize::to_string).collect();
+            barline.push_str(&format!("{}.", numbers.join(",")));
+        }
+    }
+    barline
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ChordEvent {
+    pub position: Duration,
+    pub duration: Duration,
+    pub chord: ChordSymbol,
+}
+
+impl ChordEvent {
+    pub const fn new(position: Duration, duration: Duration, chord: ChordSymbol) -> Self {
+        ChordEvent {
+            position,
+            duration,
+            chord,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Progression {
+    pub title: Option<String>,
+    pub key: Option<Key>,
+    pub time_signature: TimeSignature,
+    pub sections: Vec<ChartSection>,
+}
+
+impl Progression {
+    pub fn new(time_signature: TimeSignature) -> Self {
+        Progression {
+            title: None,
+            key: None,
+            time_signature,
+            sections: Vec::new(),
+        }
+    }
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+    pub const fn with_key(mut self, key: Key) -> Self {
+        self.key = Some(key);
+        self
+    }
+    pub fn with_section(mut self, section: ChartSection) -> Self {
+        self.sections.push(section);
+        self
+    }
+    pub fn bars(&self) -> impl Iterator<Item = &ChartBar> {
+        self.sections.iter().flat_map(|section| &section.bars)
+    }
+    pub fn chords(&self) -> impl Iterator<Item = &ChordSymbol> {
+        self.bars()
+            .flat_map(|bar| &bar.chords)
+            .filter_map(|chord| chord.item.chord())
+    }
+    pub fn infer_key(&self) -> Option<Key> {
+        self.key.or_else(|| {
+            self.chords().next().map(|chord| {
+                if chord.quality.is_minor() {
+                    Key::minor(chord.root)
+                } else {
+                    Key::major(chord.root)
+                }
+            })
+        })
+    }
+    pub fn unrolled(&self) -> Vec<&ChartBar> {
+        let bars: Vec<&ChartBar> = self.bars().collect();
+        let mut order = Vec::new();
+        let mut start = 0;
+        let mut pass = 1;
+        let mut i = 0;
+        while i < bars.len() {
+            let bar = bars[i];
+            if bar.repeat_start && i != start {
+                start = i;
+                pass = 1;
+            }
+            if !bar.ending.is_empty() && !bar.ending.contains(&pass) {
+                i += 1;
+                continue;
+            }
+            order.push(bar);
+            if bar.repeat_end && (pass == 1 || !bar.ending.is_empty()) {
+                pass += 1;
+                i = start;
+                continue;
+            }
+            if bar.repeat_end || !bar.ending.is_empty() {
+                start = i + 1;
+                pass = 1;
+            }
+            i += 1;
+        }
+        order
+    }
+    fn slots(&self) -> Vec<(Duration, Duration, Option<ChordSymbol>)> {
+        let length = self.time_signature.bar_duration();
+        let beat = Duration::from(self.time_signature.unit());
+        let anticipation = Duration::from(Subdivision::X8TH_NOTE);
+        let key = self.infer_key().unwrap_or(Key::C_MAJOR);
+        let mut slots: Vec<(Duration, Duration, Option<ChordSymbol>)> = Vec::new();
+        let mut position = Duration::ZERO;
+        let mut previous: Vec<(Option<ChordSymbol>, Option<usize>, bool)> = Vec::new();
+        let mut last = None;
+        for bar in self.unrolled() {
+            if !bar.is_simile() {
+                previous = bar
+                    .chords
+                    .iter()
+                    .map(|chord| {
+                        let symbol = match &chord.item {
+                            ChartItem::Chord(symbol) => Some(symbol.clone()),
+                            ChartItem::Number(number) => Some(number.to_chord(&key)),
+                            ChartItem::NoChord => None,
+                            ChartItem::Simile => last.clone(),
+                        };
+                        last.clone_from(&symbol);
+                        (symbol, chord.beats, chord.push)
+                    })
+                    .collect();
+            }
+            let even = previous.iter().any(|(_, beats, _)| beats.is_none());
+            let share = Ratio::from(length).mul(Ratio::new(1, previous.len() as u64));
+            let mut room = length;
+            let mut durations: Vec<Duration> = previous
+                .iter()
+                .map(|&(_, beats, _)| {
+                    let duration = match (even, beats) {
+                        (false, Some(beats)) => beat.scale(beats),
+                        _ => share.to_duration(),
+                    }
+                    .min(room);
+                    room -= duration;
+                    duration
+                })
+                .collect();
+            if let Some(duration) = durations.last_mut() {
+                *duration += room;
+            }
+            for ((chord, _, push), duration) in previous.iter().zip(durations) {
+                let mut slot = (position, duration, chord.clone());
+                if let Some(before) = slots.last_mut().filter(|_| *push) {
+                    let shift = anticipation.min(before.1);
+                    before.1 -= shift;
+                    slot.0 -= shift;
+                    slot.1 += shift;
+                }
+                slots.push(slot);
+                position += duration;
+            }
+        }
+        slots.retain(|(_, duration, _)| !duration.is_zero());
+        slots
+    }
+    pub fn to_nashville(&self, key: &Key) -> Self {
+        let mut progression = self.clone();
+        for chord in progression
+            .sections
+            .iter_mut()
+            .flat_map(|section| &mut section.bars)
+            .flat_map(|bar| &mut bar.chords)
+        {
+            if let ChartItem::Chord(symbol) = &chord.item {
+                chord.item = ChartItem::Number(NashvilleNumber::from_chord(symbol, key));
+            }
+        }
+        progression.key = Some(*key);
+        progression
+    }
+    pub fn to_chords(&self, key: &Key) -> Self {
+        let mut progression = self.clone();
+        for chord in progression
+            .sections
+            .iter_mut()
+            .flat_map(|section| &mut section.bars)
+            .flat_map(|bar| &mut bar.chords)
+        {
+            if let ChartItem::Number(number) = &chord.item {
+                chord.item = ChartItem::Chord(number.to_chord(key));
+            }
+        }
+        progression.key = Some(*key);
+        progression
+    }
+    pub fn events(&self) -> Vec<ChordEvent> {
+        self.slots()
+            .into_iter()
+            .filter_map(|(position, duration, chord)| {
+                chord.map(|chord| ChordEvent::new(position, duration, chord))
+            })
+            .collect()
+    }
+    pub fn duration(&self) -> Duration {
+        self.slots()
+            .last()
+            .map_or(Duration::ZERO, |(position, duration, _)| {
+                *position + *duration
+            })
+    }
+}
+
+impl Transpose for Progression {
+    fn checked_transpose(self, semitones: isize) -> Option<Self> {
+        let Some(from) = self.infer_key() else {
+            return Some(self);
+        };
+        let key = from.checked_transpose(semitones)?;
+        let fifths = key.tonic.fifths() - from.tonic.fifths();
+        let mut sections = Vec::with_capacity(self.sections.len());
+        for section in self.sections {
+            let mut bars = Vec::with_capacity(section.bars.len());
+            for bar in section.bars {
+                let mut chords = Vec::with_capacity(bar.chords.len());
+                for chord in bar.chords {
+                    let item = match chord.item {
+                        ChartItem::Chord(symbol) => {
+                            ChartItem::Chord(symbol.transpose_fifths(fifths))
+                        }
+                        item => item,
+                    };
+                    chords.push(ChartChord { item, ..chord });
+                }
+                bars.push(ChartBar { chords, ..bar });
+            }
+            sections.push(ChartSection { bars, ..section });
+        }
+        Some(Progression {
+            key: self.key.map(|_| key),
+            sections,
+            ..self
+        })
+    }
+}
+
+impl fmt::Display for Progression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(title) = &self.title {
+            writeln!(f, "Title: {title}")?;
+        }
+        if let Some(key) = &self.key {
+            writeln!(f, "Key: {key:#}")?;
+        }
+        writeln!(f, "Time: {}", self.time_signature)?;
+        for section in &self.sections {
+            if let Some(label) = &section.label {
+                writeln!(f, "[{label}]")?;
+            }
+            for line in section.bars.chunks(BARS_PER_LINE) {
+                let mut previous = None;
+                for bar in line {
+                    write!(f, "{} ", barline(previous, Some(bar)))?;
+                    for chord in &bar.chords {
+                        write!(f, "{chord:#} ")?;
+                    }
+                    previous = Some(bar);
+                }
+                writeln!(f, "{}", barline(previous, None))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Progression {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut progression = Progression::new(TimeSignature::common_time());
+        let mut section = ChartSection::new();
+        let mut bar = ChartBar::default();
+        let mut slashed = false;
+        let mut at = 0;
+        for raw in s.split_inclusive('\n') {
+            let start = at;
+            at += raw.len();
+            let line = raw.trim_end_matches(['\r', '\n']);
+            let trimmed = line.trim();
+            let offset = start + line.len() - line.trim_start().len();
+            if let Some((name, value)) = trimmed.split_once(':') {
+                let value_at = offset + name.len() + 1;
+                let value_at = value_at + value.len() - value.trim_start().len();
+                match name.trim().to_lowercase().as_str() {
+                    "title" => {
+                        progression.title = Some(value.trim().to_string());
+                        continue;
+                    }
+                    "key" => {
+                        let key = Key::from_str(value.trim()).map_err(|e| e.offset(value_at))?;
+                        progression.key = Some(key);
+                        continue;
+                    }
+                    "time" => {
+                        progression.time_signature = TimeSignature::from_str(value.trim())
+                            .map_err(|e| e.offset(value_at))?;
+                        continue;
+                    }
+                    _ => {}
+                }
+            }
+            if let Some(label) = trimmed
+                .strip_prefix('[')
+                .and_then(|label| label.strip_suffix(']'))
+            {
+                if section != ChartSection::new() {
+                    progression.sections.push(section);
+                }
+                section = ChartSection::new().with_label(label.trim());
+                continue;
+            }
+            for (i, token) in tokens(line) {
+                let at = start + i;
+                if token.starts_with(['|', ':']) {
+                    if !bar.chords.is_empty() {
+                        if !slashed {
+                            bar.chords.iter_mut().for_each(|chord| chord.beats = None);
+                        }
+                        section.bars.push(std::mem::take(&mut bar));
+                        slashed = false;
+                    }
+                    if !token.contains('|') && token != "::" {
+                        return Err(ParseError::unexpected(s, at..at + token.len(), &["|"]));
+                    }
+                    let previous = section.bars.last_mut().or_else(|| {
+                        progression
+                            .sections
+                            .iter_mut()
+                            .rev()
+                            .find_map(|section| section.bars.last_mut())
+                    });
+                    if let Some(previous) = previous {
+                        if token.starts_with(':') {
+                            previous.repeat_end = true;
+                        }
+                        if token.ends_with(']') {
+                            previous.barline = ChartBarline::Final;
+                        } else if token.matches('|').count() > 1 {
+                            previous.barline = ChartBarline::Double;
+                        }
+                    }
+                    if token.len() > 1 && token.ends_with(':') {
+                        bar.repeat_start = true;
+                    }
+                    continue;
+                }
+                if let Some(numbers) = ending(token) {
+                    if !bar.chords.is_empty() {
+                        return Err(ParseError::unexpected(
+                            s,
+                            at..at + token.len(),
+                            &["chord", "|"],
+                        ));
+                    }
+                    bar.ending = numbers;
+                    continue;
+                }
+                if token == "/" || token == "." {
+                    let Some(chord) = bar.chords.last_mut() else {
+                        return Err(ParseError::unexpected(s, at..at + token.len(), &["chord"]));
+                    };
+                    chord.beats = chord.beats.map(|beats| beats + 1);
+                    slashed = true;
+                    let beats: usize = bar.chords.iter().filter_map(|chord| chord.beats).sum();
+                    if beats > progression.time_signature.numerator() {
+                        return Err(ParseError::unexpected(s, at..at + token.len(), &["|"]));
+                    }
+                    continue;
+                }
+                let chord = ChartChord::from_str(token).map_err(|error| error.offset(at))?;
+                bar.chords.push(chord.with_beats(1));
+            }
+            if !bar.chords.is_empty() {
+                if !slashed {
+                    bar.chords.iter_mut().for_each(|chord| chord.beats = None);
+                }
+                section.bars.push(std::mem::take(&mut bar));
+                slashed = false;
+            }
+        }
+        if bar != ChartBar::default() {
+            return Err(ParseError::UnexpectedEnd {
+                at: s.len(),
+                expected: &["chord"],
+            });
+        }
+        if section != ChartSection::new() {
+            progression.sections.push(section);
+        }
+        Ok(progression)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let text = "Title: Test\nKey: G\nTime: 4/4\n[Verse]\n|: G / C / | D | Em / / / :|\n";
+        let progression: Progression = text.parse().unwrap();
+        assert_eq!(progression.to_string(), text);
+        assert_eq!(progression.unrolled().len(), 6);
+    }
+
+    #[test]
+    fn numbers_without_key_use_inferred_key() {
+        let progression: Progression = "| 1 | 4 / 5 / |\n".parse().unwrap();
+        let chords: Vec<String> = progression
+            .events()
+            .iter()
+            .map(|event| event.chord.to_string())
+            .collect();
+        assert_eq!(chords, ["C", "F", "G"]);
+        assert_eq!(
+            progression.duration(),
+            Duration::new(2, Subdivision::WHOLE_NOTE)
+        );
+    }
+
+    #[test]
+    fn rejects_slashes_past_the_bar() {
+        let text = "| C / / / / / |\n";
+        assert_eq!(
+            text.parse::<Progression>(),
+            Err(ParseError::unexpected(text, 10..11, &["|"]))
+        );
+    }
+
+    #[test]
+    fn clamps_beats_to_the_bar() {
+        let chord = |text: &str, beats| ChartChord::from_str(text).unwrap().with_beats(beats);
+        let bar = ChartBar::new([chord("C", 6), chord("G", 2)]);
+        let progression = Progression::new(TimeSignature::common_time())
+            .with_section(ChartSection::new().with_bar(bar));
+        let durations: Vec<Duration> = progression
+            .events()
+            .iter()
+            .map(|event| event.duration)
+            .collect();
+        assert_eq!(durations, [Subdivision::WHOLE_NOTE.to_duration()]);
+        assert_eq!(
+            progression.duration(),
+            Subdivision::WHOLE_NOTE.to_duration()
+        );
+    }
+}
@@ -0,0 +1,24 @@
+use crate::chart::ChartBar;
+
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ChartSection {
+    pub label: Option<String>,
+    pub bars: Vec<ChartBar>,
+}
+
+impl ChartSection {
+    pub const fn new() -> Self {
+        ChartSection {
+            label: None,
+            bars: Vec::new(),
+        }
+    }
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+    pub fn with_bar(mut self, bar: ChartBar) -> Self {
+        self.bars.push(bar);
+        self
+    }
+}
@@ -0,0 +1,7 @@
+mod chord_degree;
+mod chord_quality;
+mod chord_symbol;
+
+pub use chord_degree::{ChordDegree, DegreeKind};
+pub use chord_quality::ChordQuality;
+pub use chord_symbol::ChordSymbol;
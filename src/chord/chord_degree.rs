@@ -0,0 +1,104 @@
+use crate::note::{Accidental, Notation};
+use crate::ParseError;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum DegreeKind {
+    #[default]
+    Add,
+    Alter,
+    Subtract,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ChordDegree {
+    pub value: usize,
+    pub accidental: Accidental,
+    pub kind: DegreeKind,
+}
+
+impl ChordDegree {
+    pub const fn new(value: usize, accidental: Accidental, kind: DegreeKind) -> Self {
+        ChordDegree {
+            value,
+            accidental,
+            kind,
+        }
+    }
+    pub const fn add(value: usize, accidental: Accidental) -> Self {
+        ChordDegree::new(value, accidental, DegreeKind::Add)
+    }
+    pub const fn alter(value: usize, accidental: Accidental) -> Self {
+        ChordDegree::new(value, accidental, DegreeKind::Alter)
+    }
+    pub const fn subtract(value: usize) -> Self {
+        ChordDegree::new(value, Accidental::NATURAL, DegreeKind::Subtract)
+    }
+    pub const fn fifths(&self) -> isize {
+        let natural = match self.value % 7 {
+            1 => 0,
+            2 => 2,
+            3 => 4,
+            4 => -1,
+            5 => 1,
+            6 => 3,
+            _ => 5,
+        };
+        natural + self.accidental.pitch_shift() * 7
+    }
+}
+
+impl fmt::Display for ChordDegree {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let accidental = match self.accidental {
+            Accidental::NATURAL => String::new(),
+            accidental => Notation::of(f).accidental(accidental),
+        };
+        match self.kind {
+            DegreeKind::Add => write!(f, "add{}{}", accidental, self.value),
+            DegreeKind::Alter => write!(f, "{}{}", accidental, self.value),
+            DegreeKind::Subtract => write!(f, "no{}", self.value),
+        }
+    }
+}
+
+impl FromStr for ChordDegree {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, rest) = match s {
+            _ if s.starts_with("add") => (DegreeKind::Add, &s[3..]),
+            _ if s.starts_with("omit") => (DegreeKind::Subtract, &s[4..]),
+            _ if s.starts_with("no") => (DegreeKind::Subtract, &s[2..]),
+            _ => (DegreeKind::Alter, s),
+        };
+        let digits = rest.trim_start_matches(|c: char| !c.is_ascii_digit());
+        let at = s.len() - digits.len();
+        let accidental = match &s[s.len() - rest.len()..at] {
+            "" => Accidental::NATURAL,
+            "+" => Accidental::SHARP,
+            "-" => Accidental::FLAT,
+            accidental => Accidental::from_str(accidental)
+                .map_err(|error| error.offset(s.len() - rest.len()))?,
+        };
+        if kind == DegreeKind::Alter && accidental == Accidental::NATURAL {
+            return Err(ParseError::at(s, 0, &["add", "no", "omit", "#", "b"]));
+        }
+        match usize::from_str(digits) {
+            Ok(value @ 1..) => Ok(ChordDegree::new(value, accidental, kind)),
+            _ => Err(ParseError::at(s, at, &["degree"])),
+        }
+    }
+}
+
+impl From<ChordDegree> for (usize, Accidental, DegreeKind) {
+    fn from(degree: ChordDegree) -> Self {
+        (degree.value, degree.accidental, degree.kind)
+    }
+}
+
+impl From<(usize, Accidental, DegreeKind)> for ChordDegree {
+    fn from((value, accidental, kind): (usize, Accidental, DegreeKind)) -> Self {
+        ChordDegree::new(value, accidental, kind)
+    }
+}
@@ -0,0 +1,257 @@
+use crate::ParseError;
+use std::fmt;
+use std::str::FromStr;
+
+const SYMBOLS: [(&str, ChordQuality); 61] = [
+    ("", ChordQuality::Major),
+    ("maj", ChordQuality::Major),
+    ("M", ChordQuality::Major),
+    ("m", ChordQuality::Minor),
+    ("mi", ChordQuality::Minor),
+    ("min", ChordQuality::Minor),
+    ("-", ChordQuality::Minor),
+    ("aug", ChordQuality::Augmented),
+    ("+", ChordQuality::Augmented),
+    ("dim", ChordQuality::Diminished),
+    ("°", ChordQuality::Diminished),
+    ("7", ChordQuality::Dominant),
+    ("maj7", ChordQuality::MajorSeventh),
+    ("Maj7", ChordQuality::MajorSeventh),
+    ("ma7", ChordQuality::MajorSeventh),
+    ("M7", ChordQuality::MajorSeventh),
+    ("Δ7", ChordQuality::MajorSeventh),
+    ("Δ", ChordQuality::MajorSeventh),
+    ("m7", ChordQuality::MinorSeventh),
+    ("mi7", ChordQuality::MinorSeventh),
+    ("min7", ChordQuality::MinorSeventh),
+    ("-7", ChordQuality::MinorSeventh),
+    ("dim7", ChordQuality::DiminishedSeventh),
+    ("°7", ChordQuality::DiminishedSeventh),
+    ("o7", ChordQuality::DiminishedSeventh),
+    ("aug7", ChordQuality::AugmentedSeventh),
+    ("+7", ChordQuality::AugmentedSeventh),
+    ("m7b5", ChordQuality::HalfDiminished),
+    ("m7♭5", ChordQuality::HalfDiminished),
+    ("min7b5", ChordQuality::HalfDiminished),
+    ("-7b5", ChordQuality::HalfDiminished),
+    ("ø7", ChordQuality::HalfDiminished),
+    ("ø", ChordQuality::HalfDiminished),
+    ("mmaj7", ChordQuality::MinorMajorSeventh),
+    ("mMaj7", ChordQuality::MinorMajorSeventh),
+    ("mM7", ChordQuality::MinorMajorSeventh),
+    ("m(maj7)", ChordQuality::MinorMajorSeventh),
+    ("minmaj7", ChordQuality::MinorMajorSeventh),
+    ("-maj7", ChordQuality::MinorMajorSeventh),
+    ("mΔ7", ChordQuality::MinorMajorSeventh),
+    ("6", ChordQuality::MajorSixth),
+    ("m6", ChordQuality::MinorSixth),
+    ("min6", ChordQuality::MinorSixth),
+    ("-6", ChordQuality::MinorSixth),
+    ("9", ChordQuality::DominantNinth),
+    ("maj9", ChordQuality::MajorNinth),
+    ("M9", ChordQuality::MajorNinth),
+    ("m9", ChordQuality::MinorNinth),
+    ("-9", ChordQuality::MinorNinth),
+    ("11", ChordQuality::DominantEleventh),
+    ("maj11", ChordQuality::MajorEleventh),
+    ("m11", ChordQuality::MinorEleventh),
+    ("-11", ChordQuality::MinorEleventh),
+    ("13", ChordQuality::DominantThirteenth),
+    ("maj13", ChordQuality::MajorThirteenth),
+    ("m13", ChordQuality::MinorThirteenth),
+    ("-13", ChordQuality::MinorThirteenth),
+    ("sus2", ChordQuality::SuspendedSecond),
+    ("sus4", ChordQuality::SuspendedFourth),
+    ("sus", ChordQuality::SuspendedFourth),
+    ("5", ChordQuality::Power),
+];
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum ChordQuality {
+    #[default]
+    Major,
+    Minor,
+    Augmented,
+    Diminished,
+    Dominant,
+    MajorSeventh,
+    MinorSeventh,
+    DiminishedSeventh,
+    AugmentedSeventh,
+    HalfDiminished,
+    MinorMajorSeventh,
+    MajorSixth,
+    MinorSixth,
+    DominantNinth,
+    MajorNinth,
+    MinorNinth,
+    DominantEleventh,
+    MajorEleventh,
+    MinorEleventh,
+    DominantThirteenth,
+    MajorThirteenth,
+    MinorThirteenth,
+    SuspendedSecond,
+    SuspendedFourth,
+    Power,
+}
+
+impl ChordQuality {
+    pub const ALL: [Self; 25] = [
+        ChordQuality::Major,
+        ChordQuality::Minor,
+        ChordQuality::Augmented,
+        ChordQuality::Diminished,
+        ChordQuality::Dominant,
+        ChordQuality::MajorSeventh,
+        ChordQuality::MinorSeventh,
+        ChordQuality::DiminishedSeventh,
+        ChordQuality::AugmentedSeventh,
+        ChordQuality::HalfDiminished,
+        ChordQuality::MinorMajorSeventh,
+        ChordQuality::MajorSixth,
+        ChordQuality::MinorSixth,
+        ChordQuality::DominantNinth,
+        ChordQuality::MajorNinth,
+        ChordQuality::MinorNinth,
+        ChordQuality::DominantEleventh,
+        ChordQuality::MajorEleventh,
+        ChordQuality::MinorEleventh,
+        ChordQuality::DominantThirteenth,
+        ChordQuality::MajorThirteenth,
+        ChordQuality::MinorThirteenth,
+        ChordQuality::SuspendedSecond,
+        ChordQuality::SuspendedFourth,
+        ChordQuality::Power,
+    ];
+    pub const fn name(&self) -> &'static str {
+        match self {
+            ChordQuality::Major => "major",
+            ChordQuality::Minor => "minor",
+            ChordQuality::Augmented => "augmented",
+            ChordQuality::Diminished => "diminished",
+            ChordQuality::Dominant => "dominant",
+            ChordQuality::MajorSeventh => "major seventh",
+            ChordQuality::MinorSeventh => "minor seventh",
+            ChordQuality::DiminishedSeventh => "diminished seventh",
+            ChordQuality::AugmentedSeventh => "augmented seventh",
+            ChordQuality::HalfDiminished => "half-diminished",
+            ChordQuality::MinorMajorSeventh => "minor-major seventh",
+            ChordQuality::MajorSixth => "major sixth",
+            ChordQuality::MinorSixth => "minor sixth",
+            ChordQuality::DominantNinth => "dominant ninth",
+            ChordQuality::MajorNinth => "major ninth",
+            ChordQuality::MinorNinth => "minor ninth",
+            ChordQuality::DominantEleventh => "dominant eleventh",
+            ChordQuality::MajorEleventh => "major eleventh",
+            ChordQuality::MinorEleventh => "minor eleventh",
+            ChordQuality::DominantThirteenth => "dominant thirteenth",
+            ChordQuality::MajorThirteenth => "major thirteenth",
+            ChordQuality::MinorThirteenth => "minor thirteenth",
+            ChordQuality::SuspendedSecond => "suspended second",
+            ChordQuality::SuspendedFourth => "suspended fourth",
+            ChordQuality::Power => "power",
+        }
+    }
+    pub const fn symbol(&self) -> &'static str {
+        match self {
+            ChordQuality::Major => "",
+            ChordQuality::Minor => "m",
+            ChordQuality::Augmented => "aug",
+            ChordQuality::Diminished => "dim",
+            ChordQuality::Dominant => "7",
+            ChordQuality::MajorSeventh => "maj7",
+            ChordQuality::MinorSeventh => "m7",
+            ChordQuality::DiminishedSeventh => "dim7",
+            ChordQuality::AugmentedSeventh => "aug7",
+            ChordQuality::HalfDiminished => "m7b5",
+            ChordQuality::MinorMajorSeventh => "mmaj7",
+            ChordQuality::MajorSixth => "6",
+            ChordQuality::MinorSixth => "m6",
+            ChordQuality::DominantNinth => "9",
+            ChordQuality::MajorNinth => "maj9",
+            ChordQuality::MinorNinth => "m9",
+            ChordQuality::DominantEleventh => "11",
+            ChordQuality::MajorEleventh => "maj11",
+            ChordQuality::MinorEleventh => "m11",
+            ChordQuality::DominantThirteenth => "13",
+            ChordQuality::MajorThirteenth => "maj13",
+            ChordQuality::MinorThirteenth => "m13",
+            ChordQuality::SuspendedSecond => "sus2",
+            ChordQuality::SuspendedFourth => "sus4",
+            ChordQuality::Power => "5",
+        }
+    }
+    pub const fn tones(&self) -> &'static [(usize, isize)] {
+        match self {
+            ChordQuality::Major => &[(1, 0), (3, 4), (5, 1)],
+            ChordQuality::Minor => &[(1, 0), (3, -3), (5, 1)],
+            ChordQuality::Augmented => &[(1, 0), (3, 4), (5, 8)],
+            ChordQuality::Diminished => &[(1, 0), (3, -3), (5, -6)],
+            ChordQuality::Dominant => &[(1, 0), (3, 4), (5, 1), (7, -2)],
+            ChordQuality::MajorSeventh => &[(1, 0), (3, 4), (5, 1), (7, 5)],
+            ChordQuality::MinorSeventh => &[(1, 0), (3, -3), (5, 1), (7, -2)],
+            ChordQuality::DiminishedSeventh => &[(1, 0), (3, -3), (5, -6), (7, -9)],
+            ChordQuality::AugmentedSeventh => &[(1, 0), (3, 4), (5, 8), (7, -2)],
+            ChordQuality::HalfDiminished => &[(1, 0), (3, -3), (5, -6), (7, -2)],
+            ChordQuality::MinorMajorSeventh => &[(1, 0), (3, -3), (5, 1), (7, 5)],
+            ChordQuality::MajorSixth => &[(1, 0), (3, 4), (5, 1), (6, 3)],
+            ChordQuality::MinorSixth => &[(1, 0), (3, -3), (5, 1), (6, 3)],
+            ChordQuality::DominantNinth => &[(1, 0), (3, 4), (5, 1), (7, -2), (9, 2)],
+            ChordQuality::MajorNinth => &[(1, 0), (3, 4), (5, 1), (7, 5), (9, 2)],
+            ChordQuality::MinorNinth => &[(1, 0), (3, -3), (5, 1), (7, -2), (9, 2)],
+            ChordQuality::DominantEleventh => &[(1, 0), (3, 4), (5, 1), (7, -2), (9, 2), (11, -1)],
+            ChordQuality::MajorEleventh => &[(1, 0), (3, 4), (5, 1), (7, 5), (9, 2), (11, -1)],
+            ChordQuality::MinorEleventh => &[(1, 0), (3, -3), (5, 1), (7, -2), (9, 2), (11, -1)],
+            ChordQuality::DominantThirteenth => {
+                &[(1, 0), (3, 4), (5, 1), (7, -2), (9, 2), (11, -1), (13, 3)]
+            }
+            ChordQuality::MajorThirteenth => {
+                &[(1, 0), (3, 4), (5, 1), (7, 5), (9, 2), (11, -1), (13, 3)]
+            }
+            ChordQuality::MinorThirteenth => {
+                &[(1, 0), (3, -3), (5, 1), (7, -2), (9, 2), (11, -1), (13, 3)]
+            }
+            ChordQuality::SuspendedSecond => &[(1, 0), (2, 2), (5, 1)],
+            ChordQuality::SuspendedFourth => &[(1, 0), (4, -1), (5, 1)],
+            ChordQuality::Power => &[(1, 0), (5, 1)],
+        }
+    }
+    pub fn has_degree(&self, degree: usize) -> bool {
+        self.tones().iter().any(|&(other, _)| other == degree)
+    }
+    pub fn is_minor(&self) -> bool {
+        self.tones().contains(&(3, -3))
+    }
+    pub fn split(s: &str) -> (ChordQuality, &str) {
+        SYMBOLS
+            .iter()
+            .filter(|(symbol, _)| s.starts_with(symbol))
+            .max_by_key(|(symbol, _)| symbol.len())
+            .map_or((ChordQuality::Major, s), |&(symbol, quality)| {
+                (quality, &s[symbol.len()..])
+            })
+    }
+}
+
+impl fmt::Display for ChordQuality {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
+
+impl FromStr for ChordQuality {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(quality) = ChordQuality::ALL
+            .into_iter()
+            .find(|quality| quality.name().eq_ignore_ascii_case(s))
+        {
+            return Ok(quality);
+        }
+        match ChordQuality::split(s) {
+            (quality, "") => Ok(quality),
+            (_, rest) => Err(ParseError::at(s, s.len() - rest.len(), &[])),
+        }
+    }
+}
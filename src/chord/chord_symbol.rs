@@ -0,0 +1,219 @@
+use crate::chord::{ChordDegree, ChordQuality, DegreeKind};
+use crate::note::{Accidental, Diatonic, Notation, PitchClass, Transpose};
+use crate::ParseError;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ChordSymbol {
+    pub root: PitchClass,
+    pub quality: ChordQuality,
+    pub degrees: Vec<ChordDegree>,
+    pub bass: Option<PitchClass>,
+}
+
+impl ChordSymbol {
+    pub const fn new(root: PitchClass, quality: ChordQuality) -> Self {
+        ChordSymbol {
+            root,
+            quality,
+            degrees: Vec::new(),
+            bass: None,
+        }
+    }
+    pub fn with_degree(mut self, degree: ChordDegree) -> Self {
+        self.degrees.push(degree);
+        self
+    }
+    pub const fn with_bass(mut self, bass: PitchClass) -> Self {
+        self.bass = Some(bass);
+        self
+    }
+    pub fn is_suspended(&self) -> bool {
+        self.degrees.contains(&ChordDegree::subtract(3))
+            && self.degrees.iter().any(|degree| {
+                degree.kind == DegreeKind::Add
+                    && degree.accidental == Accidental::NATURAL
+                    && matches!(degree.value, 2 | 4)
+            })
+    }
+    pub fn tones(&self) -> Vec<(usize, isize)> {
+        let mut tones = self.quality.tones().to_vec();
+        for degree in &self.degrees {
+            match degree.kind {
+                DegreeKind::Subtract => tones.retain(|&(value, _)| value != degree.value),
+                DegreeKind::Alter => {
+                    match tones.iter_mut().find(|(value, _)| *value == degree.value) {
+                        Some(tone) => tone.1 = degree.fifths(),
+                        None => tones.push((degree.value, degree.fifths())),
+                    }
+                }
+                DegreeKind::Add => tones.push((degree.value, degree.fifths())),
+            }
+        }
+        tones.sort_by_key(|&(value, _)| value);
+        tones
+    }
+    pub fn pitch_classes(&self) -> Vec<PitchClass> {
+        let root = self.root.fifths();
+        let mut pitch_classes: Vec<PitchClass> = self
+            .tones()
+            .into_iter()
+            .map(|(_, fifths)| PitchClass::from_fifths(root + fifths))
+            .collect();
+        if let Some(bass) = self.bass {
+            pitch_classes.retain(|pitch_class| *pitch_class != bass);
+            pitch_classes.insert(0, bass);
+        }
+        pitch_classes
+    }
+    pub fn transpose_fifths(self, fifths: isize) -> Self {
+        ChordSymbol {
+            root: PitchClass::from_fifths(self.root.fifths() + fifths).simplify(),
+            bass: self
+                .bass
+                .map(|bass| PitchClass::from_fifths(bass.fifths() + fifths).simplify()),
+            ..self
+        }
+    }
+    pub fn split_root(s: &str) -> Result<(PitchClass, &str), ParseError> {
+        let letter = s.get(..1).unwrap_or_default();
+        let base = Diatonic::from_str(&letter.to_uppercase())
+            .map_err(|_| ParseError::char_at(s, 0, &["A", "B", "C", "D", "E", "F", "G"]))?;
+        let mut rest = &s[1..];
+        let mut shift = 0;
+        while let Some((token, step)) = [
+            ("\u{1d12a}", 2),
+            ("\u{1d12b}", -2),
+            ("\u{266f}", 1),
+            ("\u{266d}", -1),
+            ("#", 1),
+            ("b", -1),
+        ]
+        .into_iter()
+        .find(|(token, _)| rest.starts_with(token))
+        {
+            shift += step;
+            rest = &rest[token.len()..];
+        }
+        Ok((PitchClass::new(base, Accidental::new(shift)), rest))
+    }
+}
+
+impl fmt::Display for ChordSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let notation = Notation::of(f);
+        write!(
+            f,
+            "{}{}",
+            notation.pitch_class(self.root),
+            self.quality.symbol()
+        )?;
+        let suspended = self.is_suspended();
+        for degree in &self.degrees {
+            match (degree.kind, degree.value) {
+                (DegreeKind::Subtract, 3) if suspended => {}
+                (DegreeKind::Add, value @ (2 | 4))
+                    if suspended && degree.accidental == Accidental::NATURAL =>
+                {
+                    write!(f, "sus{value}")?
+                }
+                (DegreeKind::Add, value)
+                    if degree.accidental != Accidental::NATURAL
+                        && !self.quality.has_degree(value) =>
+                {
+                    write!(f, "{}{}", notation.accidental(degree.accidental), value)?
+                }
+                _ if f.alternate() => write!(f, "{degree:#}")?,
+                _ => write!(f, "{degree}")?,
+            }
+        }
+        if let Some(bass) = self.bass {
+            write!(f, "/{}", notation.pitch_class(bass))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ChordSymbol {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (root, rest) = ChordSymbol::split_root(s)?;
+        let (quality, mut rest) = ChordQuality::split(rest);
+        let mut chord = ChordSymbol::new(root, quality);
+        while !rest.is_empty() {
+            let at = s.len() - rest.len();
+            if let Some(tail) = rest.strip_prefix(['(', ')', ',', ' ']) {
+                rest = tail;
+                continue;
+            }
+            if let Some(bass) = rest.strip_prefix('/') {
+                if matches!(quality, ChordQuality::MajorSixth | ChordQuality::MinorSixth)
+                    && bass.starts_with('9')
+                {
+                    chord.degrees.push(ChordDegree::add(9, Accidental::NATURAL));
+                    rest = &bass[1..];
+                    continue;
+                }
+                let (bass, tail) =
+                    ChordSymbol::split_root(bass).map_err(|error| error.offset(at + 1))?;
+                if !tail.is_empty() {
+                    return Err(ParseError::at(s, s.len() - tail.len(), &[]));
+                }
+                chord.bass = Some(bass);
+                break;
+            }
+            if let Some(tail) = rest.strip_prefix("sus") {
+                let (value, tail) = match tail.as_bytes().first() {
+                    Some(b'2') => (2, &tail[1..]),
+                    Some(b'4') => (4, &tail[1..]),
+                    _ => (4, tail),
+                };
+                chord.degrees.push(ChordDegree::subtract(3));
+                chord
+                    .degrees
+                    .push(ChordDegree::add(value, Accidental::NATURAL));
+                rest = tail;
+                continue;
+            }
+            let end = rest
+                .char_indices()
+                .skip_while(|(_, c)| !c.is_ascii_digit())
+                .find(|(_, c)| !c.is_ascii_digit())
+                .map_or(rest.len(), |(i, _)| i);
+            let mut degree =
+                ChordDegree::from_str(&rest[..end]).map_err(|error| error.offset(at))?;
+            if degree.kind == DegreeKind::Alter && !quality.has_degree(degree.value) {
+                degree.kind = DegreeKind::Add;
+            }
+            chord.degrees.push(degree);
+            rest = &rest[end..];
+        }
+        Ok(chord)
+    }
+}
+
+impl Transpose for ChordSymbol {
+    fn checked_transpose(self, semitones: isize) -> Option<Self> {
+        Some(ChordSymbol {
+            root: self.root.checked_transpose(semitones)?,
+            bass: match self.bass {
+                Some(bass) => Some(bass.checked_transpose(semitones)?),
+                None => None,
+            },
+            ..self
+        })
+    }
+}
+
+impl From<ChordSymbol> for (PitchClass, ChordQuality) {
+    fn from(chord: ChordSymbol) -> Self {
+        (chord.root, chord.quality)
+    }
+}
+
+impl From<(PitchClass, ChordQuality)> for ChordSymbol {
+    fn from((root, quality): (PitchClass, ChordQuality)) -> Self {
+        ChordSymbol::new(root, quality)
+    }
+}
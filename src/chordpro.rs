@@ -0,0 +1,5 @@
+mod line;
+mod song;
+
+pub use line::{ChordProChord, ChordProLine, ChordProSegment};
+pub use song::ChordProSong;
@@ -0,0 +1,112 @@
+use crate::chord::ChordSymbol;
+use std::fmt;
+
+const ALIASES: [(&str, &str); 14] = [
+    ("t", "title"),
+    ("st", "subtitle"),
+    ("c", "comment"),
+    ("ci", "comment_italic"),
+    ("cb", "comment_box"),
+    ("soc", "start_of_chorus"),
+    ("eoc", "end_of_chorus"),
+    ("sov", "start_of_verse"),
+    ("eov", "end_of_verse"),
+    ("sob", "start_of_bridge"),
+    ("eob", "end_of_bridge"),
+    ("sot", "start_of_tab"),
+    ("eot", "end_of_tab"),
+    ("ns", "new_song"),
+];
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ChordProChord {
+    Symbol(ChordSymbol),
+    Annotation(String),
+}
+
+impl fmt::Display for ChordProChord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChordProChord::Symbol(chord) if f.alternate() => write!(f, "{chord:#}"),
+            ChordProChord::Symbol(chord) => write!(f, "{chord}"),
+            ChordProChord::Annotation(text) => f.write_str(text),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ChordProSegment {
+    pub chord: Option<ChordProChord>,
+    pub lyrics: String,
+}
+
+impl ChordProSegment {
+    pub fn new(chord: Option<ChordProChord>, lyrics: impl Into<String>) -> Self {
+        ChordProSegment {
+            chord,
+            lyrics: lyrics.into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ChordProLine {
+    Directive { name: String, value: Option<String> },
+    Lyrics(Vec<ChordProSegment>),
+    Comment(String),
+}
+
+impl ChordProLine {
+    pub fn directive(name: &str, value: Option<&str>) -> Self {
+        let name = name.trim().to_lowercase();
+        let name = ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == name)
+            .map_or(name.clone(), |(_, canonical)| canonical.to_string());
+        ChordProLine::Directive {
+            name,
+            value: value
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string),
+        }
+    }
+    pub fn has_chords(&self) -> bool {
+        match self {
+            ChordProLine::Lyrics(segments) => {
+                segments.iter().any(|segment| segment.chord.is_some())
+            }
+            _ => false,
+        }
+    }
+    pub fn has_lyrics(&self) -> bool {
+        match self {
+            ChordProLine::Lyrics(segments) => segments
+                .iter()
+                .any(|segment| !segment.lyrics.trim().is_empty()),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for ChordProLine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChordProLine::Directive { name, value: None } => write!(f, "{{{name}}}"),
+            ChordProLine::Directive {
+                name,
+                value: Some(value),
+            } => write!(f, "{{{name}: {value}}}"),
+            ChordProLine::Lyrics(segments) => {
+                for segment in segments {
+                    if let Some(chord) = &segment.chord {
+                        write!(f, "[{chord:#}]")?;
+                    }
+                    f.write_str(&segment.lyrics)?;
+                }
+                Ok(())
+            }
+            ChordProLine::Comment(text) => write!(f, "#{text}"),
+        }
+    }
+}
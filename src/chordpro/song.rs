@@ -0,0 +1,347 @@
+use crate::chord::ChordSymbol;
+use crate::chordpro::{ChordProChord, ChordProLine, ChordProSegment};
+use crate::note::{PitchClass, Transpose};
+use crate::scale::Key;
+use crate::ParseError;
+use std::fmt;
+use std::str::FromStr;
+
+fn chord(text: &str) -> ChordProChord {
+    match text {
+        "N.C." | "N.C" | "NC" => ChordProChord::Annotation(text.to_string()),
+        text if text.starts_with('*') => ChordProChord::Annotation(text.to_string()),
+        text => ChordSymbol::from_str(text).map_or_else(
+            |_| ChordProChord::Annotation(text.to_string()),
+            ChordProChord::Symbol,
+        ),
+    }
+}
+
+fn respell(chord: ChordSymbol, key: &Key) -> ChordSymbol {
+    let scale = key.scale();
+    let respell = |pitch_class: PitchClass| match pitch_class.fifths() {
+        _ if scale.contains(&pitch_class) => pitch_class,
+        fifths @ (11 | 12) => PitchClass::from_fifths(fifths - 12),
+        fifths @ (-8 | -7) => PitchClass::from_fifths(fifths + 12),
+        _ => pitch_class,
+    };
+    ChordSymbol {
+        root: respell(chord.root),
+        bass: chord.bass.map(respell),
+        ..chord
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ChordProSong {
+    pub lines: Vec<ChordProLine>,
+}
+
+impl ChordProSong {
+    pub const fn new() -> Self {
+        ChordProSong { lines: Vec::new() }
+    }
+    pub fn with_line(mut self, line: ChordProLine) -> Self {
+        self.lines.push(line);
+        self
+    }
+    pub fn directive(&self, name: &str) -> Option<&str> {
+        let ChordProLine::Directive { name: wanted, .. } = ChordProLine::directive(name, None)
+        else {
+            return None;
+        };
+        self.lines.iter().find_map(|line| match line {
+            ChordProLine::Directive { name, value } if *name == wanted => {
+                Some(value.as_deref().unwrap_or_default())
+            }
+            _ => None,
+        })
+    }
+    pub fn title(&self) -> Option<&str> {
+        self.directive("title")
+    }
+    pub fn key(&self) -> Option<Key> {
+        self.directive("key")
+            .and_then(|key| Key::from_str(key).ok())
+    }
+    pub fn capo(&self) -> Option<usize> {
+        self.directive("capo")
+            .and_then(|capo| capo.trim().parse().ok())
+    }
+    pub fn chords(&self) -> impl Iterator<Item = &ChordSymbol> {
+        self.lines
+            .iter()
+            .filter_map(|line| match line {
+                ChordProLine::Lyrics(segments) => Some(segments),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|segment| match &segment.chord {
+                Some(ChordProChord::Symbol(chord)) => Some(chord),
+                _ => None,
+            })
+    }
+    pub fn infer_key(&self) -> Option<Key> {
+        self.key().or_else(|| {
+            self.chords().next().map(|chord| {
+                if chord.quality.is_minor() {
+                    Key::minor(chord.root)
+                } else {
+                    Key::major(chord.root)
+                }
+            })
+        })
+    }
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        let mut tab = false;
+        for line in &self.lines {
+            match line {
+                ChordProLine::Directive { name, value } => {
+                    let value = value.as_deref().unwrap_or_default();
+                    match name.as_str() {
+                        "title" | "subtitle" | "artist" | "comment" | "comment_italic"
+                        | "comment_box" => out.push_str(&format!("{value}\n")),
+                        "key" => out.push_str(&format!("Key: {value}\n")),
+                        "capo" => out.push_str(&format!("Capo: {value}\n")),
+                        "start_of_tab" => tab = true,
+                        "end_of_tab" => tab = false,
+                        "start_of_chorus" | "start_of_verse" | "start_of_bridge" => {
+                            let label = match value {
+                                "" => &name["start_of_".len()..],
+                                label => label,
+                            };
+                            let mut chars = label.chars();
+                            let first = chars.next().map(|c| c.to_uppercase().to_string());
+                            out.push_str(&format!(
+                                "{}{}:\n",
+                                first.unwrap_or_default(),
+                                chars.as_str()
+                            ));
+                        }
+                        _ => {}
+                    }
+                }
+                ChordProLine::Lyrics(segments) if tab => {
+                    for segment in segments {
+                        out.push_str(&segment.lyrics);
+                    }
+                    out.push('\n');
+                }
+                ChordProLine::Lyrics(segments) => {
+                    let mut chords = String::new();
+                    let mut lyrics = String::new();
+                    for segment in segments {
+                        let mut width = lyrics.chars().count();
+                        if let Some(chord) = &segment.chord {
+                            let chord = format!("{chord:#}");
+                            let chord = chord.strip_prefix('*').unwrap_or(&chord);
+                            let column = chords.chars().count();
+                            if column > width {
+                                lyrics.extend(std::iter::repeat_n(' ', column - width));
+                                width = column;
+                            }
+                            chords.extend(std::iter::repeat_n(' ', width - column));
+                            chords.push_str(chord);
+                            chords.push(' ');
+                        }
+                        lyrics.push_str(&segment.lyrics);
+                    }
+                    let chords = chords.trim_end();
+                    let lyrics = lyrics.trim_end();
+                    if !chords.is_empty() {
+                        out.push_str(&format!("{chords}\n"));
+                    }
+                    if !lyrics.is_empty() || chords.is_empty() {
+                        out.push_str(&format!("{lyrics}\n"));
+                    }
+                }
+                ChordProLine::Comment(_) => {}
+            }
+        }
+        out
+    }
+}
+
+impl Transpose for ChordProSong {
+    fn checked_transpose(self, semitones: isize) -> Option<Self> {
+        let Some(from) = self.infer_key() else {
+            return Some(self);
+        };
+        let mut key = from.checked_transpose(semitones)?;
+        let fifths = key.tonic.fifths() - from.tonic.fifths();
+        let mut lines = Vec::with_capacity(self.lines.len());
+        for line in self.lines {
+            lines.push(match line {
+                ChordProLine::Directive {
+                    name,
+                    value: Some(value),
+                } if name == "key" => {
+                    let value = match Key::from_str(&value) {
+                        Ok(own) => {
+                            let tonic = PitchClass::from_fifths(own.tonic.fifths() + fifths);
+                            key = Key::new(tonic.simplify(), own.mode);
+                            format!("{key:#}")
+                        }
+                        Err(_) => value,
+                    };
+                    ChordProLine::Directive {
+                        name,
+                        value: Some(value),
+                    }
+                }
+                ChordProLine::Lyrics(segments) => {
+                    let mut transposed = Vec::with_capacity(segments.len());
+                    for segment in segments {
+                        let chord = match segment.chord {
+                            Some(ChordProChord::Symbol(chord)) => Some(ChordProChord::Symbol(
+                                respell(chord.transpose_fifths(fifths), &key),
+                            )),
+                            chord => chord,
+                        };
+                        transposed.push(ChordProSegment { chord, ..segment });
+                    }
+                    ChordProLine::Lyrics(transposed)
+                }
+                line => line,
+            });
+        }
+        Some(ChordProSong { lines })
+    }
+}
+
+impl fmt::Display for ChordProSong {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for line in &self.lines {
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ChordProSong {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut song = ChordProSong::new();
+        let mut tab = false;
+        let mut at = 0;
+        for raw in s.split_inclusive('\n') {
+            let start = at;
+            at += raw.len();
+            let line = raw.trim_end_matches(['\r', '\n']);
+            let trimmed = line.trim();
+            if let Some(comment) = line.strip_prefix('#') {
+                song.lines.push(ChordProLine::Comment(comment.to_string()));
+                continue;
+            }
+            if let Some(inner) = trimmed
+                .strip_prefix('{')
+                .and_then(|inner| inner.strip_suffix('}'))
+            {
+                let split = inner
+                    .find(|c: char| c == ':' || c.is_whitespace())
+                    .unwrap_or(inner.len());
+                let (name, value) = inner.split_at(split);
+                let value = value.strip_prefix(':').unwrap_or(value);
+                let directive = ChordProLine::directive(name, Some(value));
+                if let ChordProLine::Directive { name, .. } = &directive {
+                    match name.as_str() {
+                        "start_of_tab" => tab = true,
+                        "end_of_tab" => tab = false,
+                        _ => {}
+                    }
+                }
+                song.lines.push(directive);
+                continue;
+            }
+            if trimmed.starts_with('{') {
+                return Err(ParseError::unexpected(
+                    s,
+                    start + line.len()..start + line.len(),
+                    &["}"],
+                ));
+            }
+            if tab {
+                song.lines
+                    .push(ChordProLine::Lyrics(vec![ChordProSegment::new(None, line)]));
+                continue;
+            }
+            let mut segments = Vec::new();
+            let mut rest = line;
+            let mut current = ChordProSegment::default();
+            while let Some(open) = rest.find('[') {
+                current.lyrics.push_str(&rest[..open]);
+                let Some(close) = rest[open..].find(']') else {
+                    return Err(ParseError::unexpected(
+                        s,
+                        start + line.len()..start + line.len(),
+                        &["]"],
+                    ));
+                };
+                let text = &rest[open + 1..open + close];
+                if current.chord.is_some() || !current.lyrics.is_empty() {
+                    segments.push(current);
+                }
+                current = ChordProSegment::new(Some(chord(text)), "");
+                rest = &rest[open + close + 1..];
+            }
+            current.lyrics.push_str(rest);
+            if current.chord.is_some() || !current.lyrics.is_empty() {
+                segments.push(current);
+            }
+            song.lines.push(ChordProLine::Lyrics(segments));
+        }
+        Ok(song)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let text = "{title: Song}\n{key: G}\n# note\n[G]Amazing [D/F#]grace\n{start_of_chorus}\n[Em7]How [C]sweet\n{end_of_chorus}\n";
+        let song: ChordProSong = text.parse().unwrap();
+        assert_eq!(song.to_string(), text);
+        assert_eq!(song.title(), Some("Song"));
+        assert_eq!(song.chords().count(), 4);
+    }
+
+    #[test]
+    fn unparseable_chords_are_annotations() {
+        let song: ChordProSong = "[C2]a [(G)]b [|]c [x]d\n".parse().unwrap();
+        let ChordProLine::Lyrics(segments) = &song.lines[0] else {
+            panic!("expected lyrics");
+        };
+        let chords: Vec<_> = segments
+            .iter()
+            .map(|segment| segment.chord.clone())
+            .collect();
+        assert_eq!(
+            chords,
+            ["C2", "(G)", "|", "x"].map(|text| Some(ChordProChord::Annotation(text.to_string())))
+        );
+        assert_eq!(song.chords().count(), 0);
+    }
+
+    #[test]
+    fn transposes_each_key_directive() {
+        let song: ChordProSong = "{key: G}\n[G]a\n{key: A}\n[A]b\n".parse().unwrap();
+        let transposed = song.transpose(2);
+        assert_eq!(transposed.to_string(), "{key: A}\n[A]a\n{key: B}\n[B]b\n");
+    }
+
+    #[test]
+    fn respells_double_and_non_diatonic_accidentals() {
+        let song: ChordProSong = "{key: C}\n[E]a [Db]b [Bb]c\n".parse().unwrap();
+        assert_eq!(
+            song.clone().transpose(1).to_string(),
+            "{key: Db}\n[F]a [D]b [B]c\n"
+        );
+        assert_eq!(
+            song.transpose(6).to_string(),
+            "{key: Gb}\n[Bb]a [G]b [E]c\n"
+        );
+    }
+}
@@ -0,0 +1,5 @@
+mod reader;
+mod writer;
+
+pub use reader::{Kern, KernWarning};
+pub use writer::KernWriter;
@@ -0,0 +1,781 @@
+use crate::note::{
+    Accidental, Diatonic, Duration, NoteEvent, Octave, Pitch, PitchClass, Tie, Tuplet,
+};
+use crate::ratio::Ratio;
+use crate::scale::{Key, Mode};
+use crate::score::{Clef, Instrument, Part, Score, Staff, Tempo, TimeSignature};
+use crate::ParseError;
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+const MAX_ZEROS: usize = 3;
+const MAX_DOTS: usize = 16;
+
+const CLEFS: [(&str, Clef); 9] = [
+    ("G2", Clef::Treble),
+    ("Gv2", Clef::TrebleOctaveDown),
+    ("C1", Clef::Soprano),
+    ("C2", Clef::MezzoSoprano),
+    ("C3", Clef::Alto),
+    ("C4", Clef::Tenor),
+    ("F3", Clef::Baritone),
+    ("F4", Clef::Bass),
+    ("X", Clef::Percussion),
+];
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum KernWarning {
+    Change { offset: usize, token: String },
+    Pickup { offset: usize, length: Duration },
+}
+
+impl fmt::Display for KernWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KernWarning::Change { offset, token } => {
+                write!(f, "change `{token}` at byte offset {offset} is not kept")
+            }
+            KernWarning::Pickup { offset, length } => write!(
+                f,
+                "pickup of {}/{} before byte offset {offset} is not kept as an anacrusis",
+                length.length,
+                1usize << length.unit.exponent()
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Kern {
+    pub score: Score,
+    pub references: Vec<(String, String)>,
+    pub warnings: Vec<KernWarning>,
+}
+
+impl Kern {
+    pub fn reference(&self, key: &str) -> Option<&str> {
+        self.references
+            .iter()
+            .find(|(other, _)| other == key)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+struct Note {
+    pitch: Option<Pitch>,
+    sounding: Option<Ratio>,
+    tie: Tie,
+    grace: bool,
+}
+
+fn note(s: &str, at: usize, token: &str) -> Result<Note, ParseError> {
+    let mut note = Note {
+        pitch: None,
+        sounding: None,
+        tie: Tie::NONE,
+        grace: false,
+    };
+    let mut rest = false;
+    let mut shift = 0;
+    let mut dots = 0;
+    let bytes = token.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        match bytes[i] {
+            b'0'..=b'9' if note.sounding.is_none() => {
+                while bytes.get(i).is_some_and(u8::is_ascii_digit) {
+                    i += 1;
+                }
+                let digits = &token[start..i];
+                let duration = || ParseError::unexpected(s, at + start..at + i, &["duration"]);
+                let mut base = match digits.trim_start_matches('0') {
+                    "" if digits.len() > MAX_ZEROS => {
+                        return Err(ParseError::out_of_range(
+                            s,
+                            at + start..at + i,
+                            1,
+                            MAX_ZEROS as isize,
+                        ));
+                    }
+                    "" => Ratio::new(1 << digits.len(), 1),
+                    reciprocal => reciprocal
+                        .parse()
+                        .ok()
+                        .and_then(|reciprocal| Ratio::checked_new(1, reciprocal))
+                        .ok_or_else(duration)?,
+                };
+                if bytes.get(i) == Some(&b'%') {
+                    i += 1;
+                    let numerator = i;
+                    while bytes.get(i).is_some_and(u8::is_ascii_digit) {
+                        i += 1;
+                    }
+                    match token[numerator..i].parse::<u64>() {
+                        Ok(multiplier) if multiplier > 0 => {
+                            base = Ratio::checked_new(multiplier, 1)
+                                .and_then(|multiplier| base.checked_mul(multiplier))
+                                .ok_or_else(|| {
+                                    ParseError::unexpected(s, at + start..at + i, &["duration"])
+                                })?;
+                        }
+                        _ => return Err(ParseError::char_at(s, at + numerator, &["numerator"])),
+                    }
+                }
+                note.sounding = Some(base);
+                continue;
+            }
+            b'.' => {
+                while bytes.get(i) == Some(&b'.') {
+                    i += 1;
+                }
+                dots += i - start;
+                if dots > MAX_DOTS {
+                    return Err(ParseError::out_of_range(
+                        s,
+                        at + start..at + i,
+                        0,
+                        MAX_DOTS as isize,
+                    ));
+                }
+                continue;
+            }
+            letter @ (b'a'..=b'g' | b'A'..=b'G') if note.pitch.is_none() => {
+                while bytes.get(i) == Some(&letter) {
+                    i += 1;
+                }
+                let count = (i - start) as isize;
+                let base = Diatonic::from_str(&token[start..start + 1].to_uppercase())
+                    .map_err(|error| error.offset(at + start))?;
+                let octave = match letter.is_ascii_lowercase() {
+                    true => 3 + count,
+                    false => 4 - count,
+                };
+                note.pitch = Some(Pitch::new(
+                    PitchClass::new(base, Accidental::NATURAL),
+                    Octave::new(octave),
+                ));
+                continue;
+            }
+            b'r' => rest = true,
+            b'#' => shift += 1,
+            b'-' => shift -= 1,
+            b'[' => note.tie.start = true,
+            b']' => note.tie.stop = true,
+            b'_' => note.tie = Tie::CONTINUE,
+            b'q' | b'Q' => note.grace = true,
+            _ => {}
+        }
+        i += 1;
+    }
+    if let Some(pitch) = &mut note.pitch {
+        pitch.pitch_class.accidental = Accidental::new(shift);
+    }
+    if note.grace {
+        return Ok(note);
+    }
+    if note.pitch.is_none() && !rest {
+        return Err(ParseError::unexpected(
+            s,
+            at..at + token.len(),
+            &["pitch", "rest"],
+        ));
+    }
+    let dots = Ratio::new((2 << dots) - 1, 1 << dots);
+    note.sounding = match note.sounding {
+        Some(sounding) => Some(sounding.checked_mul(dots).ok_or(ParseError::unexpected(
+            s,
+            at..at + token.len(),
+            &["duration"],
+        ))?),
+        None => None,
+    };
+    Ok(note)
+}
+
+fn event(s: &str, at: usize, token: &str) -> Result<Option<(NoteEvent, Ratio)>, ParseError> {
+    let mut pitches = Vec::new();
+    let mut tie = Tie::NONE;
+    let mut sounding = None;
+    let mut offset = at;
+    for subtoken in token.split(' ') {
+        let note = note(s, offset, subtoken)?;
+        offset += subtoken.len() + 1;
+        if note.grace {
+            return Ok(None);
+        }
+        sounding = sounding.or(note.sounding);
+        tie.start |= note.tie.start;
+        tie.stop |= note.tie.stop;
+        pitches.extend(note.pitch);
+    }
+    let sounding = sounding.ok_or(ParseError::unexpected(
+        s,
+        at..at + token.len(),
+        &["duration"],
+    ))?;
+    let odd = sounding.denominator >> sounding.denominator.trailing_zeros();
+    let value = match odd {
+        1 => sounding.to_duration(),
+        odd => {
+            let tuplet = Tuplet::common(odd as usize);
+            sounding
+                .checked_mul(Ratio::new(tuplet.actual as u64, tuplet.normal as u64))
+                .ok_or(ParseError::unexpected(
+                    s,
+                    at..at + token.len(),
+                    &["duration"],
+                ))?
+                .to_duration()
+        }
+    };
+    let event = match pitches.len() {
+        0 => NoteEvent::rest(value),
+        1 => NoteEvent::single(pitches[0], value).with_tie(tie),
+        _ => NoteEvent::chord(pitches, value).with_tie(tie),
+    };
+    Ok(Some((event, sounding)))
+}
+
+fn key(value: &str) -> Option<Key> {
+    let (tonic, mode) = value.split_once(':')?;
+    let mut chars = tonic.chars();
+    let letter = chars.next()?;
+    let base = Diatonic::from_str(&letter.to_uppercase().to_string()).ok()?;
+    let accidentals = chars.as_str();
+    let shift = match accidentals.chars().next() {
+        None => 0,
+        Some('#') => accidentals.len() as isize,
+        Some('-') => -(accidentals.len() as isize),
+        Some(_) => return None,
+    };
+    let mode = match (mode, letter.is_ascii_lowercase()) {
+        ("", true) => Mode::Aeolian,
+        ("", false) => Mode::Ionian,
+        (mode, _) => Mode::from_str(mode).ok()?,
+    };
+    Some(Key::new(
+        PitchClass::new(base, Accidental::new(shift)),
+        mode,
+    ))
+}
+
+struct Group {
+    tuplet: Tuplet,
+    events: Vec<(NoteEvent, Ratio)>,
+    length: Ratio,
+}
+
+struct VoiceState {
+    events: Vec<NoteEvent>,
+    end: Ratio,
+    group: Option<Group>,
+}
+
+impl VoiceState {
+    const fn new() -> Self {
+        VoiceState {
+            events: Vec::new(),
+            end: Ratio::ZERO,
+            group: None,
+        }
+    }
+    fn flush(&mut self) {
+        let Some(group) = self.group.take() else {
+            return;
+        };
+        let events: Vec<NoteEvent> = group
+            .events
+            .iter()
+            .map(|(event, _)| event.clone())
+            .collect();
+        match NoteEvent::tuplet(group.tuplet, events) {
+            Some(tuplet) => self.events.push(tuplet),
+            None => self.events.extend(
+                group
+                    .events
+                    .into_iter()
+                    .map(|(event, sounding)| event.with_value(sounding.to_duration())),
+            ),
+        }
+    }
+    fn pad(&mut self, position: Ratio) -> Option<()> {
+        if self.end < position {
+            self.flush();
+            let gap = position.checked_saturating_sub(self.end)?;
+            self.events.push(NoteEvent::rest(gap.to_duration()));
+            self.end = position;
+        }
+        Some(())
+    }
+    fn push(&mut self, event: NoteEvent, sounding: Ratio) -> Option<()> {
+        self.end = self.end.checked_add(sounding)?;
+        let odd = sounding.denominator >> sounding.denominator.trailing_zeros();
+        if odd == 1 {
+            self.flush();
+            self.events.push(event);
+            return Some(());
+        }
+        let tuplet = Tuplet::common(odd as usize);
+        if self
+            .group
+            .as_ref()
+            .is_some_and(|group| group.tuplet != tuplet)
+        {
+            self.flush();
+        }
+        let group = self.group.get_or_insert(Group {
+            tuplet,
+            events: Vec::new(),
+            length: Ratio::ZERO,
+        });
+        group.events.push((event, sounding));
+        group.length = group.length.checked_add(sounding)?;
+        if group.length.is_dyadic() {
+            self.flush();
+        }
+        Some(())
+    }
+}
+
+struct PartState {
+    name: Option<String>,
+    clef: Option<Clef>,
+    key: Option<Key>,
+    signature: Option<isize>,
+    voices: Vec<VoiceState>,
+}
+
+#[derive(Clone, Copy)]
+struct Spine {
+    part: Option<usize>,
+    voice: usize,
+}
+
+struct Reader<'a> {
+    text: &'a str,
+    spines: Vec<Spine>,
+    parts: Vec<PartState>,
+    now: Ratio,
+    barlines: usize,
+    meters: Vec<(Ratio, TimeSignature)>,
+    tempos: Vec<(Ratio, Tempo)>,
+    references: Vec<(String, String)>,
+    warnings: Vec<KernWarning>,
+}
+
+impl Reader<'_> {
+    fn exclusive(&mut self, token: &str) -> Spine {
+        match token {
+            "**kern" => {
+                self.parts.push(PartState {
+                    name: None,
+                    clef: None,
+                    key: None,
+                    signature: None,
+                    voices: vec![VoiceState::new()],
+                });
+                Spine {
+                    part: Some(self.parts.len() - 1),
+                    voice: 0,
+                }
+            }
+            _ => Spine {
+                part: None,
+                voice: 0,
+            },
+        }
+    }
+    fn interpretation(&mut self, spine: Spine, at: usize, token: &str) {
+        let value = &token[1..];
+        if let Some(meter) = value.strip_prefix('M') {
+            if let Ok(meter) = TimeSignature::from_str(meter) {
+                self.meters.retain(|(position, _)| *position != self.now);
+                self.meters.push((self.now, meter));
+            } else if let Some(Ok(bpm)) = meter.strip_prefix('M').map(f64::from_str) {
+                self.tempos.retain(|(position, _)| *position != self.now);
+                self.tempos.push((self.now, Tempo::quarter(bpm)));
+            }
+            return;
+        }
+        let Some(part) = spine.part.map(|part| &mut self.parts[part]) else {
+            return;
+        };
+        let kept = if let Some(clef) = value.strip_prefix("clef") {
+            match CLEFS.iter().find(|(name, _)| *name == clef) {
+                Some((_, clef)) => *part.clef.get_or_insert(*clef) == *clef,
+                None => true,
+            }
+        } else if let Some(signature) = value.strip_prefix("k[") {
+            let sharps = signature.matches('#').count() as isize;
+            let flats = signature.matches('-').count() as isize;
+            *part.signature.get_or_insert(sharps - flats) == sharps - flats
+        } else if let Some(name) = value.strip_prefix("I\"") {
+            part.name.get_or_insert_with(|| name.to_string()) == name
+        } else if let Some(key) = key(value) {
+            *part.key.get_or_insert(key) == key
+        } else {
+            true
+        };
+        if !kept {
+            self.warnings.push(KernWarning::Change {
+                offset: at,
+                token: token.to_string(),
+            });
+        }
+    }
+    fn manipulate(&mut self, tokens: &[(usize, &str)]) -> Result<(), ParseError> {
+        let mut spines = Vec::new();
+        let mut i = 0;
+        while i < tokens.len() {
+            let (at, token) = tokens[i];
+            let spine = self.spines[i];
+            if !token.starts_with('*') {
+                return Err(ParseError::char_at(self.text, at, &["interpretation"]));
+            }
+            match token {
+                "*^" => {
+                    spines.push(spine);
+                    let voice = match spine.part {
+                        Some(part) => {
+                            let used: Vec<usize> = spines
+                                .iter()
+                                .chain(&self.spines[i + 1..])
+                                .filter(|other| other.part == Some(part))
+                                .map(|other| other.voice)
+                                .collect();
+                            let voices = &mut self.parts[part].voices;
+                            match (0..voices.len()).find(|voice| !used.contains(voice)) {
+                                Some(voice) => voice,
+                                None => {
+                                    voices.push(VoiceState::new());
+                                    voices.len() - 1
+                                }
+                            }
+                        }
+                        None => 0,
+                    };
+                    spines.push(Spine { voice, ..spine });
+                }
+                "*v" => {
+                    spines.push(spine);
+                    while tokens.get(i + 1).is_some_and(|(_, token)| *token == "*v") {
+                        i += 1;
+                    }
+                }
+                "*-" => {}
+                "*+" => {
+                    spines.push(spine);
+                    spines.push(Spine {
+                        part: None,
+                        voice: 0,
+                    });
+                }
+                "*x" if tokens.get(i + 1).is_some_and(|(_, token)| *token == "*x") => {
+                    spines.push(self.spines[i + 1]);
+                    spines.push(spine);
+                    i += 1;
+                }
+                token if token.starts_with("**") => {
+                    let spine = self.exclusive(token);
+                    spines.push(spine);
+                }
+                token => {
+                    self.interpretation(spine, at, token);
+                    spines.push(spine);
+                }
+            }
+            i += 1;
+        }
+        self.spines = spines;
+        Ok(())
+    }
+    fn barline(&mut self, at: usize) {
+        self.barlines += 1;
+        if self.barlines > 1 || self.now == Ratio::ZERO {
+            return;
+        }
+        let bar = self.meters.first().map_or_else(
+            || TimeSignature::common_time().bar_duration(),
+            |(_, meter)| meter.bar_duration(),
+        );
+        if self.now < Ratio::from(bar) {
+            self.warnings.push(KernWarning::Pickup {
+                offset: at,
+                length: self.now.to_duration(),
+            });
+        }
+    }
+    fn data(&mut self, tokens: &[(usize, &str)]) -> Result<(), ParseError> {
+        for (i, &(at, token)) in tokens.iter().enumerate() {
+            let spine = self.spines[i];
+            let Some(part) = spine.part else {
+                continue;
+            };
+            if token == "." {
+                continue;
+            }
+            if let Some((event, sounding)) = event(self.text, at, token)? {
+                let voice = &mut self.parts[part].voices[spine.voice];
+                voice
+                    .pad(self.now)
+                    .and_then(|()| voice.push(event, sounding))
+                    .ok_or(ParseError::unexpected(
+                        self.text,
+                        at..at + token.len(),
+                        &["duration"],
+                    ))?;
+            }
+        }
+        let next = self
+            .spines
+            .iter()
+            .filter_map(|spine| Some(self.parts[spine.part?].voices[spine.voice].end))
+            .filter(|end| *end > self.now)
+            .min();
+        if let Some(next) = next {
+            self.now = next;
+        }
+        Ok(())
+    }
+    fn line(&mut self, at: usize, line: &str) -> Result<(), ParseError> {
+        if let Some(reference) = line.strip_prefix("!!!") {
+            if let Some((key, value)) = reference.split_once(':') {
+                self.references
+                    .push((key.trim().to_string(), value.trim().to_string()));
+            }
+            return Ok(());
+        }
+        if line.is_empty() || line.starts_with('!') {
+            return Ok(());
+        }
+        let mut tokens = Vec::new();
+        let mut offset = at;
+        for token in line.split('\t') {
+            tokens.push((offset, token));
+            offset += token.len() + 1;
+        }
+        if self.spines.is_empty() {
+            if !line.starts_with("**") {
+                return Err(ParseError::at(self.text, at, &["exclusive interpretation"]));
+            }
+            for &(at, token) in &tokens {
+                if !token.starts_with("**") {
+                    return Err(ParseError::char_at(
+                        self.text,
+                        at,
+                        &["exclusive interpretation"],
+                    ));
+                }
+                let spine = self.exclusive(token);
+                self.spines.push(spine);
+            }
+            return Ok(());
+        }
+        match tokens.len().cmp(&self.spines.len()) {
+            std::cmp::Ordering::Less => {
+                return Err(ParseError::unexpected(
+                    self.text,
+                    at + line.len()..at + line.len(),
+                    &["spine token"],
+                ))
+            }
+            std::cmp::Ordering::Greater => {
+                let (at, token) = tokens[self.spines.len()];
+                return Err(ParseError::unexpected(
+                    self.text,
+                    at - 1..at + token.len(),
+                    &["end of line"],
+                ));
+            }
+            std::cmp::Ordering::Equal => {}
+        }
+        match line.as_bytes()[0] {
+            b'*' => self.manipulate(&tokens),
+            b'=' => {
+                self.barline(at);
+                Ok(())
+            }
+            _ => self.data(&tokens),
+        }
+    }
+    fn finish(mut self) -> Kern {
+        let meters = std::mem::take(&mut self.meters);
+        let mut tempos = std::mem::take(&mut self.tempos).into_iter().peekable();
+        let initial = tempos
+            .next_if(|(position, _)| *position == Ratio::ZERO)
+            .map_or(Tempo::default(), |(_, tempo)| tempo);
+        let mut meters = meters.into_iter().peekable();
+        let meter = meters
+            .next_if(|(position, _)| *position == Ratio::ZERO)
+            .map_or_else(TimeSignature::common_time, |(_, meter)| meter);
+        let mut score = Score::new(meter, initial);
+        for (position, meter) in meters {
+            score.set_time_signature(position.to_duration(), meter);
+        }
+        for (position, tempo) in tempos {
+            score.tempo.set_tempo(position.to_duration(), tempo);
+        }
+        for (i, part) in self.parts.into_iter().enumerate().rev() {
+            let name = part.name.unwrap_or_else(|| format!("Spine {}", i + 1));
+            let key = part.key.unwrap_or_else(|| {
+                Key::from_fifths(part.signature.unwrap_or_default(), Mode::Ionian)
+            });
+            let mut staff = Staff::new(part.clef.unwrap_or(Clef::Treble));
+            for mut voice in part.voices {
+                voice.flush();
+                if !voice.events.is_empty() {
+                    staff = staff.with_voice(voice.events.into_iter().collect());
+                }
+            }
+            score = score.with_part(
+                Part::new(name.clone(), Instrument::new(name, 0), key).with_staff(staff),
+            );
+        }
+        score.title = self
+            .references
+            .iter()
+            .find(|(key, _)| key == "OTL")
+            .map(|(_, value)| value.clone());
+        score.composer = self
+            .references
+            .iter()
+            .find(|(key, _)| key == "COM")
+            .map(|(_, value)| value.clone());
+        Kern {
+            score,
+            references: self.references,
+            warnings: self.warnings,
+        }
+    }
+}
+
+impl Kern {
+    pub fn parse(text: &str) -> Result<Self, ParseError> {
+        let mut reader = Reader {
+            text,
+            spines: Vec::new(),
+            parts: Vec::new(),
+            now: Ratio::ZERO,
+            barlines: 0,
+            meters: Vec::new(),
+            tempos: Vec::new(),
+            references: Vec::new(),
+            warnings: Vec::new(),
+        };
+        let mut at = 0;
+        for line in text.split_inclusive('\n') {
+            reader.line(at, line.trim_end_matches(['\r', '\n']))?;
+            at += line.len();
+        }
+        Ok(reader.finish())
+    }
+    pub fn read<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        Kern::parse(&text).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+    }
+}
+
+impl FromStr for Kern {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Kern::parse(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kern::KernWriter;
+    use crate::note::Subdivision;
+
+    #[test]
+    fn round_trip() {
+        let quarter = Subdivision::QUARTER_NOTE.to_duration();
+        let events = vec![
+            NoteEvent::single("C4".parse().unwrap(), quarter),
+            NoteEvent::rest(quarter),
+            NoteEvent::chord(
+                ["E4".parse().unwrap(), "G4".parse().unwrap()],
+                Subdivision::HALF_NOTE.to_duration(),
+            ),
+        ];
+        let text =
+            KernWriter::from_events(events.clone(), TimeSignature::common_time(), Key::C_MAJOR)
+                .to_kern();
+        let kern = Kern::parse(&text).unwrap();
+        assert_eq!(kern.warnings, []);
+        assert_eq!(kern.score.parts[0].key, Key::C_MAJOR);
+        assert_eq!(
+            kern.score.voices().next().map(|voice| voice.events()),
+            Some(&events[..])
+        );
+    }
+
+    #[test]
+    fn warns_on_mid_piece_changes() {
+        let text = "**kern\n*M4/4\n*k[]\n*C:\n1c\n=1\n*k[f#]\n*G:\n1d\n==\n*-\n";
+        let kern = Kern::parse(text).unwrap();
+        assert_eq!(kern.score.parts[0].key, Key::C_MAJOR);
+        assert_eq!(
+            kern.warnings,
+            [
+                KernWarning::Change {
+                    offset: 28,
+                    token: "*k[f#]".to_string()
+                },
+                KernWarning::Change {
+                    offset: 35,
+                    token: "*G:".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn warns_on_pickup() {
+        let text = "**kern\n*M3/4\n4c\n=1\n2.d\n==\n*-\n";
+        let kern = Kern::parse(text).unwrap();
+        assert_eq!(
+            kern.score.time_signatures,
+            [(Duration::ZERO, TimeSignature::from_str("3/4").unwrap())]
+        );
+        assert_eq!(
+            kern.warnings,
+            [KernWarning::Pickup {
+                offset: 16,
+                length: Subdivision::QUARTER_NOTE.to_duration()
+            }]
+        );
+    }
+
+    #[test]
+    fn rejects_long_breves_and_dots() {
+        let zeros = format!("**kern\n{}c\n*-\n", "0".repeat(70));
+        assert_eq!(
+            Kern::parse(&zeros),
+            Err(ParseError::out_of_range(&zeros, 7..77, 1, 3))
+        );
+        let dots = format!("**kern\n4{}c\n*-\n", ".".repeat(70));
+        assert_eq!(
+            Kern::parse(&dots),
+            Err(ParseError::out_of_range(&dots, 8..78, 0, 16))
+        );
+    }
+
+    #[test]
+    fn rejects_overflowing_durations() {
+        let text = "**kern\n18446744073709551615c\n*-\n";
+        assert_eq!(
+            Kern::parse(text),
+            Err(ParseError::unexpected(text, 7..27, &["duration"]))
+        );
+        let text = "**kern\n4294967295c\n4294967291c\n*-\n";
+        assert_eq!(
+            Kern::parse(text),
+            Err(ParseError::unexpected(text, 19..30, &["duration"]))
+        );
+    }
+}
@@ -0,0 +1,300 @@
+use crate::note::{Duration, NoteEvent, Pitch, Tie};
+use crate::ratio::Ratio;
+use crate::scale::{Key, Mode};
+use crate::score::{Clef, Instrument, Part, Score, Staff, Tempo, TimeSignature};
+use std::io;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct KernWriter {
+    pub score: Score,
+}
+
+fn clef(clef: Clef) -> &'static str {
+    match clef {
+        Clef::Treble => "G2",
+        Clef::TrebleOctaveDown => "Gv2",
+        Clef::Soprano => "C1",
+        Clef::MezzoSoprano => "C2",
+        Clef::Alto => "C3",
+        Clef::Tenor => "C4",
+        Clef::Baritone => "F3",
+        Clef::Bass => "F4",
+        Clef::Percussion => "X",
+    }
+}
+
+fn accidentals(shift: isize) -> String {
+    match shift >= 0 {
+        true => "#".repeat(shift as usize),
+        false => "-".repeat(-shift as usize),
+    }
+}
+
+fn pitch(pitch: Pitch) -> String {
+    let letter = pitch.pitch_class.base.to_string();
+    let octave = pitch.octave.numeric();
+    let name = match octave >= 4 {
+        true => letter.to_lowercase().repeat((octave - 3) as usize),
+        false => letter.repeat((4 - octave) as usize),
+    };
+    format!(
+        "{name}{}",
+        accidentals(pitch.pitch_class.accidental.pitch_shift())
+    )
+}
+
+fn signature(key: &Key) -> String {
+    let pitch_classes: String = key
+        .signature()
+        .into_iter()
+        .map(|pitch_class| {
+            format!(
+                "{}{}",
+                pitch_class.base.to_string().to_lowercase(),
+                accidentals(pitch_class.accidental.pitch_shift())
+            )
+        })
+        .collect();
+    format!("*k[{pitch_classes}]")
+}
+
+fn key(key: &Key) -> String {
+    let tonic = format!(
+        "{}{}",
+        key.tonic.base,
+        accidentals(key.tonic.accidental.pitch_shift())
+    );
+    match key.mode {
+        Mode::Ionian => format!("*{tonic}:"),
+        Mode::Aeolian => format!("*{}:", tonic.to_lowercase()),
+        Mode::Dorian | Mode::Phrygian | Mode::Locrian => {
+            format!("*{}:{}", tonic.to_lowercase(), &key.mode.name()[..3])
+        }
+        mode => format!("*{tonic}:{}", &mode.name()[..3]),
+    }
+}
+
+fn tempo(tempo: &Tempo) -> String {
+    let bpm = tempo.bpm * tempo.beat.as_f64() * 4.0;
+    format!("*MM{}", bpm.round() as usize)
+}
+
+fn reciprocal(value: Ratio) -> String {
+    match (value.numerator, value.denominator) {
+        (1, denominator) => denominator.to_string(),
+        (numerator @ (2 | 4 | 8), 1) => "0".repeat(numerator.trailing_zeros() as usize),
+        (numerator, denominator) => format!("{denominator}%{numerator}"),
+    }
+}
+
+fn leaves(event: &NoteEvent, scale: Ratio, position: &mut Ratio, out: &mut Vec<(Ratio, String)>) {
+    let (pitches, tie) = match event {
+        NoteEvent::Tuplet { tuplet, events, .. } => {
+            let scale = scale.mul(Ratio::new(tuplet.normal as u64, tuplet.actual as u64));
+            for event in events {
+                leaves(event, scale, position, out);
+            }
+            return;
+        }
+        NoteEvent::Rest { .. } => (&[][..], Tie::NONE),
+        event => (event.pitches(), event.tie()),
+    };
+    let parts = event.value().to_dotted_parts();
+    for (i, &(unit, dots)) in parts.iter().enumerate() {
+        let duration = format!(
+            "{}{}",
+            reciprocal(Ratio::from(unit.to_duration()).mul(scale)),
+            ".".repeat(dots)
+        );
+        let start = tie.start || i + 1 < parts.len();
+        let stop = tie.stop || i > 0;
+        let (open, close) = match (start, stop) {
+            (true, true) => ("", "_"),
+            (true, false) => ("[", ""),
+            (false, true) => ("", "]"),
+            (false, false) => ("", ""),
+        };
+        let token = match pitches.is_empty() {
+            true => format!("{duration}r"),
+            false => pitches
+                .iter()
+                .map(|&note| format!("{open}{duration}{}{close}", pitch(note)))
+                .collect::<Vec<_>>()
+                .join(" "),
+        };
+        out.push((*position, token));
+        *position = position.add(Ratio::from(Duration::dotted(unit, dots)).mul(scale));
+    }
+}
+
+struct Spine<'a> {
+    part: &'a Part,
+    staff: &'a Staff,
+    voices: Vec<Vec<(Ratio, String)>>,
+}
+
+impl KernWriter {
+    pub const fn new(score: Score) -> Self {
+        KernWriter { score }
+    }
+    pub fn from_events(
+        events: impl IntoIterator<Item = NoteEvent>,
+        time_signature: TimeSignature,
+        key: Key,
+    ) -> Self {
+        let part = Part::new("Music", Instrument::new("Piano", 0), key)
+            .with_staff(Staff::new(Clef::Treble).with_voice(events.into_iter().collect()));
+        KernWriter::new(Score::new(time_signature, Tempo::default()).with_part(part))
+    }
+    fn spines(&self) -> Vec<Spine<'_>> {
+        let mut spines = Vec::new();
+        for part in self.score.parts.iter().rev() {
+            for staff in part.staves.iter().rev() {
+                let mut voices: Vec<Vec<(Ratio, String)>> = staff
+                    .voices
+                    .iter()
+                    .map(|voice| {
+                        let mut out = Vec::new();
+                        let mut position = Ratio::ZERO;
+                        for measure in self.score.measures(voice) {
+                            for event in &measure.events {
+                                leaves(event, Ratio::new(1, 1), &mut position, &mut out);
+                            }
+                        }
+                        out
+                    })
+                    .collect();
+                if voices.is_empty() {
+                    voices.push(Vec::new());
+                }
+                spines.push(Spine {
+                    part,
+                    staff,
+                    voices,
+                });
+            }
+        }
+        spines
+    }
+    pub fn to_kern(&self) -> String {
+        let spines = self.spines();
+        let mut lines: Vec<String> = Vec::new();
+        if let Some(composer) = &self.score.composer {
+            lines.push(format!("!!!COM: {composer}"));
+        }
+        if let Some(title) = &self.score.title {
+            lines.push(format!("!!!OTL: {title}"));
+        }
+        let each = |token: &dyn Fn(&Spine) -> String| {
+            spines.iter().map(token).collect::<Vec<_>>().join("\t")
+        };
+        lines.push(each(&|_| "**kern".to_string()));
+        lines.push(each(&|spine| format!("*I\"{}", spine.part.name)));
+        lines.push(each(&|spine| format!("*clef{}", clef(spine.staff.clef))));
+        lines.push(each(&|spine| signature(&spine.part.key)));
+        lines.push(each(&|spine| key(&spine.part.key)));
+        if let Some((_, time_signature)) = self.score.time_signatures.first() {
+            lines.push(each(&|_| format!("*M{time_signature}")));
+        }
+        if let Some(change) = self.score.tempo.changes().first() {
+            lines.push(each(&|_| tempo(&change.tempo)));
+        }
+        let widths: Vec<usize> = spines.iter().map(|spine| spine.voices.len()).collect();
+        for split in 1..widths.iter().copied().max().unwrap_or(1) {
+            let tokens: Vec<String> = widths
+                .iter()
+                .flat_map(|&width| {
+                    let current = width.min(split);
+                    (0..current).map(move |i| match i + 1 == current && split < width {
+                        true => "*^".to_string(),
+                        false => "*".to_string(),
+                    })
+                })
+                .collect();
+            lines.push(tokens.join("\t"));
+        }
+        let columns: Vec<&Vec<(Ratio, String)>> =
+            spines.iter().flat_map(|spine| &spine.voices).collect();
+        let barlines: Vec<Ratio> = self.score.barlines().into_iter().map(Ratio::from).collect();
+        let meters: Vec<(Ratio, &TimeSignature)> = self
+            .score
+            .time_signatures
+            .iter()
+            .skip(1)
+            .map(|(position, time_signature)| (Ratio::from(*position), time_signature))
+            .collect();
+        let tempos: Vec<(Ratio, &Tempo)> = self
+            .score
+            .tempo
+            .changes()
+            .iter()
+            .skip(1)
+            .map(|change| (Ratio::from(change.position), &change.tempo))
+            .collect();
+        let mut times: Vec<Ratio> = columns
+            .iter()
+            .flat_map(|column| column.iter().map(|(position, _)| *position))
+            .chain(barlines.iter().copied())
+            .chain(meters.iter().map(|(position, _)| *position))
+            .chain(tempos.iter().map(|(position, _)| *position))
+            .collect();
+        times.sort();
+        times.dedup();
+        let all = |token: &str| vec![token; columns.len()].join("\t");
+        let mut cursors = vec![0; columns.len()];
+        for time in times {
+            if let Some(i) = barlines.iter().position(|&barline| barline == time) {
+                if i > 0 {
+                    lines.push(all(&format!("={}", i + 1)));
+                }
+            }
+            for (_, time_signature) in meters.iter().filter(|(position, _)| *position == time) {
+                lines.push(all(&format!("*M{time_signature}")));
+            }
+            for (_, change) in tempos.iter().filter(|(position, _)| *position == time) {
+                lines.push(all(&tempo(change)));
+            }
+            let mut tokens = Vec::new();
+            let mut found = false;
+            for (column, cursor) in columns.iter().zip(&mut cursors) {
+                match column.get(*cursor) {
+                    Some((position, token)) if *position == time => {
+                        tokens.push(token.as_str());
+                        *cursor += 1;
+                        found = true;
+                    }
+                    _ => tokens.push("."),
+                }
+            }
+            if found {
+                lines.push(tokens.join("\t"));
+            }
+        }
+        lines.push(all("=="));
+        for (i, &merge) in widths.iter().enumerate() {
+            if merge < 2 {
+                continue;
+            }
+            let tokens: Vec<&str> = widths
+                .iter()
+                .enumerate()
+                .flat_map(|(j, &width)| {
+                    let width = match j < i {
+                        true => 1,
+                        false => width,
+                    };
+                    (0..width).map(move |_| match j == i {
+                        true => "*v",
+                        false => "*",
+                    })
+                })
+                .collect();
+            lines.push(tokens.join("\t"));
+        }
+        lines.push(vec!["*-"; spines.len()].join("\t"));
+        lines.join("\n") + "\n"
+    }
+    pub fn write<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(self.to_kern().as_bytes())
+    }
+}
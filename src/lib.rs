@@ -1,4 +1,18 @@
-//pub mod chord;
+pub mod abc;
+pub mod chart;
+pub mod chord;
+pub mod chordpro;
 //pub mod freq;
+pub mod kern;
+pub mod lilypond;
+pub mod mei;
+pub mod midi;
+pub mod musicxml;
 pub mod note;
-//pub mod scale;
+mod parse_error;
+mod ratio;
+pub mod scale;
+pub mod score;
+mod xml;
+
+pub use parse_error::ParseError;
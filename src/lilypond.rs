@@ -0,0 +1,5 @@
+mod language;
+mod writer;
+
+pub use language::LilyPondLanguage;
+pub use writer::{LilyPondWriter, OctaveEntry};
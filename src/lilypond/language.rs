@@ -0,0 +1,165 @@
+use crate::note::{Diatonic, PitchClass};
+use crate::ParseError;
+use std::fmt;
+use std::str::FromStr;
+
+const SOLFEGE: [&str; 7] = ["do", "re", "mi", "fa", "sol", "la", "si"];
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum LilyPondLanguage {
+    #[default]
+    Dutch,
+    English,
+    German,
+    Norwegian,
+    Finnish,
+    Swedish,
+    Italian,
+    Spanish,
+    French,
+    Portuguese,
+    Catalan,
+    Flemish,
+}
+
+impl LilyPondLanguage {
+    pub const ALL: [Self; 12] = [
+        LilyPondLanguage::Dutch,
+        LilyPondLanguage::English,
+        LilyPondLanguage::German,
+        LilyPondLanguage::Norwegian,
+        LilyPondLanguage::Finnish,
+        LilyPondLanguage::Swedish,
+        LilyPondLanguage::Italian,
+        LilyPondLanguage::Spanish,
+        LilyPondLanguage::French,
+        LilyPondLanguage::Portuguese,
+        LilyPondLanguage::Catalan,
+        LilyPondLanguage::Flemish,
+    ];
+    pub const fn name(&self) -> &'static str {
+        match self {
+            LilyPondLanguage::Dutch => "nederlands",
+            LilyPondLanguage::English => "english",
+            LilyPondLanguage::German => "deutsch",
+            LilyPondLanguage::Norwegian => "norsk",
+            LilyPondLanguage::Finnish => "suomi",
+            LilyPondLanguage::Swedish => "svenska",
+            LilyPondLanguage::Italian => "italiano",
+            LilyPondLanguage::Spanish => "espanol",
+            LilyPondLanguage::French => "francais",
+            LilyPondLanguage::Portuguese => "portugues",
+            LilyPondLanguage::Catalan => "catalan",
+            LilyPondLanguage::Flemish => "vlaams",
+        }
+    }
+    const fn suffixes(&self) -> (&'static str, &'static str) {
+        match self {
+            LilyPondLanguage::Dutch
+            | LilyPondLanguage::German
+            | LilyPondLanguage::Norwegian
+            | LilyPondLanguage::Finnish => ("is", "es"),
+            LilyPondLanguage::Swedish => ("iss", "ess"),
+            LilyPondLanguage::English => ("s", "f"),
+            LilyPondLanguage::Spanish | LilyPondLanguage::Portuguese => ("s", "b"),
+            LilyPondLanguage::Italian | LilyPondLanguage::French | LilyPondLanguage::Catalan => {
+                ("d", "b")
+            }
+            LilyPondLanguage::Flemish => ("k", "b"),
+        }
+    }
+    const fn is_solfege(&self) -> bool {
+        matches!(
+            self,
+            LilyPondLanguage::Italian
+                | LilyPondLanguage::Spanish
+                | LilyPondLanguage::French
+                | LilyPondLanguage::Portuguese
+                | LilyPondLanguage::Catalan
+                | LilyPondLanguage::Flemish
+        )
+    }
+    const fn is_germanic(&self) -> bool {
+        matches!(
+            self,
+            LilyPondLanguage::German
+                | LilyPondLanguage::Norwegian
+                | LilyPondLanguage::Finnish
+                | LilyPondLanguage::Swedish
+        )
+    }
+    pub fn pitch_class(&self, pitch_class: PitchClass) -> String {
+        let PitchClass { base, accidental } = pitch_class;
+        let shift = accidental.pitch_shift();
+        let (sharp, flat) = self.suffixes();
+        let suffix = |count: isize| match count >= 0 {
+            true => sharp.repeat(count as usize),
+            false => flat.repeat(-count as usize),
+        };
+        let letter = base.name().to_lowercase();
+        match (self, base, shift) {
+            _ if self.is_solfege() => {
+                format!("{}{}", SOLFEGE[base.index() as usize], suffix(shift))
+            }
+            (_, Diatonic::B, -1) if self.is_germanic() => "b".to_string(),
+            (LilyPondLanguage::Finnish | LilyPondLanguage::Swedish, Diatonic::B, ..=-2) => {
+                format!("b{}", suffix(shift + 1))
+            }
+            (_, Diatonic::B, _) if self.is_germanic() => format!("h{}", suffix(shift)),
+            (LilyPondLanguage::Swedish, Diatonic::E | Diatonic::A, ..=-1) => {
+                format!("{letter}ss{}", suffix(shift + 1))
+            }
+            (LilyPondLanguage::English, ..) => format!("{letter}{}", suffix(shift)),
+            (_, Diatonic::E | Diatonic::A, ..=-1) => format!("{letter}s{}", suffix(shift + 1)),
+            _ => format!("{letter}{}", suffix(shift)),
+        }
+    }
+}
+
+impl fmt::Display for LilyPondLanguage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for LilyPondLanguage {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.to_lowercase();
+        let alias = match lower.as_str() {
+            "dutch" => "nederlands",
+            "german" => "deutsch",
+            "norwegian" => "norsk",
+            "finnish" => "suomi",
+            "swedish" => "svenska",
+            "italian" => "italiano",
+            "spanish" | "español" => "espanol",
+            "french" | "français" => "francais",
+            "portuguese" | "português" => "portugues",
+            "català" => "catalan",
+            "flemish" => "vlaams",
+            other => other,
+        };
+        LilyPondLanguage::ALL
+            .into_iter()
+            .find(|language| language.name() == alias)
+            .ok_or(ParseError::at(
+                s,
+                0,
+                &[
+                    "nederlands",
+                    "english",
+                    "deutsch",
+                    "norsk",
+                    "suomi",
+                    "svenska",
+                    "italiano",
+                    "espanol",
+                    "francais",
+                    "portugues",
+                    "catalan",
+                    "vlaams",
+                ],
+            ))
+    }
+}
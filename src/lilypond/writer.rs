@@ -0,0 +1,386 @@
+use crate::chord::{ChordQuality, ChordSymbol, DegreeKind};
+use crate::lilypond::LilyPondLanguage;
+use crate::note::{Duration, NoteEvent, Pitch, Subdivision};
+use crate::scale::Key;
+use crate::score::{Clef, Instrument, Part, Score, Staff, Tempo, TimeSignature, Voice};
+use std::io;
+
+const VERSION: &str = "2.24.0";
+const REFERENCE: isize = 4 * 7;
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum OctaveEntry {
+    #[default]
+    Absolute,
+    Relative,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LilyPondWriter {
+    pub score: Score,
+    pub language: LilyPondLanguage,
+    pub octaves: OctaveEntry,
+}
+
+fn string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn durations(duration: Duration) -> Vec<String> {
+    duration
+        .to_dotted_parts()
+        .into_iter()
+        .map(|(unit, dots)| format!("{}{}", unit.nth(), ".".repeat(dots)))
+        .collect()
+}
+
+const fn step(pitch: Pitch) -> isize {
+    pitch.octave.numeric() * 7 + pitch.pitch_class.base.index()
+}
+
+const fn quality(quality: ChordQuality) -> &'static str {
+    match quality {
+        ChordQuality::Major => "",
+        ChordQuality::Minor => "m",
+        ChordQuality::Augmented => "aug",
+        ChordQuality::Diminished => "dim",
+        ChordQuality::Dominant => "7",
+        ChordQuality::MajorSeventh => "maj7",
+        ChordQuality::MinorSeventh => "m7",
+        ChordQuality::DiminishedSeventh => "dim7",
+        ChordQuality::AugmentedSeventh => "aug7",
+        ChordQuality::HalfDiminished => "m7.5-",
+        ChordQuality::MinorMajorSeventh => "m7+",
+        ChordQuality::MajorSixth => "6",
+        ChordQuality::MinorSixth => "m6",
+        ChordQuality::DominantNinth => "9",
+        ChordQuality::MajorNinth => "maj9",
+        ChordQuality::MinorNinth => "m9",
+        ChordQuality::DominantEleventh => "11",
+        ChordQuality::MajorEleventh => "maj11",
+        ChordQuality::MinorEleventh => "m11",
+        ChordQuality::DominantThirteenth => "13",
+        ChordQuality::MajorThirteenth => "maj13",
+        ChordQuality::MinorThirteenth => "m13",
+        ChordQuality::SuspendedSecond => "sus2",
+        ChordQuality::SuspendedFourth => "sus4",
+        ChordQuality::Power => "1.5",
+    }
+}
+
+fn clef(clef: Clef) -> String {
+    match clef {
+        Clef::Baritone => "varbaritone".to_string(),
+        Clef::TrebleOctaveDown => string(clef.name()),
+        clef => clef.name().to_string(),
+    }
+}
+
+fn time(time_signature: &TimeSignature) -> String {
+    match time_signature.is_additive() {
+        true => {
+            let groups: Vec<String> = time_signature
+                .groups()
+                .iter()
+                .map(usize::to_string)
+                .collect();
+            format!(
+                "\\time {} {}/{}",
+                groups.join(","),
+                time_signature.numerator(),
+                time_signature.denominator()
+            )
+        }
+        false => format!("\\time {time_signature}"),
+    }
+}
+
+fn tempo(tempo: &Tempo) -> String {
+    let tempo = match tempo.beat.as_dotted() {
+        Some(_) => *tempo,
+        None => tempo.with_beat(Subdivision::QUARTER_NOTE.to_duration()),
+    };
+    format!(
+        "\\tempo {} = {}",
+        durations(tempo.beat).join(""),
+        tempo.bpm.round() as usize
+    )
+}
+
+struct Context {
+    language: LilyPondLanguage,
+    previous: Option<isize>,
+}
+
+impl Context {
+    fn pitch(&mut self, pitch: Pitch) -> String {
+        let name = self.language.pitch_class(pitch.pitch_class);
+        let target = step(pitch);
+        let marks = match self.previous {
+            Some(previous) => {
+                let implicit = previous + (target - previous + 3).rem_euclid(7) - 3;
+                self.previous = Some(target);
+                (target - implicit) / 7
+            }
+            None => pitch.octave.numeric() - 3,
+        };
+        match marks >= 0 {
+            true => format!("{name}{}", "'".repeat(marks as usize)),
+            false => format!("{name}{}", ",".repeat(-marks as usize)),
+        }
+    }
+    fn event(&mut self, event: &NoteEvent) -> String {
+        match event {
+            NoteEvent::Rest { value } => durations(*value)
+                .into_iter()
+                .map(|duration| format!("r{duration}"))
+                .collect::<Vec<_>>()
+                .join(" "),
+            NoteEvent::Single { pitch, value, tie } => {
+                let mut notes = Vec::new();
+                for duration in durations(*value) {
+                    notes.push(format!("{}{duration}", self.pitch(*pitch)));
+                }
+                let mut notes = notes.join("~ ");
+                if tie.start {
+                    notes.push('~');
+                }
+                notes
+            }
+            NoteEvent::Chord {
+                pitches,
+                value,
+                tie,
+            } => {
+                let mut chords = Vec::new();
+                for duration in durations(*value) {
+                    let first = self.previous;
+                    let names: Vec<String> =
+                        pitches.iter().map(|&pitch| self.pitch(pitch)).collect();
+                    if first.is_some() {
+                        self.previous = pitches.first().map(|&pitch| step(pitch));
+                    }
+                    chords.push(format!("<{}>{duration}", names.join(" ")));
+                }
+                let mut chords = chords.join("~ ");
+                if tie.start {
+                    chords.push('~');
+                }
+                chords
+            }
+            NoteEvent::Tuplet { tuplet, events, .. } => {
+                let events: Vec<String> = events.iter().map(|event| self.event(event)).collect();
+                format!(
+                    "\\tuplet {}/{} {{ {} }}",
+                    tuplet.actual,
+                    tuplet.normal,
+                    events.join(" ")
+                )
+            }
+        }
+    }
+}
+
+impl LilyPondWriter {
+    pub const fn new(score: Score) -> Self {
+        LilyPondWriter {
+            score,
+            language: LilyPondLanguage::Dutch,
+            octaves: OctaveEntry::Absolute,
+        }
+    }
+    pub fn from_events(
+        events: impl IntoIterator<Item = NoteEvent>,
+        time_signature: TimeSignature,
+        key: Key,
+    ) -> Self {
+        let part = Part::new("Music", Instrument::new("Piano", 0), key)
+            .with_staff(Staff::new(Clef::Treble).with_voice(events.into_iter().collect()));
+        LilyPondWriter::new(Score::new(time_signature, Tempo::default()).with_part(part))
+    }
+    pub const fn with_language(mut self, language: LilyPondLanguage) -> Self {
+        self.language = language;
+        self
+    }
+    pub const fn with_octaves(mut self, octaves: OctaveEntry) -> Self {
+        self.octaves = octaves;
+        self
+    }
+    pub fn chord(&self, chord: &ChordSymbol, duration: &str) -> String {
+        let mut modifiers = quality(chord.quality).to_string();
+        let suspended = chord.is_suspended();
+        for degree in &chord.degrees {
+            let alteration = degree.accidental.pitch_shift();
+            let alteration = match alteration >= 0 {
+                true => "+".repeat(alteration as usize),
+                false => "-".repeat(-alteration as usize),
+            };
+            match (degree.kind, degree.value) {
+                (DegreeKind::Subtract, 3) if suspended => {}
+                (DegreeKind::Add, value @ (2 | 4)) if suspended && alteration.is_empty() => {
+                    modifiers.push_str(&format!("sus{value}"))
+                }
+                (DegreeKind::Subtract, value) => modifiers.push_str(&format!("^{value}")),
+                (_, value) => {
+                    if modifiers.is_empty() {
+                        modifiers.push('5');
+                    }
+                    modifiers.push_str(&format!(".{value}{alteration}"));
+                }
+            }
+        }
+        let mut symbol = format!("{}{duration}", self.language.pitch_class(chord.root));
+        if !modifiers.is_empty() {
+            symbol.push(':');
+            symbol.push_str(&modifiers);
+        }
+        if let Some(bass) = chord.bass {
+            symbol.push('/');
+            symbol.push_str(&self.language.pitch_class(bass));
+        }
+        symbol
+    }
+    fn chord_names(&self, part: &Part) -> Option<String> {
+        if part.chords.is_empty() {
+            return None;
+        }
+        let end = self.score.duration();
+        let mut items = Vec::new();
+        let mut position = Duration::ZERO;
+        for (i, (start, chord)) in part.chords.iter().enumerate() {
+            if *start > position {
+                items.extend(
+                    durations(*start - position)
+                        .into_iter()
+                        .map(|d| format!("s{d}")),
+                );
+            }
+            let until = part.chords.get(i + 1).map_or_else(
+                || end.max(*start + Subdivision::QUARTER_NOTE.to_duration()),
+                |&(next, _)| next,
+            );
+            let mut parts = durations(until - *start).into_iter();
+            if let Some(first) = parts.next() {
+                items.push(self.chord(chord, &first));
+            }
+            items.extend(parts.map(|d| format!("s{d}")));
+            position = until;
+        }
+        Some(format!(
+            "    \\new ChordNames \\chordmode {{ {} }}\n",
+            items.join(" ")
+        ))
+    }
+    fn voice(&self, voice: &Voice, with_tempo: bool) -> String {
+        let mut context = Context {
+            language: self.language,
+            previous: match self.octaves {
+                OctaveEntry::Absolute => None,
+                OctaveEntry::Relative => Some(REFERENCE),
+            },
+        };
+        let mut tempos = self.score.tempo.changes().iter().skip(1).peekable();
+        let barlines = self.score.barlines();
+        let mut out = String::new();
+        for (i, (measure, start)) in self.score.measures(voice).iter().zip(&barlines).enumerate() {
+            let mut items = Vec::new();
+            if i > 0
+                && self
+                    .score
+                    .time_signatures
+                    .iter()
+                    .any(|(position, _)| position == start)
+            {
+                items.push(time(&measure.time_signature));
+            }
+            let mut position = *start;
+            for event in &measure.events {
+                let end = position + event.value();
+                while let Some(change) =
+                    tempos.next_if(|change| with_tempo && change.position < end)
+                {
+                    items.push(tempo(&change.tempo));
+                }
+                items.push(context.event(event));
+                position = end;
+            }
+            out.push_str(&format!("        {} |\n", items.join(" ")));
+        }
+        match self.octaves {
+            OctaveEntry::Absolute => format!("      {{\n{out}      }}\n"),
+            OctaveEntry::Relative => format!("      \\relative c' {{\n{out}      }}\n"),
+        }
+    }
+    fn staff(&self, part: &Part, staff: &Staff, name: Option<&str>, with_tempo: bool) -> String {
+        let mut out = String::from("    \\new Staff ");
+        if let Some(name) = name {
+            out.push_str(&format!("\\with {{ instrumentName = {} }} ", string(name)));
+        }
+        out.push_str("{\n");
+        out.push_str(&format!("      \\clef {}\n", clef(staff.clef)));
+        out.push_str(&format!(
+            "      \\key {} \\{}\n",
+            self.language.pitch_class(part.key.tonic),
+            part.key.mode.name()
+        ));
+        if let Some((_, time_signature)) = self.score.time_signatures.first() {
+            out.push_str(&format!("      {}\n", time(time_signature)));
+        }
+        if let (true, Some(change)) = (with_tempo, self.score.tempo.changes().first()) {
+            out.push_str(&format!("      {}\n", tempo(&change.tempo)));
+        }
+        let voices: Vec<String> = staff
+            .voices
+            .iter()
+            .enumerate()
+            .map(|(i, voice)| self.voice(voice, with_tempo && i == 0))
+            .collect();
+        match voices.len() {
+            0 => {}
+            1 => out.push_str(&voices[0]),
+            _ => {
+                out.push_str("      <<\n");
+                out.push_str(&voices.join("      \\\\\n"));
+                out.push_str("      >>\n");
+            }
+        }
+        out.push_str("    }\n");
+        out
+    }
+    pub fn to_lilypond(&self) -> String {
+        let mut out = format!("\\version {}\n", string(VERSION));
+        out.push_str(&format!("\\language {}\n\n", string(self.language.name())));
+        if self.score.title.is_some() || self.score.composer.is_some() {
+            out.push_str("\\header {\n");
+            if let Some(title) = &self.score.title {
+                out.push_str(&format!("  title = {}\n", string(title)));
+            }
+            if let Some(composer) = &self.score.composer {
+                out.push_str(&format!("  composer = {}\n", string(composer)));
+            }
+            out.push_str("}\n\n");
+        }
+        out.push_str("\\score {\n  <<\n");
+        for (i, part) in self.score.parts.iter().enumerate() {
+            out.push_str(&self.chord_names(part).unwrap_or_default());
+            match part.staves.len() {
+                1 => out.push_str(&self.staff(part, &part.staves[0], Some(&part.name), i == 0)),
+                _ => {
+                    out.push_str(&format!(
+                        "  \\new PianoStaff \\with {{ instrumentName = {} }} <<\n",
+                        string(&part.name)
+                    ));
+                    for (j, staff) in part.staves.iter().enumerate() {
+                        out.push_str(&self.staff(part, staff, None, i == 0 && j == 0));
+                    }
+                    out.push_str("  >>\n");
+                }
+            }
+        }
+        out.push_str("  >>\n  \\layout { }\n  \\midi { }\n}\n");
+        out
+    }
+    pub fn write<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(self.to_lilypond().as_bytes())
+    }
+}
@@ -0,0 +1,363 @@
+use crate::note::{Accidental, Diatonic, Duration, NoteEvent, Octave, Pitch, Subdivision, Tie};
+use crate::scale::Key;
+use crate::score::{Clef, Instrument, Measure, Part, Score, Staff, Tempo, TimeSignature, Voice};
+use crate::xml::{escape, XmlWriter};
+use std::io;
+
+const NAMESPACE: &str = "http://www.music-encoding.org/ns/mei";
+const VERSION: &str = "5.0";
+
+fn accid(accidental: Accidental) -> &'static str {
+    match accidental.pitch_shift() {
+        3.. => "ts",
+        2 => "x",
+        1 => "s",
+        0 => "n",
+        -1 => "f",
+        -2 => "ff",
+        ..=-3 => "tf",
+    }
+}
+
+fn key_signature(key: &Key) -> String {
+    match key.fifths() {
+        0 => "0".to_string(),
+        fifths if fifths > 0 => format!("{fifths}s"),
+        fifths => format!("{}f", -fifths),
+    }
+}
+
+fn clef(clef: Clef) -> String {
+    match clef {
+        Clef::Percussion => "clef.shape=\"perc\"".to_string(),
+        clef => {
+            let mut attributes = format!(
+                "clef.shape=\"{}\" clef.line=\"{}\"",
+                clef.sign(),
+                clef.line()
+            );
+            if clef.octave_change() < 0 {
+                attributes.push_str(" clef.dis=\"8\" clef.dis.place=\"below\"");
+            }
+            attributes
+        }
+    }
+}
+
+fn meter(time_signature: &TimeSignature) -> String {
+    let groups: Vec<String> = time_signature
+        .groups()
+        .iter()
+        .map(usize::to_string)
+        .collect();
+    format!(
+        "meterSig count=\"{}\" unit=\"{}\"",
+        groups.join("+"),
+        time_signature.denominator()
+    )
+}
+
+fn dur(unit: Subdivision, dots: usize) -> String {
+    let mut attributes = format!("dur=\"{}\"", unit.nth());
+    if dots > 0 {
+        attributes.push_str(&format!(" dots=\"{dots}\""));
+    }
+    attributes
+}
+
+fn tie(tie: Tie) -> &'static str {
+    match (tie.start, tie.stop) {
+        (true, true) => " tie=\"m\"",
+        (true, false) => " tie=\"i\"",
+        (false, true) => " tie=\"t\"",
+        (false, false) => "",
+    }
+}
+
+fn tstamp(offset: Duration, time_signature: &TimeSignature) -> f64 {
+    1.0 + offset.as_f64() * time_signature.denominator() as f64
+}
+
+struct Layer<'a> {
+    xml: &'a mut XmlWriter,
+    key: Key,
+    accidentals: &'a mut Vec<(Duration, Diatonic, Octave, Accidental)>,
+    position: Duration,
+}
+
+impl Layer<'_> {
+    fn note(&mut self, pitch: Pitch, attributes: &str, tie: Tie) {
+        let Pitch {
+            pitch_class,
+            octave,
+        } = pitch;
+        let base = pitch_class.base;
+        let position = self.position;
+        let expected = self
+            .accidentals
+            .iter()
+            .filter(|&&(from, other, at, _)| (other, at) == (base, octave) && from <= position)
+            .max_by_key(|&&(from, ..)| from)
+            .map_or(self.key.accidental(base), |&(.., accidental)| accidental);
+        let accidental = pitch_class.accidental;
+        let accid = if accidental != expected && !tie.stop {
+            self.accidentals
+                .retain(|&(from, other, at, _)| (from, other, at) != (position, base, octave));
+            self.accidentals.push((position, base, octave, accidental));
+            format!(" accid=\"{}\"", accid(accidental))
+        } else if accidental == expected && accidental == Accidental::NATURAL {
+            String::new()
+        } else {
+            format!(" accid.ges=\"{}\"", accid(accidental))
+        };
+        self.xml.empty(&format!(
+            "note pname=\"{}\" oct=\"{}\"{accid}{attributes}",
+            base.name().to_lowercase(),
+            octave.numeric(),
+        ));
+    }
+    fn event(&mut self, event: &NoteEvent) {
+        if let NoteEvent::Tuplet { tuplet, events, .. } = event {
+            self.xml.open(&format!(
+                "tuplet num=\"{}\" numbase=\"{}\"",
+                tuplet.actual, tuplet.normal
+            ));
+            for event in events {
+                self.event(event);
+            }
+            self.xml.close("tuplet");
+            return;
+        }
+        let parts = event.value().to_dotted_parts();
+        for (i, &(unit, dots)) in parts.iter().enumerate() {
+            let tie = Tie::new(
+                event.tie().start || i + 1 < parts.len(),
+                event.tie().stop || i > 0,
+            );
+            match event.pitches() {
+                [] => self.xml.empty(&format!("rest {}", dur(unit, dots))),
+                &[pitch] => self.note(
+                    pitch,
+                    &format!(" {}{}", dur(unit, dots), self::tie(tie)),
+                    tie,
+                ),
+                pitches => {
+                    self.xml.open(&format!("chord {}", dur(unit, dots)));
+                    for &pitch in pitches {
+                        self.note(pitch, self::tie(tie), tie);
+                    }
+                    self.xml.close("chord");
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MeiWriter {
+    pub score: Score,
+}
+
+impl MeiWriter {
+    pub const fn new(score: Score) -> Self {
+        MeiWriter { score }
+    }
+    pub fn from_events(
+        events: impl IntoIterator<Item = NoteEvent>,
+        time_signature: TimeSignature,
+        key: Key,
+    ) -> Self {
+        let part = Part::new("Music", Instrument::new("Piano", 0), key)
+            .with_staff(Staff::new(Clef::Treble).with_voice(events.into_iter().collect()));
+        MeiWriter::new(Score::new(time_signature, Tempo::default()).with_part(part))
+    }
+    fn head(&self, xml: &mut XmlWriter) {
+        xml.open("meiHead");
+        xml.open("fileDesc");
+        xml.open("titleStmt");
+        match &self.score.title {
+            Some(title) => xml.text("title", title),
+            None => xml.empty("title"),
+        }
+        if let Some(composer) = &self.score.composer {
+            xml.text("composer", composer);
+        }
+        xml.close("titleStmt");
+        xml.empty("pubStmt");
+        xml.close("fileDesc");
+        xml.close("meiHead");
+    }
+    fn score_definition(&self, xml: &mut XmlWriter) {
+        xml.open("scoreDef");
+        if let Some((_, time_signature)) = self.score.time_signatures.first() {
+            xml.empty(&meter(time_signature));
+        }
+        xml.open("staffGrp");
+        let mut n = 0;
+        for part in &self.score.parts {
+            let grouped = part.staves.len() > 1;
+            if grouped {
+                xml.open("staffGrp symbol=\"brace\" bar.thru=\"true\"");
+                xml.text("label", &part.name);
+            }
+            for staff in &part.staves {
+                n += 1;
+                xml.open(&format!(
+                    "staffDef n=\"{n}\" lines=\"5\" {}",
+                    clef(staff.clef)
+                ));
+                if !grouped {
+                    xml.text("label", &part.name);
+                }
+                xml.empty(&format!(
+                    "keySig sig=\"{}\" mode=\"{}\"",
+                    key_signature(&part.key),
+                    part.key.mode.name()
+                ));
+                xml.close("staffDef");
+            }
+            if grouped {
+                xml.close("staffGrp");
+            }
+        }
+        xml.close("staffGrp");
+        xml.close("scoreDef");
+    }
+    fn measure(
+        &self,
+        xml: &mut XmlWriter,
+        index: usize,
+        start: Duration,
+        staves: &[(&Part, Vec<Vec<Measure>>)],
+    ) {
+        let end = self
+            .score
+            .barlines()
+            .get(index + 1)
+            .copied()
+            .unwrap_or(self.score.duration());
+        let time_signature = self
+            .score
+            .time_signature_at(start)
+            .cloned()
+            .unwrap_or_else(TimeSignature::common_time);
+        xml.open(&format!("measure n=\"{}\"", index + 1));
+        for (n, (part, voices)) in staves.iter().enumerate() {
+            xml.open(&format!("staff n=\"{}\"", n + 1));
+            let mut accidentals = Vec::new();
+            for (i, measures) in voices.iter().enumerate() {
+                let measure = &measures[index];
+                if i > 0 && measure.events.is_empty() {
+                    continue;
+                }
+                xml.open(&format!("layer n=\"{}\"", i + 1));
+                let mut layer = Layer {
+                    xml,
+                    key: part.key,
+                    accidentals: &mut accidentals,
+                    position: Duration::ZERO,
+                };
+                if measure.events.is_empty() {
+                    layer.xml.empty("mRest");
+                }
+                for event in &measure.events {
+                    layer.event(event);
+                    layer.position += event.value();
+                }
+                xml.close("layer");
+            }
+            xml.close("staff");
+        }
+        for change in self.score.tempo.changes() {
+            if change.position >= start && change.position < end {
+                let (unit, dots) = change
+                    .tempo
+                    .beat
+                    .as_dotted()
+                    .unwrap_or((Subdivision::QUARTER_NOTE, 0));
+                let mut attributes = format!(
+                    "tempo tstamp=\"{}\" staff=\"1\" mm=\"{}\" mm.unit=\"{}\"",
+                    tstamp(change.position - start, &time_signature),
+                    change.tempo.bpm,
+                    unit.nth()
+                );
+                if dots > 0 {
+                    attributes.push_str(&format!(" mm.dots=\"{dots}\""));
+                }
+                xml.line(&format!("<{attributes}>{}</tempo>", change.tempo));
+            }
+        }
+        let mut n = 0;
+        for part in &self.score.parts {
+            for (position, chord) in &part.chords {
+                if *position >= start && *position < end {
+                    xml.line(&format!(
+                        "<harm tstamp=\"{}\" staff=\"{}\">{}</harm>",
+                        tstamp(*position - start, &time_signature),
+                        n + 1,
+                        escape(&chord.to_string())
+                    ));
+                }
+            }
+            n += part.staves.len();
+        }
+        xml.close("measure");
+    }
+    pub fn to_xml(&self) -> String {
+        let mut xml = XmlWriter::new();
+        xml.line("<?xml version=\"1.0\" encoding=\"UTF-8\"?>");
+        xml.line(&format!("<?xml-model href=\"https://music-encoding.org/schema/{VERSION}/mei-CMN.rng\" type=\"application/xml\" schematypens=\"http://relaxng.org/ns/structure/1.0\"?>"));
+        xml.open(&format!(
+            "mei xmlns=\"{NAMESPACE}\" meiversion=\"{VERSION}\""
+        ));
+        self.head(&mut xml);
+        xml.open("music");
+        xml.open("body");
+        xml.open("mdiv");
+        xml.open("score");
+        self.score_definition(&mut xml);
+        xml.open("section");
+        let staves: Vec<(&Part, Vec<Vec<Measure>>)> = self
+            .score
+            .parts
+            .iter()
+            .flat_map(|part| part.staves.iter().map(move |staff| (part, staff)))
+            .map(|(part, staff)| {
+                let mut voices: Vec<Vec<Measure>> = staff
+                    .voices
+                    .iter()
+                    .map(|voice| self.score.measures(voice))
+                    .collect();
+                if voices.is_empty() {
+                    voices.push(self.score.measures(&Voice::new()));
+                }
+                (part, voices)
+            })
+            .collect();
+        for (index, start) in self.score.barlines().into_iter().enumerate() {
+            if index > 0 {
+                if let Some((_, time_signature)) = self
+                    .score
+                    .time_signatures
+                    .iter()
+                    .find(|(position, _)| *position == start)
+                {
+                    xml.open("scoreDef");
+                    xml.empty(&meter(time_signature));
+                    xml.close("scoreDef");
+                }
+            }
+            self.measure(&mut xml, index, start, &staves);
+        }
+        xml.close("section");
+        xml.close("score");
+        xml.close("mdiv");
+        xml.close("body");
+        xml.close("music");
+        xml.close("mei");
+        xml.out
+    }
+    pub fn write<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(self.to_xml().as_bytes())
+    }
+}
@@ -0,0 +1,9 @@
+mod message;
+mod reader;
+mod smf;
+mod writer;
+
+pub use message::{MidiDecodeError, MidiDecoder, MidiMessage};
+pub use reader::{Smf, SmfError, SmfErrorKind, SmfEvent, SmfNote};
+pub use smf::Format;
+pub use writer::{SmfTrack, SmfWriteError, SmfWriter};
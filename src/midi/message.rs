@@ -0,0 +1,374 @@
+use crate::note::MidiNote;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MidiDecodeError {
+    Empty,
+    Incomplete,
+    UnexpectedData(u8),
+    UnexpectedStatus(u8),
+    Undefined(u8),
+}
+
+impl fmt::Display for MidiDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MidiDecodeError::Empty => f.write_str("no bytes to decode"),
+            MidiDecodeError::Incomplete => f.write_str("incomplete message"),
+            MidiDecodeError::UnexpectedData(byte) => {
+                write!(f, "data byte {byte:#04x} without status")
+            }
+            MidiDecodeError::UnexpectedStatus(byte) => {
+                write!(f, "unexpected status byte {byte:#04x}")
+            }
+            MidiDecodeError::Undefined(byte) => write!(f, "undefined status byte {byte:#04x}"),
+        }
+    }
+}
+
+impl Error for MidiDecodeError {}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum MidiMessage {
+    NoteOff {
+        channel: u8,
+        note: MidiNote,
+        velocity: u8,
+    },
+    NoteOn {
+        channel: u8,
+        note: MidiNote,
+        velocity: u8,
+    },
+    PolyAftertouch {
+        channel: u8,
+        note: MidiNote,
+        pressure: u8,
+    },
+    ControlChange {
+        channel: u8,
+        controller: u8,
+        value: u8,
+    },
+    ProgramChange {
+        channel: u8,
+        program: u8,
+    },
+    ChannelAftertouch {
+        channel: u8,
+        pressure: u8,
+    },
+    PitchBend {
+        channel: u8,
+        value: i16,
+    },
+    SystemExclusive(Vec<u8>),
+    TimeCodeQuarterFrame(u8),
+    SongPosition(u16),
+    SongSelect(u8),
+    TuneRequest,
+    TimingClock,
+    Start,
+    Continue,
+    Stop,
+    ActiveSensing,
+    Reset,
+}
+
+impl MidiMessage {
+    pub const fn status(&self) -> u8 {
+        match self {
+            MidiMessage::NoteOff { channel, .. } => 0x80 | (*channel & 0x0f),
+            MidiMessage::NoteOn { channel, .. } => 0x90 | (*channel & 0x0f),
+            MidiMessage::PolyAftertouch { channel, .. } => 0xa0 | (*channel & 0x0f),
+            MidiMessage::ControlChange { channel, .. } => 0xb0 | (*channel & 0x0f),
+            MidiMessage::ProgramChange { channel, .. } => 0xc0 | (*channel & 0x0f),
+            MidiMessage::ChannelAftertouch { channel, .. } => 0xd0 | (*channel & 0x0f),
+            MidiMessage::PitchBend { channel, .. } => 0xe0 | (*channel & 0x0f),
+            MidiMessage::SystemExclusive(_) => 0xf0,
+            MidiMessage::TimeCodeQuarterFrame(_) => 0xf1,
+            MidiMessage::SongPosition(_) => 0xf2,
+            MidiMessage::SongSelect(_) => 0xf3,
+            MidiMessage::TuneRequest => 0xf6,
+            MidiMessage::TimingClock => 0xf8,
+            MidiMessage::Start => 0xfa,
+            MidiMessage::Continue => 0xfb,
+            MidiMessage::Stop => 0xfc,
+            MidiMessage::ActiveSensing => 0xfe,
+            MidiMessage::Reset => 0xff,
+        }
+    }
+    pub const fn channel(&self) -> Option<u8> {
+        match self.status() {
+            status @ 0x80..=0xef => Some(status & 0x0f),
+            _ => None,
+        }
+    }
+    pub const fn is_realtime(&self) -> bool {
+        self.status() >= 0xf8
+    }
+    pub const fn data_len(status: u8) -> Option<usize> {
+        match status {
+            0x80..=0xbf | 0xe0..=0xef | 0xf2 => Some(2),
+            0xc0..=0xdf | 0xf1 | 0xf3 => Some(1),
+            0xf6 | 0xf8 | 0xfa..=0xfc | 0xfe | 0xff => Some(0),
+            _ => None,
+        }
+    }
+    pub fn encode_into(&self, bytes: &mut Vec<u8>) {
+        bytes.push(self.status());
+        match self {
+            MidiMessage::NoteOff { note, velocity, .. }
+            | MidiMessage::NoteOn { note, velocity, .. }
+            | MidiMessage::PolyAftertouch {
+                note,
+                pressure: velocity,
+                ..
+            } => bytes.extend([note.as_u8(), velocity & 0x7f]),
+            MidiMessage::ControlChange {
+                controller, value, ..
+            } => bytes.extend([controller & 0x7f, value & 0x7f]),
+            MidiMessage::ProgramChange { program: data, .. }
+            | MidiMessage::ChannelAftertouch { pressure: data, .. }
+            | MidiMessage::TimeCodeQuarterFrame(data)
+            | MidiMessage::SongSelect(data) => bytes.push(data & 0x7f),
+            MidiMessage::PitchBend { value, .. } => {
+                let value = (*value as i32 + 0x2000).clamp(0, 0x3fff) as u16;
+                bytes.extend([(value & 0x7f) as u8, (value >> 7) as u8]);
+            }
+            MidiMessage::SongPosition(position) => {
+                bytes.extend([(position & 0x7f) as u8, (position >> 7 & 0x7f) as u8]);
+            }
+            MidiMessage::SystemExclusive(data) => {
+                bytes.extend(data.iter().map(|byte| byte & 0x7f));
+                bytes.push(0xf7);
+            }
+            _ => {}
+        }
+    }
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(3);
+        self.encode_into(&mut bytes);
+        bytes
+    }
+    pub fn decode(bytes: &[u8]) -> Result<(Self, usize), MidiDecodeError> {
+        let mut decoder = MidiDecoder::new();
+        for (i, &byte) in bytes.iter().enumerate() {
+            if let Some(message) = decoder.feed(byte).next() {
+                let unterminated = matches!(message, Ok(MidiMessage::SystemExclusive(_)));
+                let len = if unterminated && byte != 0xf7 {
+                    i
+                } else {
+                    i + 1
+                };
+                return message.map(|message| (message, len));
+            }
+        }
+        if bytes.is_empty() {
+            Err(MidiDecodeError::Empty)
+        } else {
+            Err(MidiDecodeError::Incomplete)
+        }
+    }
+    fn from_parts(status: u8, data: &[u8]) -> Result<Self, MidiDecodeError> {
+        let channel = status & 0x0f;
+        let note = || MidiNote::from_u7(data[0]);
+        Ok(match status & 0xf0 {
+            0x80 => MidiMessage::NoteOff {
+                channel,
+                note: note(),
+                velocity: data[1],
+            },
+            0x90 => MidiMessage::NoteOn {
+                channel,
+                note: note(),
+                velocity: data[1],
+            },
+            0xa0 => MidiMessage::PolyAftertouch {
+                channel,
+                note: note(),
+                pressure: data[1],
+            },
+            0xb0 => MidiMessage::ControlChange {
+                channel,
+                controller: data[0],
+                value: data[1],
+            },
+            0xc0 => MidiMessage::ProgramChange {
+                channel,
+                program: data[0],
+            },
+            0xd0 => MidiMessage::ChannelAftertouch {
+                channel,
+                pressure: data[0],
+            },
+            0xe0 => MidiMessage::PitchBend {
+                channel,
+                value: (data[0] as i16 | (data[1] as i16) << 7) - 0x2000,
+            },
+            _ => match status {
+                0xf1 => MidiMessage::TimeCodeQuarterFrame(data[0]),
+                0xf2 => MidiMessage::SongPosition(data[0] as u16 | (data[1] as u16) << 7),
+                0xf3 => MidiMessage::SongSelect(data[0]),
+                0xf6 => MidiMessage::TuneRequest,
+                0xf8 => MidiMessage::TimingClock,
+                0xfa => MidiMessage::Start,
+                0xfb => MidiMessage::Continue,
+                0xfc => MidiMessage::Stop,
+                0xfe => MidiMessage::ActiveSensing,
+                0xff => MidiMessage::Reset,
+                status => return Err(MidiDecodeError::Undefined(status)),
+            },
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MidiDecoder {
+    running: Option<u8>,
+    buffer: Vec<u8>,
+    sysex: Option<Vec<u8>>,
+}
+
+impl MidiDecoder {
+    pub const fn new() -> Self {
+        MidiDecoder {
+            running: None,
+            buffer: Vec::new(),
+            sysex: None,
+        }
+    }
+    pub const fn running_status(&self) -> Option<u8> {
+        self.running
+    }
+    pub fn reset(&mut self) {
+        *self = MidiDecoder::new();
+    }
+    pub fn feed(&mut self, byte: u8) -> impl Iterator<Item = Result<MidiMessage, MidiDecodeError>> {
+        let messages = match byte {
+            0xf8.. => [Some(MidiMessage::from_parts(byte, &[])), None],
+            0x80.. => self.feed_status(byte),
+            _ => [self.feed_data(byte), None],
+        };
+        messages.into_iter().flatten()
+    }
+    fn feed_data(&mut self, byte: u8) -> Option<Result<MidiMessage, MidiDecodeError>> {
+        if let Some(sysex) = &mut self.sysex {
+            sysex.push(byte);
+            return None;
+        }
+        if self.buffer.is_empty() {
+            match self.running {
+                Some(status) => self.buffer.push(status),
+                None => return Some(Err(MidiDecodeError::UnexpectedData(byte))),
+            }
+        }
+        self.buffer.push(byte);
+        self.complete()
+    }
+    fn feed_status(&mut self, status: u8) -> [Option<Result<MidiMessage, MidiDecodeError>>; 2] {
+        let sysex = self.sysex.take();
+        if status == 0xf7 {
+            return [
+                Some(match sysex {
+                    Some(data) => Ok(MidiMessage::SystemExclusive(data)),
+                    None => Err(MidiDecodeError::UnexpectedStatus(status)),
+                }),
+                None,
+            ];
+        }
+        self.buffer.clear();
+        self.running = None;
+        let result = match (status, MidiMessage::data_len(status)) {
+            (0xf0, _) => {
+                self.sysex = Some(Vec::new());
+                None
+            }
+            (_, None) => Some(Err(MidiDecodeError::Undefined(status))),
+            (_, Some(_)) => {
+                if status < 0xf0 {
+                    self.running = Some(status);
+                }
+                self.buffer.push(status);
+                self.complete()
+            }
+        };
+        match sysex {
+            Some(data) => [Some(Ok(MidiMessage::SystemExclusive(data))), result],
+            None => [result, None],
+        }
+    }
+    fn complete(&mut self) -> Option<Result<MidiMessage, MidiDecodeError>> {
+        let status = *self.buffer.first()?;
+        let len = MidiMessage::data_len(status)?;
+        if self.buffer.len() < len + 1 {
+            return None;
+        }
+        let message = MidiMessage::from_parts(status, &self.buffer[1..]);
+        self.buffer.clear();
+        Some(message)
+    }
+    pub fn decode<'a>(
+        &'a mut self,
+        bytes: &'a [u8],
+    ) -> impl Iterator<Item = Result<MidiMessage, MidiDecodeError>> + 'a {
+        bytes.iter().flat_map(move |&byte| self.feed(byte))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unterminated_sysex_keeps_following_status() {
+        let mut decoder = MidiDecoder::new();
+        let messages: Vec<_> = decoder.decode(&[0xf0, 0x01, 0x02, 0xf6]).collect();
+        assert_eq!(
+            messages,
+            [
+                Ok(MidiMessage::SystemExclusive(vec![0x01, 0x02])),
+                Ok(MidiMessage::TuneRequest),
+            ]
+        );
+    }
+
+    #[test]
+    fn unterminated_sysex_keeps_following_channel_message() {
+        let mut decoder = MidiDecoder::new();
+        let messages: Vec<_> = decoder.decode(&[0xf0, 0x01, 0xc3, 0x05]).collect();
+        assert_eq!(
+            messages,
+            [
+                Ok(MidiMessage::SystemExclusive(vec![0x01])),
+                Ok(MidiMessage::ProgramChange {
+                    channel: 3,
+                    program: 5
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn feed_returns_both_messages() {
+        let mut decoder = MidiDecoder::new();
+        assert_eq!(decoder.feed(0xf0).next(), None);
+        assert_eq!(decoder.feed(0x7f).next(), None);
+        assert_eq!(
+            decoder.feed(0xf6).collect::<Vec<_>>(),
+            [
+                Ok(MidiMessage::SystemExclusive(vec![0x7f])),
+                Ok(MidiMessage::TuneRequest),
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_leaves_status_after_unterminated_sysex() {
+        assert_eq!(
+            MidiMessage::decode(&[0xf0, 0x01, 0xf6]),
+            Ok((MidiMessage::SystemExclusive(vec![0x01]), 2))
+        );
+    }
+}
@@ -0,0 +1,436 @@
+use crate::midi::smf::{self, Format};
+use crate::midi::MidiMessage;
+use crate::note::{Duration, MidiNote, Note, NoteEvent, Pitch, Subdivision};
+use crate::scale::{Key, Mode};
+use crate::score::{Tempo, TempoMap, TimeSignature, Voice};
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SmfErrorKind {
+    UnexpectedEof,
+    InvalidHeader,
+    UnsupportedFormat(u16),
+    UnsupportedTiming,
+    InvalidLength,
+    MissingRunningStatus,
+    InvalidStatus(u8),
+    InvalidDataByte(u8),
+    MissingEndOfTrack,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SmfError {
+    pub offset: usize,
+    pub kind: SmfErrorKind,
+}
+
+impl SmfError {
+    pub const fn new(offset: usize, kind: SmfErrorKind) -> Self {
+        SmfError { offset, kind }
+    }
+}
+
+impl fmt::Display for SmfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            SmfErrorKind::UnexpectedEof => f.write_str("unexpected end of file")?,
+            SmfErrorKind::InvalidHeader => f.write_str("invalid header chunk")?,
+            SmfErrorKind::UnsupportedFormat(format) => write!(f, "unsupported format {format}")?,
+            SmfErrorKind::UnsupportedTiming => f.write_str("unsupported timecode division")?,
+            SmfErrorKind::InvalidLength => f.write_str("invalid variable-length quantity")?,
+            SmfErrorKind::MissingRunningStatus => {
+                f.write_str("data byte without running status")?
+            }
+            SmfErrorKind::InvalidStatus(status) => write!(f, "invalid status byte {status:#04x}")?,
+            SmfErrorKind::InvalidDataByte(byte) => write!(f, "invalid data byte {byte:#04x}")?,
+            SmfErrorKind::MissingEndOfTrack => f.write_str("track ends without end-of-track")?,
+        }
+        write!(f, " at byte offset {}", self.offset)
+    }
+}
+
+impl Error for SmfError {}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SmfEvent {
+    Midi(MidiMessage),
+    Tempo(u32),
+    TimeSignature {
+        numerator: u8,
+        denominator: u8,
+        clocks: u8,
+        thirty_seconds: u8,
+    },
+    KeySignature {
+        fifths: i8,
+        minor: bool,
+    },
+    TrackName(String),
+    Meta {
+        kind: u8,
+        data: Vec<u8>,
+    },
+    SysEx(Vec<u8>),
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SmfNote {
+    pub start: u64,
+    pub end: u64,
+    pub channel: u8,
+    pub note: MidiNote,
+    pub velocity: u8,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Smf {
+    pub format: Format,
+    pub ppq: u16,
+    pub tracks: Vec<Vec<(u64, SmfEvent)>>,
+}
+
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn error(&self, kind: SmfErrorKind) -> SmfError {
+        SmfError::new(self.offset, kind)
+    }
+    fn is_empty(&self) -> bool {
+        self.offset >= self.bytes.len()
+    }
+    fn peek(&self) -> Result<u8, SmfError> {
+        self.bytes
+            .get(self.offset)
+            .copied()
+            .ok_or(self.error(SmfErrorKind::UnexpectedEof))
+    }
+    fn take(&mut self, len: usize) -> Result<&'a [u8], SmfError> {
+        let end = self
+            .offset
+            .checked_add(len)
+            .filter(|&end| end <= self.bytes.len());
+        let end = end.ok_or(self.error(SmfErrorKind::UnexpectedEof))?;
+        let bytes = &self.bytes[self.offset..end];
+        self.offset = end;
+        Ok(bytes)
+    }
+    fn u8(&mut self) -> Result<u8, SmfError> {
+        let byte = self.peek()?;
+        self.offset += 1;
+        Ok(byte)
+    }
+    fn data(&mut self) -> Result<u8, SmfError> {
+        match self.peek()? {
+            byte if byte & 0x80 != 0 => Err(self.error(SmfErrorKind::InvalidDataByte(byte))),
+            _ => self.u8(),
+        }
+    }
+    fn u16(&mut self) -> Result<u16, SmfError> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+    fn u32(&mut self) -> Result<u32, SmfError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+    fn vlq(&mut self) -> Result<u32, SmfError> {
+        let start = self.offset;
+        let mut value = 0;
+        for _ in 0..4 {
+            let byte = self.u8()?;
+            value = value << 7 | (byte & 0x7f) as u32;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(SmfError::new(start, SmfErrorKind::InvalidLength))
+    }
+}
+
+impl Smf {
+    pub fn parse(bytes: &[u8]) -> Result<Self, SmfError> {
+        let mut cursor = Cursor { bytes, offset: 0 };
+        if cursor
+            .take(4)
+            .map_err(|_| cursor.error(SmfErrorKind::InvalidHeader))?
+            != b"MThd"
+        {
+            return Err(SmfError::new(0, SmfErrorKind::InvalidHeader));
+        }
+        let length = cursor.u32()? as usize;
+        if length < 6 {
+            return Err(SmfError::new(4, SmfErrorKind::InvalidHeader));
+        }
+        let header = cursor.offset;
+        let format = cursor.u16()?;
+        let format = Format::from_number(format).ok_or(SmfError::new(
+            header,
+            SmfErrorKind::UnsupportedFormat(format),
+        ))?;
+        let count = cursor.u16()?;
+        let division = cursor.u16()?;
+        if division & 0x8000 != 0 || division == 0 {
+            return Err(SmfError::new(header + 4, SmfErrorKind::UnsupportedTiming));
+        }
+        cursor.take(length - 6)?;
+        let mut tracks = Vec::with_capacity(count as usize);
+        while tracks.len() < count as usize && !cursor.is_empty() {
+            let kind = cursor.take(4)?;
+            let length = cursor.u32()? as usize;
+            let start = cursor.offset;
+            let chunk = cursor.take(length)?;
+            if kind == b"MTrk" {
+                tracks.push(Smf::parse_track(chunk, start)?);
+            }
+        }
+        if tracks.len() < count as usize {
+            return Err(cursor.error(SmfErrorKind::UnexpectedEof));
+        }
+        Ok(Smf {
+            format,
+            ppq: division,
+            tracks,
+        })
+    }
+    pub fn read<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        Smf::parse(&bytes).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+    }
+    fn parse_track(chunk: &[u8], start: usize) -> Result<Vec<(u64, SmfEvent)>, SmfError> {
+        let mut cursor = Cursor {
+            bytes: chunk,
+            offset: 0,
+        };
+        let relocate = |error: SmfError| SmfError::new(error.offset + start, error.kind);
+        let mut events = Vec::new();
+        let mut tick = 0u64;
+        let mut running = None;
+        loop {
+            if cursor.is_empty() {
+                return Err(relocate(cursor.error(SmfErrorKind::MissingEndOfTrack)));
+            }
+            tick += cursor.vlq().map_err(relocate)? as u64;
+            let status = match cursor.peek().map_err(relocate)? {
+                byte if byte & 0x80 != 0 => {
+                    cursor.offset += 1;
+                    byte
+                }
+                _ => running.ok_or(relocate(cursor.error(SmfErrorKind::MissingRunningStatus)))?,
+            };
+            let event = match status {
+                0x80..=0xef => {
+                    running = Some(status);
+                    let mut bytes = vec![status];
+                    for _ in 0..MidiMessage::data_len(status).unwrap_or(0) {
+                        bytes.push(cursor.data().map_err(relocate)?);
+                    }
+                    let (message, _) = MidiMessage::decode(&bytes)
+                        .expect("channel message with validated data bytes");
+                    SmfEvent::Midi(message)
+                }
+                0xf0 | 0xf7 => {
+                    running = None;
+                    let len = cursor.vlq().map_err(relocate)?;
+                    let data = cursor.take(len as usize).map_err(relocate)?;
+                    SmfEvent::SysEx(data.to_vec())
+                }
+                smf::META => {
+                    running = None;
+                    let kind = cursor.u8().map_err(relocate)?;
+                    let len = cursor.vlq().map_err(relocate)?;
+                    let data = cursor.take(len as usize).map_err(relocate)?;
+                    match (kind, data) {
+                        (smf::META_END_OF_TRACK, _) => break,
+                        (smf::META_TEMPO, &[a, b, c]) => {
+                            SmfEvent::Tempo(u32::from_be_bytes([0, a, b, c]))
+                        }
+                        (
+                            smf::META_TIME_SIGNATURE,
+                            &[numerator, denominator, clocks, thirty_seconds],
+                        ) => SmfEvent::TimeSignature {
+                            numerator,
+                            denominator,
+                            clocks,
+                            thirty_seconds,
+                        },
+                        (smf::META_KEY_SIGNATURE, &[fifths, minor]) => SmfEvent::KeySignature {
+                            fifths: fifths as i8,
+                            minor: minor != 0,
+                        },
+                        (smf::META_TRACK_NAME, name) => {
+                            SmfEvent::TrackName(String::from_utf8_lossy(name).into_owned())
+                        }
+                        (kind, data) => SmfEvent::Meta {
+                            kind,
+                            data: data.to_vec(),
+                        },
+                    }
+                }
+                status => {
+                    return Err(SmfError::new(
+                        start + cursor.offset - 1,
+                        SmfErrorKind::InvalidStatus(status),
+                    ))
+                }
+            };
+            events.push((tick, event));
+        }
+        Ok(events)
+    }
+    fn conductor_tracks(&self, track: usize) -> impl Iterator<Item = &(u64, SmfEvent)> {
+        self.tracks
+            .iter()
+            .enumerate()
+            .filter(move |&(i, _)| self.format != Format::Sequential || i == track)
+            .flat_map(|(_, events)| events)
+    }
+    pub fn to_duration(&self, ticks: u64, grid: Subdivision) -> Duration {
+        Duration::quantize(ticks as f64 / (4.0 * self.ppq as f64), grid)
+    }
+    pub fn tempo_map(&self, track: usize, grid: Subdivision) -> TempoMap {
+        let mut tempo_map = TempoMap::default();
+        for (tick, event) in self.conductor_tracks(track) {
+            if let SmfEvent::Tempo(micros) = event {
+                let tempo = Tempo::quarter(60e6 / (*micros).max(1) as f64);
+                tempo_map.set_tempo(self.to_duration(*tick, grid), tempo);
+            }
+        }
+        tempo_map
+    }
+    pub fn time_signatures(
+        &self,
+        track: usize,
+        grid: Subdivision,
+    ) -> Vec<(Duration, TimeSignature)> {
+        let mut time_signatures: Vec<(Duration, TimeSignature)> = Vec::new();
+        for (tick, event) in self.conductor_tracks(track) {
+            if let &SmfEvent::TimeSignature {
+                numerator,
+                denominator,
+                ..
+            } = event
+            {
+                let Some(time_signature) =
+                    TimeSignature::new(numerator as usize, Subdivision::new(denominator as usize))
+                else {
+                    continue;
+                };
+                let position = self.to_duration(*tick, grid);
+                time_signatures.retain(|(other, _)| *other != position);
+                time_signatures.push((position, time_signature));
+            }
+        }
+        time_signatures.sort_by_key(|(position, _)| *position);
+        time_signatures
+    }
+    pub fn key(&self, track: usize) -> Key {
+        self.conductor_tracks(track)
+            .find_map(|(_, event)| match *event {
+                SmfEvent::KeySignature { fifths, minor } => Some(Key::from_fifths(
+                    fifths as isize,
+                    if minor { Mode::MINOR } else { Mode::MAJOR },
+                )),
+                _ => None,
+            })
+            .unwrap_or(Key::C_MAJOR)
+    }
+    pub fn notes(&self, track: usize) -> Vec<SmfNote> {
+        let mut pending: Vec<SmfNote> = Vec::new();
+        let mut notes = Vec::new();
+        for (tick, event) in self.tracks.get(track).into_iter().flatten() {
+            match *event {
+                SmfEvent::Midi(MidiMessage::NoteOn {
+                    channel,
+                    note,
+                    velocity,
+                }) if velocity > 0 => pending.push(SmfNote {
+                    start: *tick,
+                    end: *tick,
+                    channel,
+                    note,
+                    velocity,
+                }),
+                SmfEvent::Midi(
+                    MidiMessage::NoteOn { channel, note, .. }
+                    | MidiMessage::NoteOff { channel, note, .. },
+                ) => {
+                    if let Some(i) = pending
+                        .iter()
+                        .position(|pending| pending.channel == channel && pending.note == note)
+                    {
+                        let mut note = pending.remove(i);
+                        note.end = *tick;
+                        notes.push(note);
+                    }
+                }
+                _ => {}
+            }
+        }
+        let end = self.tracks.get(track).and_then(|events| events.last());
+        let end = end.map_or(0, |(tick, _)| *tick);
+        notes.extend(pending.into_iter().map(|note| SmfNote { end, ..note }));
+        notes.sort_by_key(|note| (note.start, note.note));
+        notes
+    }
+    pub fn quantized_notes(&self, track: usize, grid: Subdivision) -> Vec<(Duration, Note)> {
+        let key = self.key(track);
+        self.notes(track)
+            .into_iter()
+            .map(|note| {
+                let start = self.to_duration(note.start, grid);
+                let value = self.to_duration(note.end, grid).saturating_sub(start);
+                let value = if value.is_zero() {
+                    grid.to_duration()
+                } else {
+                    value
+                };
+                (start, Note::new(key.spell_midi_note(note.note), value))
+            })
+            .collect()
+    }
+    pub fn track_voices(&self, track: usize, grid: Subdivision) -> Vec<Voice> {
+        let mut notes = self.quantized_notes(track, grid);
+        notes.sort_by(|(start, note), (other, next)| {
+            start.cmp(other).then(next.value.cmp(&note.value))
+        });
+        let mut voices: Vec<Voice> = Vec::new();
+        let mut i = 0;
+        while i < notes.len() {
+            let (start, Note { value, .. }) = notes[i];
+            let pitches: Vec<Pitch> = notes[i..]
+                .iter()
+                .take_while(|(other, note)| (*other, note.value) == (start, value))
+                .map(|(_, note)| note.pitch)
+                .collect();
+            i += pitches.len();
+            let event = match pitches.as_slice() {
+                &[pitch] => NoteEvent::single(pitch, value),
+                _ => NoteEvent::chord(pitches, value),
+            };
+            let index = voices
+                .iter()
+                .position(|voice| voice.duration() <= start)
+                .unwrap_or(voices.len());
+            if index == voices.len() {
+                voices.push(Voice::new());
+            }
+            let voice = &mut voices[index];
+            if voice.duration() < start {
+                voice.push(NoteEvent::rest(start - voice.duration()));
+            }
+            voice.push(event);
+        }
+        voices
+    }
+    pub fn voices(&self, grid: Subdivision) -> Vec<Voice> {
+        (0..self.tracks.len())
+            .flat_map(|track| self.track_voices(track, grid))
+            .collect()
+    }
+}
@@ -0,0 +1,52 @@
+use crate::note::Duration;
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Format {
+    Single = 0,
+    #[default]
+    Parallel = 1,
+    Sequential = 2,
+}
+
+impl Format {
+    pub const fn number(self) -> u16 {
+        self as u16
+    }
+    pub const fn from_number(number: u16) -> Option<Self> {
+        match number {
+            0 => Some(Format::Single),
+            1 => Some(Format::Parallel),
+            2 => Some(Format::Sequential),
+            _ => None,
+        }
+    }
+}
+
+pub(crate) const META: u8 = 0xff;
+pub(crate) const META_TRACK_NAME: u8 = 0x03;
+pub(crate) const META_END_OF_TRACK: u8 = 0x2f;
+pub(crate) const META_TEMPO: u8 = 0x51;
+pub(crate) const META_TIME_SIGNATURE: u8 = 0x58;
+pub(crate) const META_KEY_SIGNATURE: u8 = 0x59;
+
+pub(crate) fn to_ticks(duration: Duration, ppq: u16) -> u32 {
+    whole_notes_to_ticks(duration.as_f64(), ppq)
+}
+
+pub(crate) fn whole_notes_to_ticks(whole_notes: f64, ppq: u16) -> u32 {
+    (whole_notes * 4.0 * ppq as f64).round() as u32
+}
+
+pub(crate) fn write_vlq(bytes: &mut Vec<u8>, value: u32) {
+    let mut value = value.min(0x0fff_ffff);
+    let mut buffer = [0; 4];
+    let mut i = buffer.len() - 1;
+    buffer[i] = (value & 0x7f) as u8;
+    value >>= 7;
+    while value > 0 {
+        i -= 1;
+        buffer[i] = (value & 0x7f) as u8 | 0x80;
+        value >>= 7;
+    }
+    bytes.extend_from_slice(&buffer[i..]);
+}
@@ -0,0 +1,347 @@
+use crate::midi::smf::{self, Format};
+use crate::midi::MidiMessage;
+use crate::note::{Duration, MidiNote, MidiNoteRangeError, NoteEvent, Subdivision};
+use crate::scale::{Key, Mode};
+use crate::score::{Clef, Score, TempoMap, TimeSignature, Transition, Voice};
+use std::error::Error;
+use std::{fmt, io};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SmfWriteError {
+    NoteRange(MidiNoteRangeError),
+    KeySignature(isize),
+    Ppq(u16),
+}
+
+impl fmt::Display for SmfWriteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SmfWriteError::NoteRange(error) => error.fmt(f),
+            SmfWriteError::KeySignature(fifths) => {
+                write!(f, "key signature with {fifths} fifths is outside -7..=7")
+            }
+            SmfWriteError::Ppq(ppq) => {
+                write!(f, "{ppq} ticks per quarter note is outside 1..=32767")
+            }
+        }
+    }
+}
+
+impl Error for SmfWriteError {}
+
+impl From<MidiNoteRangeError> for SmfWriteError {
+    fn from(error: MidiNoteRangeError) -> Self {
+        SmfWriteError::NoteRange(error)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SmfTrack {
+    pub name: Option<String>,
+    pub channel: u8,
+    pub program: u8,
+    pub velocity: u8,
+    pub key: Option<Key>,
+    pub voices: Vec<Voice>,
+}
+
+impl SmfTrack {
+    pub const fn new(channel: u8, program: u8) -> Self {
+        SmfTrack {
+            name: None,
+            channel,
+            program,
+            velocity: 80,
+            key: None,
+            voices: Vec::new(),
+        }
+    }
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+    pub const fn with_velocity(mut self, velocity: u8) -> Self {
+        self.velocity = velocity;
+        self
+    }
+    pub const fn with_key(mut self, key: Key) -> Self {
+        self.key = Some(key);
+        self
+    }
+    pub fn with_voice(mut self, voice: Voice) -> Self {
+        self.voices.push(voice);
+        self
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SmfWriter {
+    pub format: Format,
+    pub ppq: u16,
+    pub tempo: TempoMap,
+    pub time_signatures: Vec<(Duration, TimeSignature)>,
+    pub key: Option<Key>,
+    pub tracks: Vec<SmfTrack>,
+}
+
+struct TimedEvent {
+    tick: u32,
+    priority: u8,
+    bytes: Vec<u8>,
+}
+
+impl TimedEvent {
+    fn meta(tick: u32, kind: u8, data: &[u8]) -> Self {
+        let mut bytes = vec![smf::META, kind];
+        smf::write_vlq(&mut bytes, data.len() as u32);
+        bytes.extend_from_slice(data);
+        TimedEvent {
+            tick,
+            priority: 0,
+            bytes,
+        }
+    }
+    fn key_signature(key: Key) -> Self {
+        let data = [key.fifths() as i8 as u8, (key.mode == Mode::MINOR) as u8];
+        TimedEvent::meta(0, smf::META_KEY_SIGNATURE, &data)
+    }
+    fn message(tick: u32, priority: u8, message: MidiMessage) -> Self {
+        TimedEvent {
+            tick,
+            priority,
+            bytes: message.encode(),
+        }
+    }
+}
+
+impl SmfWriter {
+    pub const DEFAULT_PPQ: u16 = 480;
+    pub fn new(format: Format, ppq: u16) -> Self {
+        SmfWriter {
+            format,
+            ppq,
+            tempo: TempoMap::default(),
+            time_signatures: vec![(Duration::ZERO, TimeSignature::common_time())],
+            key: None,
+            tracks: Vec::new(),
+        }
+    }
+    pub fn from_score(score: &Score, format: Format) -> Self {
+        let key = score.parts.first().map(|part| part.key);
+        let shared = score.parts.iter().all(|part| Some(part.key) == key);
+        let mut channels = (0..16u8).filter(|&channel| channel != 9);
+        let tracks = score
+            .parts
+            .iter()
+            .map(|part| {
+                let percussion = part
+                    .staves
+                    .iter()
+                    .any(|staff| staff.clef == Clef::Percussion);
+                let channel = if percussion {
+                    9
+                } else {
+                    channels.next().unwrap_or(15)
+                };
+                SmfTrack {
+                    name: Some(part.name.clone()),
+                    key: (!shared).then_some(part.key),
+                    voices: part.voices().cloned().collect(),
+                    ..SmfTrack::new(channel, part.instrument.program)
+                }
+            })
+            .collect();
+        SmfWriter {
+            tempo: score.tempo.clone(),
+            time_signatures: score.time_signatures.clone(),
+            key: key.filter(|_| shared),
+            tracks,
+            ..SmfWriter::new(format, SmfWriter::DEFAULT_PPQ)
+        }
+    }
+    pub fn with_tempo(mut self, tempo: impl Into<TempoMap>) -> Self {
+        self.tempo = tempo.into();
+        self
+    }
+    pub fn with_time_signature(mut self, time_signature: TimeSignature) -> Self {
+        self.time_signatures = vec![(Duration::ZERO, time_signature)];
+        self
+    }
+    pub const fn with_key(mut self, key: Key) -> Self {
+        self.key = Some(key);
+        self
+    }
+    pub fn with_track(mut self, track: SmfTrack) -> Self {
+        self.tracks.push(track);
+        self
+    }
+    fn ticks(&self, duration: Duration) -> u32 {
+        smf::to_ticks(duration, self.ppq)
+    }
+    fn tempo_event(&self, position: Duration) -> TimedEvent {
+        let tempo = self.tempo.tempo_at(position);
+        let micros = (1e6 / (tempo.whole_notes_per_second() * 4.0)).round() as u32;
+        let micros = micros.clamp(1, 0xff_ffff).to_be_bytes();
+        TimedEvent::meta(self.ticks(position), smf::META_TEMPO, &micros[1..])
+    }
+    fn conductor_events(&self) -> Vec<TimedEvent> {
+        let mut events = Vec::new();
+        for (position, time_signature) in &self.time_signatures {
+            let beat = time_signature.beats().first().copied();
+            let clocks = beat.map_or(24.0, |beat| beat.as_f64() * 96.0).round() as u8;
+            let data = [
+                time_signature.numerator().min(255) as u8,
+                time_signature.unit().exponent() as u8,
+                clocks,
+                8,
+            ];
+            events.push(TimedEvent::meta(
+                self.ticks(*position),
+                smf::META_TIME_SIGNATURE,
+                &data,
+            ));
+        }
+        if let Some(key) = self.key {
+            events.push(TimedEvent::key_signature(key));
+        }
+        let changes = self.tempo.changes();
+        for (i, change) in changes.iter().enumerate() {
+            if change.transition == Transition::Gradual && i > 0 {
+                let step = Subdivision::X16TH_NOTE.to_duration();
+                let mut position = changes[i - 1].position + step;
+                while position < change.position {
+                    events.push(self.tempo_event(position));
+                    position += step;
+                }
+            }
+            events.push(self.tempo_event(change.position));
+        }
+        events
+    }
+    fn track_events(&self, track: &SmfTrack) -> Result<Vec<TimedEvent>, SmfWriteError> {
+        let channel = track.channel & 0x0f;
+        let mut events = vec![TimedEvent::message(
+            0,
+            1,
+            MidiMessage::ProgramChange {
+                channel,
+                program: track.program,
+            },
+        )];
+        if let Some(name) = &track.name {
+            events.push(TimedEvent::meta(0, smf::META_TRACK_NAME, name.as_bytes()));
+        }
+        if let Some(key) = track.key {
+            events.push(TimedEvent::key_signature(key));
+        }
+        for voice in &track.voices {
+            let voice = Voice::from(NoteEvent::merge_ties(voice.events().iter().cloned()));
+            for (start, length, event) in voice.iter().flat_map(|(offset, event)| {
+                event
+                    .leaves()
+                    .into_iter()
+                    .map(move |(start, length, leaf)| (offset.as_f64() + start, length, leaf))
+            }) {
+                let on = smf::whole_notes_to_ticks(start, self.ppq);
+                let off = smf::whole_notes_to_ticks(start + length, self.ppq);
+                for &pitch in event.pitches() {
+                    let note = MidiNote::try_from_pitch(pitch)?;
+                    let velocity = track.velocity.clamp(1, 127);
+                    events.push(TimedEvent::message(
+                        off,
+                        2,
+                        MidiMessage::NoteOff {
+                            channel,
+                            note,
+                            velocity: 0,
+                        },
+                    ));
+                    events.push(TimedEvent::message(
+                        on,
+                        3,
+                        MidiMessage::NoteOn {
+                            channel,
+                            note,
+                            velocity,
+                        },
+                    ));
+                }
+            }
+        }
+        Ok(events)
+    }
+    fn encode_track(events: Vec<TimedEvent>) -> Vec<u8> {
+        let mut events = events;
+        events.sort_by_key(|event| (event.tick, event.priority));
+        let mut data = Vec::new();
+        let mut tick = 0;
+        for event in events {
+            smf::write_vlq(&mut data, event.tick - tick);
+            data.extend_from_slice(&event.bytes);
+            tick = event.tick;
+        }
+        data.extend_from_slice(&[0, smf::META, smf::META_END_OF_TRACK, 0]);
+        let mut chunk = b"MTrk".to_vec();
+        chunk.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        chunk.extend_from_slice(&data);
+        chunk
+    }
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SmfWriteError> {
+        if !(1..=0x7fff).contains(&self.ppq) {
+            return Err(SmfWriteError::Ppq(self.ppq));
+        }
+        let keys = self
+            .key
+            .iter()
+            .chain(self.tracks.iter().flat_map(|track| &track.key));
+        if let Some(key) = keys
+            .into_iter()
+            .find(|key| !(-7..=7).contains(&key.fifths()))
+        {
+            return Err(SmfWriteError::KeySignature(key.fifths()));
+        }
+        let tracks: Vec<Vec<TimedEvent>> = match self.format {
+            Format::Single => {
+                let mut events = self.conductor_events();
+                for track in &self.tracks {
+                    events.extend(self.track_events(track)?);
+                }
+                vec![events]
+            }
+            Format::Parallel => [Ok(self.conductor_events())]
+                .into_iter()
+                .chain(self.tracks.iter().map(|track| self.track_events(track)))
+                .collect::<Result<_, _>>()?,
+            Format::Sequential => self
+                .tracks
+                .iter()
+                .map(|track| {
+                    let mut events = self.conductor_events();
+                    events.extend(self.track_events(track)?);
+                    Ok(events)
+                })
+                .collect::<Result<_, SmfWriteError>>()?,
+        };
+        let mut bytes = b"MThd".to_vec();
+        bytes.extend_from_slice(&6u32.to_be_bytes());
+        bytes.extend_from_slice(&self.format.number().to_be_bytes());
+        bytes.extend_from_slice(&(tracks.len() as u16).to_be_bytes());
+        bytes.extend_from_slice(&self.ppq.to_be_bytes());
+        for track in tracks {
+            bytes.extend(SmfWriter::encode_track(track));
+        }
+        Ok(bytes)
+    }
+    pub fn write<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        let bytes = self
+            .to_bytes()
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+        writer.write_all(&bytes)
+    }
+}
+
+impl Default for SmfWriter {
+    fn default() -> Self {
+        SmfWriter::new(Format::default(), SmfWriter::DEFAULT_PPQ)
+    }
+}
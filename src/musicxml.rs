@@ -0,0 +1,7 @@
+mod harmony;
+mod reader;
+mod writer;
+mod zip;
+
+pub use reader::{MusicXml, MusicXmlError, MusicXmlErrorKind, MusicXmlWarning};
+pub use writer::MusicXmlWriter;
@@ -0,0 +1,80 @@
+use crate::chord::{ChordQuality, ChordSymbol, DegreeKind};
+use crate::xml::XmlWriter;
+
+pub(crate) const fn kind(quality: ChordQuality) -> &'static str {
+    match quality {
+        ChordQuality::Major => "major",
+        ChordQuality::Minor => "minor",
+        ChordQuality::Augmented => "augmented",
+        ChordQuality::Diminished => "diminished",
+        ChordQuality::Dominant => "dominant",
+        ChordQuality::MajorSeventh => "major-seventh",
+        ChordQuality::MinorSeventh => "minor-seventh",
+        ChordQuality::DiminishedSeventh => "diminished-seventh",
+        ChordQuality::AugmentedSeventh => "augmented-seventh",
+        ChordQuality::HalfDiminished => "half-diminished",
+        ChordQuality::MinorMajorSeventh => "major-minor",
+        ChordQuality::MajorSixth => "major-sixth",
+        ChordQuality::MinorSixth => "minor-sixth",
+        ChordQuality::DominantNinth => "dominant-ninth",
+        ChordQuality::MajorNinth => "major-ninth",
+        ChordQuality::MinorNinth => "minor-ninth",
+        ChordQuality::DominantEleventh => "dominant-11th",
+        ChordQuality::MajorEleventh => "major-11th",
+        ChordQuality::MinorEleventh => "minor-11th",
+        ChordQuality::DominantThirteenth => "dominant-13th",
+        ChordQuality::MajorThirteenth => "major-13th",
+        ChordQuality::MinorThirteenth => "minor-13th",
+        ChordQuality::SuspendedSecond => "suspended-second",
+        ChordQuality::SuspendedFourth => "suspended-fourth",
+        ChordQuality::Power => "power",
+    }
+}
+
+pub(crate) fn quality(kind: &str) -> Option<ChordQuality> {
+    ChordQuality::ALL
+        .into_iter()
+        .find(|&quality| self::kind(quality) == kind)
+}
+
+pub(crate) const fn degree_type(kind: DegreeKind) -> &'static str {
+    match kind {
+        DegreeKind::Add => "add",
+        DegreeKind::Alter => "alter",
+        DegreeKind::Subtract => "subtract",
+    }
+}
+
+pub(crate) fn write(xml: &mut XmlWriter, chord: &ChordSymbol, offset: usize) {
+    xml.open("harmony");
+    xml.open("root");
+    xml.text("root-step", chord.root.base.name());
+    if chord.root.accidental.pitch_shift() != 0 {
+        xml.text("root-alter", chord.root.accidental.pitch_shift());
+    }
+    xml.close("root");
+    xml.line(&format!(
+        "<kind text=\"{}\">{}</kind>",
+        crate::xml::escape(chord.quality.symbol()),
+        kind(chord.quality)
+    ));
+    if let Some(bass) = chord.bass {
+        xml.open("bass");
+        xml.text("bass-step", bass.base.name());
+        if bass.accidental.pitch_shift() != 0 {
+            xml.text("bass-alter", bass.accidental.pitch_shift());
+        }
+        xml.close("bass");
+    }
+    for degree in &chord.degrees {
+        xml.open("degree");
+        xml.text("degree-value", degree.value);
+        xml.text("degree-alter", degree.accidental.pitch_shift());
+        xml.text("degree-type", degree_type(degree.kind));
+        xml.close("degree");
+    }
+    if offset > 0 {
+        xml.text("offset", offset);
+    }
+    xml.close("harmony");
+}
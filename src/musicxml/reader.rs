@@ -0,0 +1,1014 @@
+use crate::chord::{ChordDegree, ChordSymbol, DegreeKind};
+use crate::musicxml::{harmony, zip};
+use crate::note::{
+    Accidental, Diatonic, Duration, NoteEvent, Octave, Pitch, PitchClass, Subdivision, Tie, Tuplet,
+};
+use crate::ratio::Ratio;
+use crate::scale::{Key, Mode};
+use crate::score::{Clef, Instrument, Part, Score, Staff, Tempo, TimeSignature, Voice};
+use crate::xml::{Element, XmlParser};
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+const TYPES: [&str; 11] = [
+    "whole", "half", "quarter", "eighth", "16th", "32nd", "64th", "128th", "256th", "512th",
+    "1024th",
+];
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MusicXmlErrorKind {
+    InvalidXml(String),
+    InvalidArchive,
+    MissingRootfile,
+    UnsupportedRoot(String),
+    MissingElement(&'static str),
+    InvalidValue { element: String, value: String },
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MusicXmlError {
+    pub offset: usize,
+    pub kind: MusicXmlErrorKind,
+}
+
+impl MusicXmlError {
+    pub const fn new(offset: usize, kind: MusicXmlErrorKind) -> Self {
+        MusicXmlError { offset, kind }
+    }
+}
+
+impl fmt::Display for MusicXmlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            MusicXmlErrorKind::InvalidXml(message) => write!(f, "invalid XML: {message}")?,
+            MusicXmlErrorKind::InvalidArchive => f.write_str("invalid compressed archive")?,
+            MusicXmlErrorKind::MissingRootfile => {
+                f.write_str("archive has no MusicXML rootfile")?
+            }
+            MusicXmlErrorKind::UnsupportedRoot(name) => {
+                write!(f, "unsupported root element `<{name}>`")?
+            }
+            MusicXmlErrorKind::MissingElement(name) => write!(f, "missing `<{name}>`")?,
+            MusicXmlErrorKind::InvalidValue { element, value } => {
+                write!(f, "invalid `<{element}>` value `{value}`")?
+            }
+        }
+        write!(f, " at byte offset {}", self.offset)
+    }
+}
+
+impl Error for MusicXmlError {}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MusicXmlWarning {
+    pub offset: usize,
+    pub part: Option<String>,
+    pub measure: Option<String>,
+    pub element: String,
+    pub value: Option<String>,
+}
+
+impl fmt::Display for MusicXmlWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unsupported `<{}>`", self.element)?;
+        if let Some(value) = &self.value {
+            write!(f, " value `{value}`")?;
+        }
+        if let Some(part) = &self.part {
+            write!(f, " in part {part}")?;
+        }
+        if let Some(measure) = &self.measure {
+            write!(f, ", measure {measure}")?;
+        }
+        write!(f, " at byte offset {}", self.offset)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MusicXml {
+    pub score: Score,
+    pub warnings: Vec<MusicXmlWarning>,
+}
+
+struct Group {
+    tuplet: Tuplet,
+    events: Vec<NoteEvent>,
+    marked: bool,
+}
+
+struct VoiceState {
+    id: String,
+    staff: usize,
+    events: Vec<NoteEvent>,
+    end: Ratio,
+    group: Option<Group>,
+}
+
+impl VoiceState {
+    fn last_mut(&mut self) -> Option<&mut NoteEvent> {
+        let mut last = match &mut self.group {
+            Some(group) => group.events.last_mut(),
+            None => self.events.last_mut(),
+        };
+        while let Some(NoteEvent::Tuplet { events, .. }) = last {
+            last = events.last_mut();
+        }
+        last
+    }
+    fn flush(&mut self) -> bool {
+        let Some(group) = self.group.take() else {
+            return true;
+        };
+        match NoteEvent::tuplet(group.tuplet, group.events.clone()) {
+            Some(tuplet) => {
+                self.events.push(tuplet);
+                true
+            }
+            None => {
+                self.events.extend(group.events.into_iter().map(|event| {
+                    let value = Duration::quantize(
+                        event.value().as_f64() * group.tuplet.ratio(),
+                        Subdivision::new(TYPES.len() - 1),
+                    );
+                    event.with_value(value)
+                }));
+                false
+            }
+        }
+    }
+}
+
+struct Importer {
+    warnings: Vec<MusicXmlWarning>,
+    part: Option<String>,
+    measure: Option<String>,
+}
+
+impl Importer {
+    fn warn(&mut self, element: &Element, value: Option<&str>) {
+        let warning = MusicXmlWarning {
+            offset: element.offset,
+            part: self.part.clone(),
+            measure: self.measure.clone(),
+            element: element.name.clone(),
+            value: value.map(str::to_string),
+        };
+        if !self.warnings.iter().any(|other| {
+            other.part == warning.part
+                && other.element == warning.element
+                && other.value == warning.value
+        }) {
+            self.warnings.push(warning);
+        }
+    }
+    fn warn_others(&mut self, element: &Element, supported: &[&str]) {
+        for child in &element.children {
+            if !supported.contains(&child.name.as_str()) {
+                self.warn(child, None);
+            }
+        }
+    }
+    fn number<T: FromStr>(element: &Element, name: &'static str) -> Result<T, MusicXmlError> {
+        let child = element.child(name).ok_or(MusicXmlError::new(
+            element.offset,
+            MusicXmlErrorKind::MissingElement(name),
+        ))?;
+        child.text.trim().parse().map_err(|_| {
+            MusicXmlError::new(
+                child.offset,
+                MusicXmlErrorKind::InvalidValue {
+                    element: name.to_string(),
+                    value: child.text.trim().to_string(),
+                },
+            )
+        })
+    }
+    fn optional<T: FromStr>(
+        element: &Element,
+        name: &'static str,
+    ) -> Result<Option<T>, MusicXmlError> {
+        match element.child(name) {
+            Some(_) => Importer::number(element, name).map(Some),
+            None => Ok(None),
+        }
+    }
+    fn step(element: &Element, name: &'static str) -> Result<Diatonic, MusicXmlError> {
+        let child = element.child(name).ok_or(MusicXmlError::new(
+            element.offset,
+            MusicXmlErrorKind::MissingElement(name),
+        ))?;
+        Diatonic::from_str(child.text.trim()).map_err(|_| {
+            MusicXmlError::new(
+                child.offset,
+                MusicXmlErrorKind::InvalidValue {
+                    element: name.to_string(),
+                    value: child.text.trim().to_string(),
+                },
+            )
+        })
+    }
+    fn alter(
+        &mut self,
+        element: &Element,
+        name: &'static str,
+    ) -> Result<Accidental, MusicXmlError> {
+        let alter: f64 = Importer::optional(element, name)?.unwrap_or(0.0);
+        if alter.fract() != 0.0 {
+            if let Some(child) = element.child(name) {
+                self.warn(child, Some(child.text.trim()));
+            }
+        }
+        Ok(Accidental::new(alter.round() as isize))
+    }
+    fn duration(element: &Element, divisions: u64) -> Result<Ratio, MusicXmlError> {
+        let duration: u64 = Importer::number(element, "duration")?;
+        divisions
+            .checked_mul(4)
+            .and_then(|whole| Ratio::checked_new(duration, whole))
+            .ok_or_else(|| Importer::overflow(element, "duration"))
+    }
+    fn overflow(element: &Element, name: &str) -> MusicXmlError {
+        let child = element.child(name).unwrap_or(element);
+        MusicXmlError::new(
+            child.offset,
+            MusicXmlErrorKind::InvalidValue {
+                element: child.name.clone(),
+                value: child.text.trim().to_string(),
+            },
+        )
+    }
+    fn key(&mut self, element: &Element) -> Result<Option<Key>, MusicXmlError> {
+        if element.child("fifths").is_none() {
+            self.warn(element, Some("non-traditional"));
+            return Ok(None);
+        }
+        let fifths = Importer::number(element, "fifths")?;
+        let mode = match element.child("mode") {
+            Some(mode) => match Mode::from_str(mode.text.trim()) {
+                Ok(parsed) => parsed,
+                Err(_) => {
+                    self.warn(mode, Some(mode.text.trim()));
+                    Mode::MAJOR
+                }
+            },
+            None => Mode::MAJOR,
+        };
+        self.warn_others(element, &["cancel", "fifths", "mode"]);
+        Ok(Some(Key::from_fifths(fifths, mode)))
+    }
+    fn time(&mut self, element: &Element) -> Result<Option<TimeSignature>, MusicXmlError> {
+        if element.child("senza-misura").is_some() || element.children_named("beats").count() != 1 {
+            self.warn(element, None);
+            return Ok(None);
+        }
+        let beats = element.child("beats").map_or("", |beats| beats.text.trim());
+        let groups: Result<Vec<usize>, _> =
+            beats.split('+').map(|group| group.trim().parse()).collect();
+        let unit: usize = Importer::number(element, "beat-type")?;
+        match (groups, unit.is_power_of_two()) {
+            (Ok(groups), true) => match TimeSignature::additive(
+                groups,
+                Subdivision::new(unit.trailing_zeros() as usize),
+            ) {
+                Some(time_signature) => Ok(Some(time_signature)),
+                None => {
+                    self.warn(element, Some(&format!("{beats}/{unit}")));
+                    Ok(None)
+                }
+            },
+            _ => {
+                self.warn(element, Some(&format!("{beats}/{unit}")));
+                Ok(None)
+            }
+        }
+    }
+    fn clef(&mut self, element: &Element) -> Result<Option<Clef>, MusicXmlError> {
+        let sign = element.child_text("sign").unwrap_or_default();
+        let line = Importer::optional(element, "line")?.unwrap_or(match sign {
+            "F" => 4,
+            "C" => 3,
+            _ => 2,
+        });
+        let octave_change = Importer::optional(element, "clef-octave-change")?.unwrap_or(0);
+        let sign = match sign {
+            "percussion" => 'P',
+            sign => sign.chars().next().unwrap_or_default(),
+        };
+        let clef = Clef::from_sign(sign, line, octave_change);
+        if clef.is_none() {
+            self.warn(element, element.child_text("sign"));
+        }
+        Ok(clef)
+    }
+    fn tempo(&mut self, element: &Element) -> Result<Option<Tempo>, MusicXmlError> {
+        let unit = element.child_text("beat-unit").unwrap_or_default();
+        let Some(exponent) = TYPES.iter().position(|name| *name == unit) else {
+            self.warn(element, Some(unit));
+            return Ok(None);
+        };
+        let Some(bpm) = Importer::optional::<f64>(element, "per-minute")? else {
+            self.warn(element, None);
+            return Ok(None);
+        };
+        let dots = element.children_named("beat-unit-dot").count();
+        Ok(Some(Tempo::new(
+            bpm,
+            Duration::dotted(Subdivision::new(exponent), dots),
+        )))
+    }
+    fn harmony(&mut self, element: &Element) -> Result<Option<ChordSymbol>, MusicXmlError> {
+        let (Some(root), Some(kind)) = (element.child("root"), element.child("kind")) else {
+            self.warn(element, None);
+            return Ok(None);
+        };
+        let Some(quality) = harmony::quality(kind.text.trim()) else {
+            self.warn(kind, Some(kind.text.trim()));
+            return Ok(None);
+        };
+        let root = PitchClass::new(
+            Importer::step(root, "root-step")?,
+            self.alter(root, "root-alter")?,
+        );
+        let mut chord = ChordSymbol::new(root, quality);
+        if let Some(bass) = element.child("bass") {
+            chord.bass = Some(PitchClass::new(
+                Importer::step(bass, "bass-step")?,
+                self.alter(bass, "bass-alter")?,
+            ));
+        }
+        for degree in element.children_named("degree") {
+            let kind = match degree.child_text("degree-type") {
+                Some("add") => DegreeKind::Add,
+                Some("alter") => DegreeKind::Alter,
+                Some("subtract") => DegreeKind::Subtract,
+                kind => {
+                    self.warn(degree, kind);
+                    continue;
+                }
+            };
+            let value = Importer::number(degree, "degree-value")?;
+            let accidental = self.alter(degree, "degree-alter")?;
+            chord
+                .degrees
+                .push(ChordDegree::new(value, accidental, kind));
+        }
+        self.warn_others(
+            element,
+            &[
+                "root",
+                "kind",
+                "bass",
+                "degree",
+                "offset",
+                "staff",
+                "inversion",
+            ],
+        );
+        Ok(Some(chord))
+    }
+}
+
+struct PartState {
+    divisions: u64,
+    cursor: Ratio,
+    start: Ratio,
+    length: Ratio,
+    voices: Vec<VoiceState>,
+    staves: usize,
+    clefs: Vec<Clef>,
+    key: Option<Key>,
+    chords: Vec<(Duration, ChordSymbol)>,
+}
+
+impl PartState {
+    fn extend(&mut self, element: &Element) -> Result<(), MusicXmlError> {
+        let length = self
+            .cursor
+            .checked_saturating_sub(self.start)
+            .ok_or_else(|| Importer::overflow(element, "duration"))?;
+        self.length = self.length.max(length);
+        Ok(())
+    }
+}
+
+impl MusicXml {
+    pub fn parse(text: &str) -> Result<Self, MusicXmlError> {
+        let root = XmlParser::parse(text).map_err(|(offset, message)| {
+            MusicXmlError::new(offset, MusicXmlErrorKind::InvalidXml(message))
+        })?;
+        let parts: Vec<(String, Vec<(&Element, &Element)>)> = match root.name.as_str() {
+            "score-partwise" => root
+                .children_named("part")
+                .map(|part| {
+                    let id = part.attribute("id").unwrap_or_default().to_string();
+                    (id, part.children_named("measure").map(|m| (m, m)).collect())
+                })
+                .collect(),
+            "score-timewise" => {
+                let mut parts: Vec<(String, Vec<(&Element, &Element)>)> = Vec::new();
+                for measure in root.children_named("measure") {
+                    for part in measure.children_named("part") {
+                        let id = part.attribute("id").unwrap_or_default();
+                        match parts.iter_mut().find(|(other, _)| other == id) {
+                            Some((_, measures)) => measures.push((measure, part)),
+                            None => parts.push((id.to_string(), vec![(measure, part)])),
+                        }
+                    }
+                }
+                parts
+            }
+            name => {
+                return Err(MusicXmlError::new(
+                    root.offset,
+                    MusicXmlErrorKind::UnsupportedRoot(name.to_string()),
+                ))
+            }
+        };
+        let mut importer = Importer {
+            warnings: Vec::new(),
+            part: None,
+            measure: None,
+        };
+        let mut score = Score::new(TimeSignature::common_time(), Tempo::default());
+        let mut time_signatures = Vec::new();
+        for child in &root.children {
+            match child.name.as_str() {
+                "work" => {
+                    score.title = child.child_text("work-title").map(str::to_string);
+                    importer.warn_others(child, &["work-title"]);
+                }
+                "movement-title" if score.title.is_none() => {
+                    score.title = Some(child.text.trim().to_string());
+                }
+                "identification" => {
+                    for creator in child.children_named("creator") {
+                        match creator.attribute("type") {
+                            Some("composer") => {
+                                score.composer = Some(creator.text.trim().to_string())
+                            }
+                            kind => importer.warn(creator, kind),
+                        }
+                    }
+                    importer.warn_others(child, &["creator"]);
+                }
+                "part-list" | "part" | "measure" | "movement-title" => {}
+                _ => importer.warn(child, None),
+            }
+        }
+        let part_list = root.child("part-list").ok_or(MusicXmlError::new(
+            root.offset,
+            MusicXmlErrorKind::MissingElement("part-list"),
+        ))?;
+        importer.warn_others(part_list, &["score-part"]);
+        for (id, measures) in &parts {
+            let header = part_list
+                .children_named("score-part")
+                .find(|part| part.attribute("id") == Some(id));
+            let name = header
+                .and_then(|part| part.child_text("part-name"))
+                .unwrap_or(id);
+            let instrument = header.and_then(|part| part.child("score-instrument"));
+            let instrument = instrument
+                .and_then(|instrument| instrument.child_text("instrument-name"))
+                .unwrap_or(name);
+            let program = header
+                .and_then(|part| part.child("midi-instrument"))
+                .and_then(|midi| midi.child_text("midi-program"))
+                .and_then(|program| program.parse::<u8>().ok())
+                .map_or(0, |program| program.saturating_sub(1));
+            importer.part = Some(id.clone());
+            let state = importer.part_state(measures, &mut score, &mut time_signatures)?;
+            importer.measure = None;
+            let mut part = Part::new(
+                name,
+                Instrument::new(instrument, program),
+                state.key.unwrap_or(Key::C_MAJOR),
+            );
+            part.chords = state.chords;
+            let staves = state
+                .voices
+                .iter()
+                .map(|voice| voice.staff + 1)
+                .max()
+                .unwrap_or(1);
+            for staff in 0..state.staves.max(staves) {
+                let mut voices: Vec<Voice> = Vec::new();
+                for voice in state.voices.iter().filter(|voice| voice.staff == staff) {
+                    voices.push(voice.events.iter().cloned().collect());
+                }
+                part.staves.push(Staff {
+                    clef: state.clefs.get(staff).copied().unwrap_or_default(),
+                    voices,
+                });
+            }
+            score.parts.push(part);
+        }
+        for (position, time_signature) in time_signatures {
+            score.set_time_signature(position, time_signature);
+        }
+        Ok(MusicXml {
+            score,
+            warnings: importer.warnings,
+        })
+    }
+    pub fn parse_mxl(bytes: &[u8]) -> Result<Self, MusicXmlError> {
+        let entries =
+            zip::read_zip(bytes).ok_or(MusicXmlError::new(0, MusicXmlErrorKind::InvalidArchive))?;
+        let container = entries
+            .iter()
+            .find(|(name, _)| name == "META-INF/container.xml")
+            .and_then(|(_, data)| XmlParser::parse(&String::from_utf8_lossy(data)).ok());
+        let rootfile = container
+            .as_ref()
+            .and_then(|container| container.child("rootfiles"))
+            .and_then(|rootfiles| rootfiles.child("rootfile"))
+            .and_then(|rootfile| rootfile.attribute("full-path"));
+        let (_, data) = entries
+            .iter()
+            .find(|(name, _)| match rootfile {
+                Some(rootfile) => name == rootfile,
+                None => {
+                    !name.starts_with("META-INF/")
+                        && (name.ends_with(".xml") || name.ends_with(".musicxml"))
+                }
+            })
+            .ok_or(MusicXmlError::new(0, MusicXmlErrorKind::MissingRootfile))?;
+        MusicXml::parse(&String::from_utf8_lossy(data))
+    }
+    pub fn parse_bytes(bytes: &[u8]) -> Result<Self, MusicXmlError> {
+        match bytes.starts_with(b"PK\x03\x04") {
+            true => MusicXml::parse_mxl(bytes),
+            false => MusicXml::parse(&String::from_utf8_lossy(bytes)),
+        }
+    }
+    pub fn read<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        MusicXml::parse_bytes(&bytes)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+    }
+}
+
+impl Importer {
+    fn part_state(
+        &mut self,
+        measures: &[(&Element, &Element)],
+        score: &mut Score,
+        time_signatures: &mut Vec<(Duration, TimeSignature)>,
+    ) -> Result<PartState, MusicXmlError> {
+        let mut state = PartState {
+            divisions: 1,
+            cursor: Ratio::ZERO,
+            start: Ratio::ZERO,
+            length: Ratio::ZERO,
+            voices: Vec::new(),
+            staves: 1,
+            clefs: Vec::new(),
+            key: None,
+            chords: Vec::new(),
+        };
+        for (measure, content) in measures {
+            self.measure = measure.attribute("number").map(str::to_string);
+            state.cursor = state.start;
+            state.length = Ratio::ZERO;
+            for element in &content.children {
+                match element.name.as_str() {
+                    "attributes" => self.attributes(element, &mut state, time_signatures)?,
+                    "note" => self.note(element, &mut state)?,
+                    "backup" => {
+                        let duration = Importer::duration(element, state.divisions)?;
+                        state.cursor = state
+                            .cursor
+                            .checked_saturating_sub(duration)
+                            .ok_or_else(|| Importer::overflow(element, "duration"))?
+                            .max(state.start);
+                    }
+                    "forward" => {
+                        state.cursor = state
+                            .cursor
+                            .checked_add(Importer::duration(element, state.divisions)?)
+                            .ok_or_else(|| Importer::overflow(element, "duration"))?;
+                        state.extend(element)?;
+                    }
+                    "direction" => {
+                        let offset: i64 = Importer::optional(element, "offset")?.unwrap_or(0);
+                        let position = Importer::shift(state.cursor, offset, state.divisions)
+                            .ok_or_else(|| Importer::overflow(element, "offset"))?;
+                        for kind in element.children_named("direction-type") {
+                            for child in &kind.children {
+                                match child.name.as_str() {
+                                    "metronome" if element.child("sound").is_none() => {
+                                        if let Some(tempo) = self.tempo(child)? {
+                                            score.tempo.set_tempo(position.to_duration(), tempo);
+                                        }
+                                    }
+                                    "metronome" => {}
+                                    _ => self.warn(child, None),
+                                }
+                            }
+                        }
+                        if let Some(sound) = element.child("sound") {
+                            self.sound(sound, position, score)?;
+                        }
+                        self.warn_others(
+                            element,
+                            &["direction-type", "sound", "offset", "staff", "voice"],
+                        );
+                    }
+                    "sound" => self.sound(element, state.cursor, score)?,
+                    "harmony" => {
+                        let offset: i64 = Importer::optional(element, "offset")?.unwrap_or(0);
+                        let position = Importer::shift(state.cursor, offset, state.divisions)
+                            .ok_or_else(|| Importer::overflow(element, "offset"))?;
+                        if let Some(chord) = self.harmony(element)? {
+                            state.chords.push((position.to_duration(), chord));
+                        }
+                    }
+                    _ => self.warn(element, None),
+                }
+            }
+            state.start = state
+                .start
+                .checked_add(state.length)
+                .ok_or_else(|| Importer::overflow(measure, "duration"))?;
+        }
+        for voice in &mut state.voices {
+            if !voice.flush() {
+                self.measure = None;
+                let offset = measures.last().map_or(0, |(measure, _)| measure.offset);
+                self.warnings.push(MusicXmlWarning {
+                    offset,
+                    part: self.part.clone(),
+                    measure: None,
+                    element: "time-modification".to_string(),
+                    value: Some(format!("incomplete in voice {}", voice.id)),
+                });
+            }
+        }
+        state.chords.sort_by_key(|(position, _)| *position);
+        Ok(state)
+    }
+    fn shift(position: Ratio, offset: i64, divisions: u64) -> Option<Ratio> {
+        let delta = Ratio::checked_new(offset.unsigned_abs(), divisions.checked_mul(4)?)?;
+        if offset < 0 {
+            position.checked_saturating_sub(delta)
+        } else {
+            position.checked_add(delta)
+        }
+    }
+    fn sound(
+        &mut self,
+        element: &Element,
+        position: Ratio,
+        score: &mut Score,
+    ) -> Result<(), MusicXmlError> {
+        if let Some(tempo) = element.attribute("tempo") {
+            match tempo.trim().parse::<f64>() {
+                Ok(bpm) if bpm > 0.0 => score
+                    .tempo
+                    .set_tempo(position.to_duration(), Tempo::quarter(bpm)),
+                _ => self.warn(element, Some(tempo)),
+            }
+        }
+        if element.attributes.iter().any(|(key, _)| key != "tempo") || !element.children.is_empty()
+        {
+            self.warn(element, None);
+        }
+        Ok(())
+    }
+    fn attributes(
+        &mut self,
+        element: &Element,
+        state: &mut PartState,
+        time_signatures: &mut Vec<(Duration, TimeSignature)>,
+    ) -> Result<(), MusicXmlError> {
+        for child in &element.children {
+            match child.name.as_str() {
+                "divisions" => {
+                    state.divisions = Importer::number(element, "divisions")?;
+                    if state.divisions == 0 {
+                        return Err(MusicXmlError::new(
+                            child.offset,
+                            MusicXmlErrorKind::InvalidValue {
+                                element: "divisions".to_string(),
+                                value: "0".to_string(),
+                            },
+                        ));
+                    }
+                }
+                "key" => match (self.key(child)?, state.key) {
+                    (Some(key), None) => state.key = Some(key),
+                    (Some(key), Some(current)) if key != current => {
+                        self.warn(child, Some(&key.to_string()))
+                    }
+                    _ => {}
+                },
+                "time" => {
+                    if let Some(time_signature) = self.time(child)? {
+                        let position = state.cursor.to_duration();
+                        time_signatures.retain(|(other, _)| *other != position);
+                        time_signatures.push((position, time_signature));
+                    }
+                }
+                "staves" => state.staves = Importer::number(element, "staves")?,
+                "clef" => {
+                    let number: usize = child
+                        .attribute("number")
+                        .and_then(|number| number.parse().ok())
+                        .unwrap_or(1);
+                    if let Some(clef) = self.clef(child)? {
+                        let index = number.max(1) - 1;
+                        if state.clefs.len() <= index {
+                            state.clefs.resize(index + 1, Clef::default());
+                        }
+                        if state
+                            .voices
+                            .iter()
+                            .any(|voice| voice.staff == index && !voice.events.is_empty())
+                            && state.clefs[index] != clef
+                        {
+                            self.warn(child, Some(clef.name()));
+                        } else {
+                            state.clefs[index] = clef;
+                        }
+                    }
+                }
+                _ => self.warn(child, None),
+            }
+        }
+        Ok(())
+    }
+    fn note(&mut self, element: &Element, state: &mut PartState) -> Result<(), MusicXmlError> {
+        for name in ["grace", "cue"] {
+            if let Some(child) = element.child(name) {
+                self.warn(child, None);
+                return Ok(());
+            }
+        }
+        let pitch = if let Some(pitch) = element.child("pitch") {
+            Some(Pitch::new(
+                PitchClass::new(Importer::step(pitch, "step")?, self.alter(pitch, "alter")?),
+                Octave::new(Importer::number(pitch, "octave")?),
+            ))
+        } else if let Some(unpitched) = element.child("unpitched") {
+            self.warn(unpitched, None);
+            match unpitched.child("display-step") {
+                Some(_) => Some(Pitch::new(
+                    PitchClass::new(
+                        Importer::step(unpitched, "display-step")?,
+                        Accidental::NATURAL,
+                    ),
+                    Octave::new(Importer::number(unpitched, "display-octave")?),
+                )),
+                None => None,
+            }
+        } else {
+            None
+        };
+        let mut tie = Tie::NONE;
+        for child in element.children_named("tie") {
+            match child.attribute("type") {
+                Some("start") => tie.start = true,
+                Some("stop") => tie.stop = true,
+                kind => self.warn(child, kind),
+            }
+        }
+        let mut tuplet_start = false;
+        let mut tuplet_stop = false;
+        if let Some(notations) = element.child("notations") {
+            for child in &notations.children {
+                match (child.name.as_str(), child.attribute("type")) {
+                    ("tied", Some("start")) => tie.start = true,
+                    ("tied", Some("stop")) => tie.stop = true,
+                    ("tied", Some("continue")) => tie = Tie::CONTINUE,
+                    ("tuplet", Some("start")) => tuplet_start = true,
+                    ("tuplet", Some("stop")) => tuplet_stop = true,
+                    _ => self.warn(child, None),
+                }
+            }
+        }
+        self.warn_others(
+            element,
+            &[
+                "chord",
+                "pitch",
+                "unpitched",
+                "rest",
+                "duration",
+                "tie",
+                "voice",
+                "type",
+                "dot",
+                "accidental",
+                "time-modification",
+                "staff",
+                "notations",
+            ],
+        );
+        let id = element.child_text("voice").unwrap_or("1").to_string();
+        let staff = Importer::optional::<usize>(element, "staff")?
+            .unwrap_or(1)
+            .max(1)
+            - 1;
+        let index = match state.voices.iter().position(|voice| voice.id == id) {
+            Some(index) => index,
+            None => {
+                state.voices.push(VoiceState {
+                    id,
+                    staff,
+                    events: Vec::new(),
+                    end: Ratio::ZERO,
+                    group: None,
+                });
+                state.voices.len() - 1
+            }
+        };
+        let voice = &mut state.voices[index];
+        if element.child("chord").is_some() {
+            if let (Some(pitch), Some(last)) = (pitch, voice.last_mut()) {
+                let mut pitches = last.pitches().to_vec();
+                if !last.is_rest() && !pitches.contains(&pitch) {
+                    pitches.push(pitch);
+                    let merged =
+                        Tie::new(last.tie().start || tie.start, last.tie().stop || tie.stop);
+                    *last = NoteEvent::chord(pitches, last.value()).with_tie(merged);
+                }
+            }
+            return Ok(());
+        }
+        let sounding = Importer::duration(element, state.divisions)?;
+        let start = state.cursor;
+        if voice.end > start {
+            self.warn(element, Some("overlapping"));
+            return Ok(());
+        }
+        if voice.end < start {
+            if !voice.flush() {
+                self.warn(element, Some("incomplete tuplet"));
+            }
+            let gap = start
+                .checked_saturating_sub(voice.end)
+                .ok_or_else(|| Importer::overflow(element, "duration"))?
+                .to_duration();
+            voice.events.push(NoteEvent::rest(gap));
+        }
+        let written = match element.child_text("type") {
+            Some(kind) => match TYPES.iter().position(|name| *name == kind) {
+                Some(exponent) => Some(Duration::dotted(
+                    Subdivision::new(exponent),
+                    element.children_named("dot").count(),
+                )),
+                None => {
+                    self.warn(element.child("type").unwrap_or(element), Some(kind));
+                    None
+                }
+            },
+            None => None,
+        };
+        let modification = match element.child("time-modification") {
+            Some(modification) => {
+                let actual = Importer::number(modification, "actual-notes")?;
+                let normal = Importer::number(modification, "normal-notes")?;
+                Some(Tuplet::new(actual, normal))
+                    .filter(|tuplet| tuplet.actual != tuplet.normal && tuplet.actual > 0)
+            }
+            None => None,
+        };
+        let value = match (modification, written) {
+            (Some(_), Some(written)) => written,
+            (Some(tuplet), None) => tuplet
+                .written(sounding.to_duration())
+                .unwrap_or(sounding.to_duration()),
+            (None, _) if sounding.is_dyadic() => sounding.to_duration(),
+            (None, Some(written)) => written,
+            (None, None) => sounding.to_duration(),
+        };
+        let event = match pitch {
+            Some(pitch) => NoteEvent::single(pitch, value).with_tie(tie),
+            None => NoteEvent::rest(value),
+        };
+        match modification {
+            Some(tuplet) => {
+                if voice.group.as_ref().is_some_and(|group| {
+                    group.tuplet != tuplet || tuplet_start && !group.events.is_empty()
+                }) && !voice.flush()
+                {
+                    self.warn(element, Some("incomplete tuplet"));
+                }
+                let group = voice.group.get_or_insert(Group {
+                    tuplet,
+                    events: Vec::new(),
+                    marked: false,
+                });
+                group.marked |= tuplet_start;
+                group.events.push(event);
+                let complete = tuplet
+                    .sounding(NoteEvent::total_duration(&group.events))
+                    .is_some();
+                if complete && (tuplet_stop || !group.marked) {
+                    voice.flush();
+                }
+            }
+            None => {
+                if !voice.flush() {
+                    self.warn(element, Some("incomplete tuplet"));
+                }
+                voice.events.push(event);
+            }
+        }
+        voice.end = start
+            .checked_add(sounding)
+            .ok_or_else(|| Importer::overflow(element, "duration"))?;
+        state.cursor = voice.end;
+        state.extend(element)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::musicxml::MusicXmlWriter;
+
+    fn document(measures: &str) -> String {
+        format!(
+            "<score-partwise><part-list><score-part id=\"P1\"><part-name>A</part-name>\
+             </score-part></part-list><part id=\"P1\">{measures}</part></score-partwise>"
+        )
+    }
+
+    fn note(duration: &str) -> String {
+        format!(
+            "<note><pitch><step>C</step><octave>4</octave></pitch>\
+             <duration>{duration}</duration></note>"
+        )
+    }
+
+    #[test]
+    fn round_trip() {
+        let events = vec![
+            NoteEvent::single(
+                "C4".parse().unwrap(),
+                Subdivision::QUARTER_NOTE.to_duration(),
+            ),
+            NoteEvent::rest(Subdivision::QUARTER_NOTE.to_duration()),
+            NoteEvent::chord(
+                ["E4".parse().unwrap(), "G4".parse().unwrap()],
+                Subdivision::HALF_NOTE.to_duration(),
+            ),
+        ];
+        let xml =
+            MusicXmlWriter::from_events(events.clone(), TimeSignature::common_time(), Key::C_MAJOR)
+                .to_xml();
+        let MusicXml { score, warnings } = MusicXml::parse(&xml).unwrap();
+        assert_eq!(warnings, []);
+        assert_eq!(score.parts[0].key, Key::C_MAJOR);
+        assert_eq!(
+            score.time_signatures,
+            [(Duration::ZERO, TimeSignature::common_time())]
+        );
+        let voice = score.voices().next().unwrap();
+        assert_eq!(voice.events(), events);
+    }
+
+    #[test]
+    fn rejects_overflowing_duration() {
+        let huge = note("18446744073709551615");
+        let xml = document(&format!("<measure number=\"1\">{huge}{huge}</measure>"));
+        let error = MusicXml::parse(&xml).unwrap_err();
+        assert!(matches!(
+            error.kind,
+            MusicXmlErrorKind::InvalidValue { element, .. } if element == "duration"
+        ));
+    }
+
+    #[test]
+    fn rejects_overflowing_divisions() {
+        let measures: String = [3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47]
+            .iter()
+            .map(|divisions| {
+                format!(
+                    "<measure><attributes><divisions>{divisions}</divisions></attributes>{}\
+                     </measure>",
+                    note("1")
+                )
+            })
+            .collect();
+        let error = MusicXml::parse(&document(&measures)).unwrap_err();
+        assert!(matches!(error.kind, MusicXmlErrorKind::InvalidValue { .. }));
+    }
+
+    #[test]
+    fn warns_on_zero_beats() {
+        let xml = document(&format!(
+            "<measure number=\"1\"><attributes><divisions>1</divisions><time><beats>0</beats>\
+             <beat-type>4</beat-type></time></attributes>{}</measure>",
+            note("1")
+        ));
+        let MusicXml { score, warnings } = MusicXml::parse(&xml).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].element, "time");
+        assert_eq!(score.barlines(), [Duration::ZERO]);
+    }
+}
@@ -0,0 +1,438 @@
+use crate::musicxml::{harmony, zip};
+use crate::note::{Duration, NoteEvent, Pitch, Subdivision, Tuplet};
+use crate::scale::Key;
+use crate::score::{Clef, Instrument, Measure, Part, Score, Staff, Tempo, TimeSignature};
+use crate::xml::{escape, XmlWriter};
+use std::io;
+
+const MIMETYPE: &str = "application/vnd.recordare.musicxml";
+const ROOTFILE: &str = "score.musicxml";
+const PLAIN: Tuplet = Tuplet::new(1, 1);
+const TYPES: [&str; 11] = [
+    "whole", "half", "quarter", "eighth", "16th", "32nd", "64th", "128th", "256th", "512th",
+    "1024th",
+];
+
+struct Leaf<'a> {
+    event: &'a NoteEvent,
+    ratio: Tuplet,
+    starts: Vec<(usize, Tuplet)>,
+    stops: Vec<usize>,
+}
+
+fn leaves<'a>(event: &'a NoteEvent, ratio: Tuplet, depth: usize, out: &mut Vec<Leaf<'a>>) {
+    let NoteEvent::Tuplet { tuplet, events, .. } = event else {
+        out.push(Leaf {
+            event,
+            ratio,
+            starts: Vec::new(),
+            stops: Vec::new(),
+        });
+        return;
+    };
+    let first = out.len();
+    for event in events {
+        leaves(event, ratio.nest(*tuplet), depth + 1, out);
+    }
+    if out.len() > first {
+        out[first].starts.push((depth + 1, *tuplet));
+        if let Some(last) = out.last_mut() {
+            last.stops.push(depth + 1);
+        }
+    }
+}
+
+const fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MusicXmlWriter {
+    pub score: Score,
+}
+
+impl MusicXmlWriter {
+    pub const fn new(score: Score) -> Self {
+        MusicXmlWriter { score }
+    }
+    pub fn from_events(
+        events: impl IntoIterator<Item = NoteEvent>,
+        time_signature: TimeSignature,
+        key: Key,
+    ) -> Self {
+        let part = Part::new("Music", Instrument::new("Piano", 0), key)
+            .with_staff(Staff::new(Clef::Treble).with_voice(events.into_iter().collect()));
+        MusicXmlWriter::new(Score::new(time_signature, Tempo::default()).with_part(part))
+    }
+    fn divisions(&self) -> usize {
+        let mut divisions = 1;
+        let mut require = |length: usize, nth: usize| {
+            let denominator = nth / gcd(length * 4, nth);
+            divisions = divisions / gcd(divisions, denominator) * denominator;
+        };
+        for (_, time_signature) in &self.score.time_signatures {
+            require(time_signature.numerator(), time_signature.denominator());
+        }
+        let positions = self
+            .score
+            .tempo
+            .changes()
+            .iter()
+            .map(|change| change.position);
+        let chords = self.score.parts.iter().flat_map(|part| &part.chords);
+        for position in positions.chain(chords.map(|(position, _)| *position)) {
+            let Duration { length, unit } = position.reduce();
+            require(length, unit.nth());
+        }
+        for voice in self.score.voices() {
+            let mut found = Vec::new();
+            for event in voice.events() {
+                leaves(event, PLAIN, 0, &mut found);
+            }
+            for Leaf { event, ratio, .. } in found {
+                for (unit, dots) in event.value().to_dotted_parts() {
+                    let Duration { length, unit } = Duration::dotted(unit, dots);
+                    require(length * ratio.normal, unit.nth() * ratio.actual);
+                }
+            }
+        }
+        divisions
+    }
+    fn ticks(duration: Duration, ratio: Tuplet, divisions: usize) -> usize {
+        duration.length * 4 * divisions * ratio.normal / (duration.unit.nth() * ratio.actual)
+    }
+    fn attributes(
+        &self,
+        xml: &mut XmlWriter,
+        part: &Part,
+        measure: usize,
+        start: Duration,
+        divisions: usize,
+    ) {
+        xml.open("attributes");
+        if measure == 0 {
+            xml.text("divisions", divisions);
+            xml.open("key");
+            xml.text("fifths", part.key.fifths());
+            xml.text("mode", part.key.mode.name());
+            xml.close("key");
+        }
+        if let Some(time_signature) = self.time_signature_change(measure, start) {
+            xml.open("time");
+            let groups: Vec<String> = time_signature
+                .groups()
+                .iter()
+                .map(usize::to_string)
+                .collect();
+            xml.text("beats", groups.join("+"));
+            xml.text("beat-type", time_signature.denominator());
+            xml.close("time");
+        }
+        if measure == 0 {
+            if part.staves.len() > 1 {
+                xml.text("staves", part.staves.len());
+            }
+            for (i, staff) in part.staves.iter().enumerate() {
+                xml.open(&format!("clef number=\"{}\"", i + 1));
+                match staff.clef {
+                    Clef::Percussion => xml.text("sign", "percussion"),
+                    clef => {
+                        xml.text("sign", clef.sign());
+                        xml.text("line", clef.line());
+                        if clef.octave_change() != 0 {
+                            xml.text("clef-octave-change", clef.octave_change());
+                        }
+                    }
+                }
+                xml.close("clef");
+            }
+        }
+        xml.close("attributes");
+    }
+    fn time_signature_change(&self, measure: usize, start: Duration) -> Option<&TimeSignature> {
+        match measure {
+            0 => self.score.time_signature_at(start),
+            _ => self
+                .score
+                .time_signatures
+                .iter()
+                .find(|(position, _)| *position == start)
+                .map(|(_, time_signature)| time_signature),
+        }
+    }
+    fn tempo(xml: &mut XmlWriter, tempo: Tempo, offset: usize) {
+        xml.open("direction placement=\"above\"");
+        xml.open("direction-type");
+        xml.open("metronome");
+        let (unit, dots) = tempo
+            .beat
+            .as_dotted()
+            .unwrap_or((Subdivision::QUARTER_NOTE, 0));
+        xml.text(
+            "beat-unit",
+            TYPES.get(unit.exponent()).unwrap_or(&"quarter"),
+        );
+        for _ in 0..dots {
+            xml.empty("beat-unit-dot");
+        }
+        xml.text("per-minute", tempo.bpm);
+        xml.close("metronome");
+        xml.close("direction-type");
+        if offset > 0 {
+            xml.text("offset", offset);
+        }
+        xml.empty(&format!(
+            "sound tempo=\"{}\"",
+            tempo.bpm * tempo.beat.as_f64() * 4.0
+        ));
+        xml.close("direction");
+    }
+    fn note(
+        xml: &mut XmlWriter,
+        leaf: &Leaf,
+        voice: usize,
+        staff: Option<usize>,
+        divisions: usize,
+    ) {
+        let parts = leaf.event.value().to_dotted_parts();
+        let pitches: Vec<Option<Pitch>> = match leaf.event.pitches() {
+            [] => vec![None],
+            pitches => pitches.iter().copied().map(Some).collect(),
+        };
+        let tie = leaf.event.tie();
+        for (i, &(unit, dots)) in parts.iter().enumerate() {
+            let stop = !leaf.event.is_rest() && (i > 0 || tie.stop);
+            let start = !leaf.event.is_rest() && (i + 1 < parts.len() || tie.start);
+            let ticks = MusicXmlWriter::ticks(Duration::dotted(unit, dots), leaf.ratio, divisions);
+            for (j, pitch) in pitches.iter().enumerate() {
+                xml.open("note");
+                if j > 0 {
+                    xml.empty("chord");
+                }
+                match pitch {
+                    Some(pitch) => {
+                        xml.open("pitch");
+                        xml.text("step", pitch.pitch_class.base.name());
+                        let alter = pitch.pitch_class.accidental.pitch_shift();
+                        if alter != 0 {
+                            xml.text("alter", alter);
+                        }
+                        xml.text("octave", pitch.octave.numeric());
+                        xml.close("pitch");
+                    }
+                    None => xml.empty("rest"),
+                }
+                xml.text("duration", ticks);
+                if stop {
+                    xml.empty("tie type=\"stop\"");
+                }
+                if start {
+                    xml.empty("tie type=\"start\"");
+                }
+                xml.text("voice", voice);
+                if let Some(name) = TYPES.get(unit.exponent()) {
+                    xml.text("type", name);
+                }
+                for _ in 0..dots {
+                    xml.empty("dot");
+                }
+                if leaf.ratio.actual != leaf.ratio.normal {
+                    xml.open("time-modification");
+                    xml.text("actual-notes", leaf.ratio.actual);
+                    xml.text("normal-notes", leaf.ratio.normal);
+                    xml.close("time-modification");
+                }
+                if let Some(staff) = staff {
+                    xml.text("staff", staff);
+                }
+                let starts: &[(usize, Tuplet)] = if i == 0 { &leaf.starts } else { &[] };
+                let stops: &[usize] = if i + 1 == parts.len() {
+                    &leaf.stops
+                } else {
+                    &[]
+                };
+                if stop || start || !starts.is_empty() || !stops.is_empty() {
+                    xml.open("notations");
+                    if stop {
+                        xml.empty("tied type=\"stop\"");
+                    }
+                    if start {
+                        xml.empty("tied type=\"start\"");
+                    }
+                    for (number, tuplet) in starts.iter().filter(|_| j == 0) {
+                        xml.open(&format!(
+                            "tuplet type=\"start\" number=\"{number}\" bracket=\"yes\""
+                        ));
+                        xml.open("tuplet-actual");
+                        xml.text("tuplet-number", tuplet.actual);
+                        xml.close("tuplet-actual");
+                        xml.open("tuplet-normal");
+                        xml.text("tuplet-number", tuplet.normal);
+                        xml.close("tuplet-normal");
+                        xml.close("tuplet");
+                    }
+                    for number in stops.iter().filter(|_| j == 0) {
+                        xml.empty(&format!("tuplet type=\"stop\" number=\"{number}\""));
+                    }
+                    xml.close("notations");
+                }
+                xml.close("note");
+            }
+        }
+    }
+    fn measure(
+        xml: &mut XmlWriter,
+        measure: &Measure,
+        voice: usize,
+        staff: Option<usize>,
+        divisions: usize,
+    ) -> usize {
+        let mut found = Vec::new();
+        for event in &measure.events {
+            leaves(event, PLAIN, 0, &mut found);
+        }
+        for leaf in &found {
+            MusicXmlWriter::note(xml, leaf, voice, staff, divisions);
+        }
+        measure
+            .events
+            .iter()
+            .map(|event| MusicXmlWriter::ticks(event.value(), PLAIN, divisions))
+            .sum()
+    }
+    fn measure_rest(xml: &mut XmlWriter, ticks: usize, voice: usize, staff: Option<usize>) {
+        xml.open("note");
+        xml.empty("rest measure=\"yes\"");
+        xml.text("duration", ticks);
+        xml.text("voice", voice);
+        if let Some(staff) = staff {
+            xml.text("staff", staff);
+        }
+        xml.close("note");
+    }
+    fn part(&self, xml: &mut XmlWriter, index: usize, part: &Part, divisions: usize) {
+        xml.open(&format!("part id=\"P{}\"", index + 1));
+        let barlines = self.score.barlines();
+        let voices: Vec<(usize, Vec<Measure>)> = part
+            .staves
+            .iter()
+            .enumerate()
+            .flat_map(|(staff, Staff { voices, .. })| {
+                voices.iter().map(move |voice| (staff, voice))
+            })
+            .map(|(staff, voice)| (staff, self.score.measures(voice)))
+            .collect();
+        let staff_number = |staff: usize| (part.staves.len() > 1).then_some(staff + 1);
+        for (measure, &start) in barlines.iter().enumerate() {
+            xml.open(&format!("measure number=\"{}\"", measure + 1));
+            if measure == 0 || self.time_signature_change(measure, start).is_some() {
+                self.attributes(xml, part, measure, start, divisions);
+            }
+            let end = barlines
+                .get(measure + 1)
+                .copied()
+                .unwrap_or(self.score.duration());
+            if index == 0 {
+                for change in self.score.tempo.changes() {
+                    if change.position >= start && change.position < end {
+                        let offset =
+                            MusicXmlWriter::ticks(change.position - start, PLAIN, divisions);
+                        MusicXmlWriter::tempo(xml, change.tempo, offset);
+                    }
+                }
+            }
+            for (position, chord) in &part.chords {
+                if *position >= start && *position < end {
+                    let offset = MusicXmlWriter::ticks(*position - start, PLAIN, divisions);
+                    harmony::write(xml, chord, offset);
+                }
+            }
+            let capacity = MusicXmlWriter::ticks(end - start, PLAIN, divisions);
+            let mut written = 0;
+            let mut rested = Vec::new();
+            for (number, (staff, measures)) in voices.iter().enumerate() {
+                let (number, measure) = (number + 1, &measures[measure]);
+                if measure.events.is_empty() && rested.contains(staff) {
+                    continue;
+                }
+                if written > 0 {
+                    xml.open("backup");
+                    xml.text("duration", written);
+                    xml.close("backup");
+                }
+                written = if measure.events.is_empty() {
+                    MusicXmlWriter::measure_rest(xml, capacity, number, staff_number(*staff));
+                    capacity
+                } else {
+                    MusicXmlWriter::measure(xml, measure, number, staff_number(*staff), divisions)
+                };
+                rested.push(*staff);
+            }
+            xml.close("measure");
+        }
+        xml.close("part");
+    }
+    pub fn to_xml(&self) -> String {
+        let divisions = self.divisions();
+        let mut xml = XmlWriter::new();
+        xml.line("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>");
+        xml.line("<!DOCTYPE score-partwise PUBLIC \"-//Recordare//DTD MusicXML 4.0 Partwise//EN\" \"http://www.musicxml.org/dtds/partwise.dtd\">");
+        xml.open("score-partwise version=\"4.0\"");
+        if let Some(title) = &self.score.title {
+            xml.open("work");
+            xml.text("work-title", title);
+            xml.close("work");
+        }
+        if let Some(composer) = &self.score.composer {
+            xml.open("identification");
+            xml.line(&format!(
+                "<creator type=\"composer\">{}</creator>",
+                escape(composer)
+            ));
+            xml.close("identification");
+        }
+        xml.open("part-list");
+        for (i, part) in self.score.parts.iter().enumerate() {
+            xml.open(&format!("score-part id=\"P{}\"", i + 1));
+            xml.text("part-name", &part.name);
+            xml.open(&format!("score-instrument id=\"P{}-I1\"", i + 1));
+            xml.text("instrument-name", &part.instrument.name);
+            xml.close("score-instrument");
+            xml.open(&format!("midi-instrument id=\"P{}-I1\"", i + 1));
+            xml.text("midi-program", part.instrument.program as usize + 1);
+            xml.close("midi-instrument");
+            xml.close("score-part");
+        }
+        xml.close("part-list");
+        for (i, part) in self.score.parts.iter().enumerate() {
+            self.part(&mut xml, i, part, divisions);
+        }
+        xml.close("score-partwise");
+        xml.out
+    }
+    pub fn to_mxl(&self) -> Vec<u8> {
+        let container = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <container>\n\
+             \x20 <rootfiles>\n\
+             \x20   <rootfile full-path=\"{ROOTFILE}\" media-type=\"{MIMETYPE}+xml\"/>\n\
+             \x20 </rootfiles>\n\
+             </container>\n"
+        );
+        zip::write_zip(&[
+            ("mimetype", MIMETYPE.as_bytes(), false),
+            ("META-INF/container.xml", container.as_bytes(), true),
+            (ROOTFILE, self.to_xml().as_bytes(), true),
+        ])
+    }
+    pub fn write<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(self.to_xml().as_bytes())
+    }
+    pub fn write_mxl<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&self.to_mxl())
+    }
+}
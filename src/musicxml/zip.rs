@@ -0,0 +1,369 @@
+const LOCAL_HEADER: u32 = 0x0403_4b50;
+const CENTRAL_HEADER: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x0605_4b50;
+const VERSION: u16 = 20;
+const STORED: u16 = 0;
+const DEFLATED: u16 = 8;
+const DOS_DATE: u16 = 0x21;
+
+const LENGTH_BASE: [usize; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [usize; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+const WINDOW: usize = 1 << 15;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+const MAX_CHAIN: usize = 64;
+
+pub(crate) fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xedb8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    count: u8,
+}
+
+impl BitWriter {
+    const fn new() -> Self {
+        BitWriter {
+            bytes: Vec::new(),
+            buffer: 0,
+            count: 0,
+        }
+    }
+    fn bits(&mut self, value: u32, count: u8) {
+        self.buffer |= value << self.count;
+        self.count += count;
+        while self.count >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.count -= 8;
+        }
+    }
+    fn code(&mut self, code: u32, count: u8) {
+        self.bits(code.reverse_bits() >> (32 - count), count);
+    }
+    fn literal(&mut self, symbol: usize) {
+        match symbol {
+            0..=143 => self.code(0x30 + symbol as u32, 8),
+            144..=255 => self.code(0x190 + (symbol - 144) as u32, 9),
+            256..=279 => self.code((symbol - 256) as u32, 7),
+            _ => self.code(0xc0 + (symbol - 280) as u32, 8),
+        }
+    }
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+fn symbol(base: &[usize], value: usize) -> usize {
+    base.partition_point(|&start| start <= value) - 1
+}
+
+pub(crate) fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut writer = BitWriter::new();
+    writer.bits(1, 1);
+    writer.bits(1, 2);
+    let hash = |i: usize| {
+        ((data[i] as usize) << 10 ^ (data[i + 1] as usize) << 5 ^ data[i + 2] as usize)
+            & (WINDOW - 1)
+    };
+    let mut head = vec![usize::MAX; WINDOW];
+    let mut prev = vec![usize::MAX; WINDOW];
+    let mut i = 0;
+    while i < data.len() {
+        let (mut length, mut distance) = (0, 0);
+        if i + MIN_MATCH <= data.len() {
+            let mut candidate = head[hash(i)];
+            let mut chain = 0;
+            while candidate != usize::MAX && i - candidate <= WINDOW && chain < MAX_CHAIN {
+                let limit = (data.len() - i).min(MAX_MATCH);
+                let matched = (0..limit)
+                    .take_while(|&k| data[candidate + k] == data[i + k])
+                    .count();
+                if matched > length {
+                    (length, distance) = (matched, i - candidate);
+                }
+                let next = prev[candidate % WINDOW];
+                if next == usize::MAX || next >= candidate {
+                    break;
+                }
+                candidate = next;
+                chain += 1;
+            }
+        }
+        if length >= MIN_MATCH {
+            let code = symbol(&LENGTH_BASE, length);
+            writer.literal(257 + code);
+            writer.bits((length - LENGTH_BASE[code]) as u32, LENGTH_EXTRA[code]);
+            let code = symbol(&DISTANCE_BASE, distance);
+            writer.code(code as u32, 5);
+            writer.bits(
+                (distance - DISTANCE_BASE[code]) as u32,
+                DISTANCE_EXTRA[code],
+            );
+        } else {
+            writer.literal(data[i] as usize);
+            length = 1;
+        }
+        for k in (i..i + length).filter(|k| k + MIN_MATCH <= data.len()) {
+            prev[k % WINDOW] = head[hash(k)];
+            head[hash(k)] = k;
+        }
+        i += length;
+    }
+    writer.literal(256);
+    writer.finish()
+}
+
+fn put_u16(bytes: &mut Vec<u8>, value: u16) {
+    bytes.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u32(bytes: &mut Vec<u8>, value: u32) {
+    bytes.extend_from_slice(&value.to_le_bytes());
+}
+
+pub(crate) fn write_zip(entries: &[(&str, &[u8], bool)]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let mut directory = Vec::new();
+    for &(name, data, compress) in entries {
+        let offset = bytes.len() as u32;
+        let (method, payload) = if compress {
+            (DEFLATED, deflate(data))
+        } else {
+            (STORED, data.to_vec())
+        };
+        let mut header = Vec::new();
+        put_u16(&mut header, VERSION);
+        put_u16(&mut header, 0);
+        put_u16(&mut header, method);
+        put_u16(&mut header, 0);
+        put_u16(&mut header, DOS_DATE);
+        put_u32(&mut header, crc32(data));
+        put_u32(&mut header, payload.len() as u32);
+        put_u32(&mut header, data.len() as u32);
+        put_u16(&mut header, name.len() as u16);
+        put_u16(&mut header, 0);
+        put_u32(&mut bytes, LOCAL_HEADER);
+        bytes.extend_from_slice(&header);
+        bytes.extend_from_slice(name.as_bytes());
+        bytes.extend_from_slice(&payload);
+        put_u32(&mut directory, CENTRAL_HEADER);
+        put_u16(&mut directory, VERSION);
+        directory.extend_from_slice(&header);
+        put_u16(&mut directory, 0);
+        put_u16(&mut directory, 0);
+        put_u16(&mut directory, 0);
+        put_u32(&mut directory, 0);
+        put_u32(&mut directory, offset);
+        directory.extend_from_slice(name.as_bytes());
+    }
+    let offset = bytes.len() as u32;
+    let size = directory.len() as u32;
+    bytes.extend(directory);
+    put_u32(&mut bytes, END_OF_CENTRAL_DIRECTORY);
+    put_u16(&mut bytes, 0);
+    put_u16(&mut bytes, 0);
+    put_u16(&mut bytes, entries.len() as u16);
+    put_u16(&mut bytes, entries.len() as u16);
+    put_u32(&mut bytes, size);
+    put_u32(&mut bytes, offset);
+    put_u16(&mut bytes, 0);
+    bytes
+}
+
+struct BitReader<'a> {
+    bytes: &'a [u8],
+    bit: usize,
+}
+
+impl BitReader<'_> {
+    fn bits(&mut self, count: u8) -> Option<usize> {
+        let mut value = 0;
+        for i in 0..count {
+            let byte = *self.bytes.get(self.bit / 8)?;
+            value |= ((byte >> (self.bit % 8)) as usize & 1) << i;
+            self.bit += 1;
+        }
+        Some(value)
+    }
+    fn decode(&mut self, huffman: &Huffman) -> Option<usize> {
+        let (mut code, mut first, mut index) = (0, 0, 0);
+        for &count in &huffman.counts[1..] {
+            code |= self.bits(1)?;
+            if code - first < count {
+                return huffman.symbols.get(index + code - first).copied();
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        None
+    }
+}
+
+struct Huffman {
+    counts: [usize; 16],
+    symbols: Vec<usize>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Self {
+        let mut counts = [0; 16];
+        for &length in lengths {
+            counts[length as usize] += 1;
+        }
+        counts[0] = 0;
+        let mut symbols = Vec::with_capacity(lengths.len());
+        for length in 1..16 {
+            symbols.extend((0..lengths.len()).filter(|&symbol| lengths[symbol] == length));
+        }
+        Huffman { counts, symbols }
+    }
+}
+
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+pub(crate) fn inflate(data: &[u8]) -> Option<Vec<u8>> {
+    let mut reader = BitReader {
+        bytes: data,
+        bit: 0,
+    };
+    let mut out = Vec::new();
+    loop {
+        let last = reader.bits(1)? == 1;
+        match reader.bits(2)? {
+            0 => {
+                let start = reader.bit.div_ceil(8);
+                let header = data.get(start..start + 4)?;
+                let length = u16::from_le_bytes([header[0], header[1]]) as usize;
+                out.extend_from_slice(data.get(start + 4..start + 4 + length)?);
+                reader.bit = (start + 4 + length) * 8;
+            }
+            kind @ (1 | 2) => {
+                let (literals, distances) = if kind == 1 {
+                    let mut lengths = [8; 288];
+                    lengths[144..256].fill(9);
+                    lengths[256..280].fill(7);
+                    (Huffman::new(&lengths), Huffman::new(&[5; 30]))
+                } else {
+                    let literal_count = reader.bits(5)? + 257;
+                    let distance_count = reader.bits(5)? + 1;
+                    let code_count = reader.bits(4)? + 4;
+                    let mut code_lengths = [0; 19];
+                    for &symbol in &CODE_LENGTH_ORDER[..code_count] {
+                        code_lengths[symbol] = reader.bits(3)? as u8;
+                    }
+                    let codes = Huffman::new(&code_lengths);
+                    let mut lengths = Vec::with_capacity(literal_count + distance_count);
+                    while lengths.len() < literal_count + distance_count {
+                        let (value, repeat) = match reader.decode(&codes)? {
+                            symbol @ 0..=15 => (symbol as u8, 1),
+                            16 => (*lengths.last()?, 3 + reader.bits(2)?),
+                            17 => (0, 3 + reader.bits(3)?),
+                            _ => (0, 11 + reader.bits(7)?),
+                        };
+                        lengths.extend(std::iter::repeat_n(value, repeat));
+                    }
+                    (
+                        Huffman::new(lengths.get(..literal_count)?),
+                        Huffman::new(lengths.get(literal_count..)?),
+                    )
+                };
+                loop {
+                    let symbol = reader.decode(&literals)?;
+                    match symbol {
+                        0..=255 => out.push(symbol as u8),
+                        256 => break,
+                        _ => {
+                            let code = symbol - 257;
+                            let length =
+                                LENGTH_BASE.get(code)? + reader.bits(LENGTH_EXTRA[code])?;
+                            let code = reader.decode(&distances)?;
+                            let distance =
+                                DISTANCE_BASE.get(code)? + reader.bits(DISTANCE_EXTRA[code])?;
+                            let start = out.len().checked_sub(distance)?;
+                            for i in 0..length {
+                                out.push(out[start + i]);
+                            }
+                        }
+                    }
+                }
+            }
+            _ => return None,
+        }
+        if last {
+            return Some(out);
+        }
+    }
+}
+
+fn get_u16(bytes: &[u8], at: usize) -> Option<usize> {
+    let bytes = bytes.get(at..at + 2)?;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]) as usize)
+}
+
+fn get_u32(bytes: &[u8], at: usize) -> Option<usize> {
+    let bytes = bytes.get(at..at + 4)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)
+}
+
+pub(crate) fn read_zip(bytes: &[u8]) -> Option<Vec<(String, Vec<u8>)>> {
+    let end = (0..=bytes.len().checked_sub(22)?)
+        .rev()
+        .find(|&at| get_u32(bytes, at) == Some(END_OF_CENTRAL_DIRECTORY as usize))?;
+    let count = get_u16(bytes, end + 10)?;
+    let mut at = get_u32(bytes, end + 16)?;
+    let mut entries = Vec::with_capacity(count);
+    for _ in 0..count {
+        if get_u32(bytes, at)? != CENTRAL_HEADER as usize {
+            return None;
+        }
+        let method = get_u16(bytes, at + 10)? as u16;
+        let compressed = get_u32(bytes, at + 20)?;
+        let name_length = get_u16(bytes, at + 28)?;
+        let skip = name_length + get_u16(bytes, at + 30)? + get_u16(bytes, at + 32)?;
+        let local = get_u32(bytes, at + 42)?;
+        let name = bytes.get(at + 46..at + 46 + name_length)?;
+        let name = String::from_utf8_lossy(name).into_owned();
+        at += 46 + skip;
+        if get_u32(bytes, local)? != LOCAL_HEADER as usize {
+            return None;
+        }
+        let start = local + 30 + get_u16(bytes, local + 26)? + get_u16(bytes, local + 28)?;
+        let payload = bytes.get(start..start + compressed)?;
+        let data = match method {
+            STORED => payload.to_vec(),
+            DEFLATED => inflate(payload)?,
+            _ => return None,
+        };
+        entries.push((name, data));
+    }
+    Some(entries)
+}
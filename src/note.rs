@@ -3,21 +3,34 @@ mod chromatic;
 mod diatonic;
 mod duration;
 mod midi_note;
+mod notation;
+#[allow(clippy::module_inception)]
 mod note;
+mod note_event;
+mod note_naming;
 mod octave;
+mod octave_convention;
 mod pitch;
 mod pitch_class;
+mod pitch_like;
 mod subdivision;
 mod tone;
+mod tuplet;
 
 pub use accidental::Accidental;
 pub use chromatic::Chromatic;
 pub use diatonic::Diatonic;
 pub use duration::Duration;
-pub use midi_note::MidiNote;
+pub use midi_note::{MidiNote, MidiNoteRangeError};
+pub use notation::Notation;
 pub use note::Note;
+pub use note_event::{NoteEvent, Tie};
+pub use note_naming::NoteNaming;
 pub use octave::Octave;
+pub use octave_convention::OctaveConvention;
 pub use pitch::Pitch;
 pub use pitch_class::PitchClass;
+pub use pitch_like::{HasPitchClass, PitchLike, Transpose};
 pub use subdivision::Subdivision;
 pub use tone::Tone;
+pub use tuplet::Tuplet;
@@ -1,8 +1,10 @@
+use crate::note::Notation;
+use crate::ParseError;
 use std::fmt;
 use std::ops::{Shl, ShlAssign, Shr, ShrAssign};
 use std::str::FromStr;
 
-#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Accidental(isize);
 
 impl Accidental {
@@ -24,28 +26,75 @@ impl Accidental {
             _ => "\u{25a1}", // □
         }
     }
+    pub fn symbol(&self, notation: Notation) -> String {
+        notation.accidental(*self)
+    }
     pub const fn pitch_shift(self) -> isize {
         self.0
     }
+    pub const fn flatten(self, semitones: isize) -> Self {
+        Accidental::new(self.pitch_shift() - semitones)
+    }
+    pub const fn sharpen(self, semitones: isize) -> Self {
+        Accidental::new(self.pitch_shift() + semitones)
+    }
 }
 
 impl fmt::Display for Accidental {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.write_str(self.name())
+        f.write_str(&self.symbol(Notation::of(f)))
     }
 }
 
 impl FromStr for Accidental {
-    type Err = ();
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "\u{1d12b}" | "\u{266d}\u{266d}" | "bb" => Ok(Accidental::DOUBLE_FLAT),
-            "\u{266d}" | "b" => Ok(Accidental::FLAT),
-            "\u{266e}" | "" | "n" => Ok(Accidental::NATURAL),
-            "\u{266f}" | "#" | "s" => Ok(Accidental::SHARP),
-            "\u{1d12a}" | "x" | "\u{266f}\u{266f}" | "##" | "ss" => Ok(Accidental::DOUBLE_SHARP),
-            _ => Err(()),
+        const SHARPS: &[&str] = &["\u{266f}", "\u{1d12a}", "#", "x", "is", "s"];
+        const FLATS: &[&str] = &["\u{266d}", "\u{1d12b}", "b", "es"];
+        const ACCIDENTALS: &[&str] = &[
+            "\u{266e}",
+            "\u{266f}",
+            "\u{266d}",
+            "\u{1d12a}",
+            "\u{1d12b}",
+            "n",
+            "#",
+            "b",
+            "x",
+            "is",
+            "es",
+        ];
+        if let "\u{266e}" | "n" = s {
+            return Ok(Accidental::NATURAL);
+        }
+        let (mut at, mut shift) = (0, 0);
+        while at < s.len() {
+            let expected = match shift {
+                1.. => SHARPS,
+                0 => ACCIDENTALS,
+                _ => FLATS,
+            };
+            let (token, step) = [
+                ("\u{1d12a}", 2),
+                ("x", 2),
+                ("\u{266f}", 1),
+                ("#", 1),
+                ("is", 1),
+                ("s", 1),
+                ("\u{1d12b}", -2),
+                ("\u{266d}", -1),
+                ("b", -1),
+                ("es", -1),
+            ]
+            .into_iter()
+            .find(|(token, _)| s[at..].starts_with(token))
+            .ok_or_else(|| ParseError::char_at(s, at, expected))?;
+            if shift * step < 0 {
+                return Err(ParseError::unexpected(s, at..at + token.len(), expected));
+            }
+            (at, shift) = (at + token.len(), shift + step);
         }
+        Ok(Accidental::new(shift))
     }
 }
 
@@ -64,25 +113,25 @@ impl From<isize> for Accidental {
 impl Shl<isize> for Accidental {
     type Output = Self;
     fn shl(self, rhs: isize) -> Self::Output {
-        Accidental::new(self.pitch_shift() - rhs)
+        self.flatten(rhs)
     }
 }
 
 impl Shr<isize> for Accidental {
     type Output = Self;
     fn shr(self, rhs: isize) -> Self::Output {
-        Accidental::new(self.pitch_shift() + rhs)
+        self.sharpen(rhs)
     }
 }
 
 impl ShlAssign<isize> for Accidental {
     fn shl_assign(&mut self, rhs: isize) {
-        self.0 -= rhs;
+        *self = *self << rhs;
     }
 }
 
 impl ShrAssign<isize> for Accidental {
     fn shr_assign(&mut self, rhs: isize) {
-        self.0 += rhs;
+        *self = *self >> rhs;
     }
 }
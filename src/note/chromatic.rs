@@ -1,9 +1,10 @@
-use crate::note::{Accidental, Diatonic, PitchClass};
+use crate::note::{Accidental, Diatonic, HasPitchClass, Notation, PitchClass, Transpose};
+use crate::ParseError;
 use std::fmt;
-use std::ops::{Add, AddAssign, Shl, ShlAssign, Shr, ShrAssign};
+use std::ops::{Add, AddAssign, Neg, Shl, ShlAssign, Shr, ShrAssign, Sub, SubAssign};
 use std::str::FromStr;
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum Chromatic {
     C = 0,
     CsDb = 1,
@@ -44,7 +45,7 @@ impl Chromatic {
         self as isize
     }
     pub const fn wrapping_chr(ord: isize) -> Self {
-        match ord % Chromatic::NOTES as isize {
+        match ord.rem_euclid(Chromatic::NOTES as isize) {
             0 => Chromatic::C,
             1 => Chromatic::CsDb,
             2 => Chromatic::D,
@@ -61,7 +62,7 @@ impl Chromatic {
         }
     }
     pub const fn checked_chr(ord: isize) -> Option<Self> {
-        match ord % Chromatic::NOTES as isize {
+        match ord {
             0 => Some(Chromatic::C),
             1 => Some(Chromatic::CsDb),
             2 => Some(Chromatic::D),
@@ -86,18 +87,48 @@ impl Chromatic {
     pub const fn with(self, accidental: Accidental) -> Chromatic {
         self.sharpen(accidental.pitch_shift())
     }
+    pub const fn interval_to(self, other: Self) -> isize {
+        (other.ord() - self.ord()).rem_euclid(Chromatic::NOTES as isize)
+    }
+    pub const fn interval_class(self, other: Self) -> isize {
+        let interval = self.interval_to(other);
+        if interval > Chromatic::NOTES as isize / 2 {
+            Chromatic::NOTES as isize - interval
+        } else {
+            interval
+        }
+    }
+    pub const fn invert(self) -> Chromatic {
+        Chromatic::wrapping_chr(-self.ord())
+    }
+    pub const fn invert_around(self, axis: Self) -> Chromatic {
+        Chromatic::wrapping_chr(2 * axis.ord() - self.ord())
+    }
 }
 
 impl fmt::Display for Chromatic {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.write_str(self.name())
+        f.write_str(&Notation::of(f).chromatic(*self))
     }
 }
 
 impl FromStr for Chromatic {
-    type Err = ();
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        PitchClass::from_str(s).map(PitchClass::chromatic)
+        let Some((alias, rest)) = s.split_once('/') else {
+            return PitchClass::from_str(s).map(PitchClass::chromatic);
+        };
+        let chromatic = PitchClass::from_str(rest)
+            .map_err(|error| error.offset(alias.len() + 1))?
+            .chromatic();
+        if PitchClass::from_str(alias)?.chromatic() != chromatic {
+            return Err(ParseError::unexpected(
+                s,
+                0..alias.len(),
+                &["enharmonic spelling"],
+            ));
+        }
+        Ok(chromatic)
         /* match s {
             "C" => Ok(Chromatic::C),
             "C♯" | "D♭" | "C♯/D♭" => Ok(Chromatic::CsDb),
@@ -116,6 +147,18 @@ impl FromStr for Chromatic {
     }
 }
 
+impl HasPitchClass for Chromatic {
+    fn chromatic(&self) -> Chromatic {
+        *self
+    }
+}
+
+impl Transpose for Chromatic {
+    fn checked_transpose(self, semitones: isize) -> Option<Self> {
+        Some(self + semitones)
+    }
+}
+
 impl TryFrom<Chromatic> for Diatonic {
     type Error = ();
     fn try_from(note: Chromatic) -> Result<Self, Self::Error> {
@@ -157,6 +200,34 @@ impl Add<Accidental> for Chromatic {
     }
 }
 
+impl Add<isize> for Chromatic {
+    type Output = Chromatic;
+    fn add(self, rhs: isize) -> Self::Output {
+        self.sharpen(rhs)
+    }
+}
+
+impl Sub<isize> for Chromatic {
+    type Output = Chromatic;
+    fn sub(self, rhs: isize) -> Self::Output {
+        self.flatten(rhs)
+    }
+}
+
+impl Sub for Chromatic {
+    type Output = isize;
+    fn sub(self, rhs: Self) -> Self::Output {
+        rhs.interval_to(self)
+    }
+}
+
+impl Neg for Chromatic {
+    type Output = Chromatic;
+    fn neg(self) -> Self::Output {
+        self.invert()
+    }
+}
+
 impl ShlAssign<isize> for Chromatic {
     fn shl_assign(&mut self, rhs: isize) {
         *self = *self << rhs;
@@ -169,6 +240,18 @@ impl ShrAssign<isize> for Chromatic {
     }
 }
 
+impl AddAssign<isize> for Chromatic {
+    fn add_assign(&mut self, rhs: isize) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign<isize> for Chromatic {
+    fn sub_assign(&mut self, rhs: isize) {
+        *self = *self - rhs;
+    }
+}
+
 impl AddAssign<Accidental> for Chromatic {
     fn add_assign(&mut self, rhs: Accidental) {
         *self = *self + rhs;
@@ -1,9 +1,10 @@
 use crate::note::{Accidental, Chromatic};
+use crate::ParseError;
 use std::fmt;
 use std::ops::{Add, Shl, Shr};
 use std::str::FromStr;
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum Diatonic {
     C = 0,
     D = 2,
@@ -16,6 +17,15 @@ pub enum Diatonic {
 
 impl Diatonic {
     pub const NOTES: u8 = 7;
+    pub const LETTERS: [Self; 7] = [
+        Diatonic::C,
+        Diatonic::D,
+        Diatonic::E,
+        Diatonic::F,
+        Diatonic::G,
+        Diatonic::A,
+        Diatonic::B,
+    ];
     pub const fn name(&self) -> &'static str {
         match self {
             Diatonic::C => "C",
@@ -33,6 +43,26 @@ impl Diatonic {
     pub const fn ord(self) -> isize {
         self as isize
     }
+    pub const fn index(self) -> isize {
+        match self {
+            Diatonic::C => 0,
+            Diatonic::D => 1,
+            Diatonic::E => 2,
+            Diatonic::F => 3,
+            Diatonic::G => 4,
+            Diatonic::A => 5,
+            Diatonic::B => 6,
+        }
+    }
+    pub const fn wrapping_index(index: isize) -> Self {
+        Diatonic::LETTERS[index.rem_euclid(Diatonic::NOTES as isize) as usize]
+    }
+    pub const fn step(self, steps: isize) -> Self {
+        Diatonic::wrapping_index(self.index() + steps)
+    }
+    pub const fn fifths(self) -> isize {
+        (self.index() * 2 + 1).rem_euclid(Diatonic::NOTES as isize) - 1
+    }
     pub const fn checked_chr(ord: isize) -> Option<Self> {
         match ord {
             0 => Some(Diatonic::C),
@@ -63,7 +93,7 @@ impl fmt::Display for Diatonic {
 }
 
 impl FromStr for Diatonic {
-    type Err = ();
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "C" | "c" => Ok(Diatonic::C),
@@ -73,7 +103,7 @@ impl FromStr for Diatonic {
             "G" | "g" => Ok(Diatonic::G),
             "A" | "a" => Ok(Diatonic::A),
             "B" | "b" => Ok(Diatonic::B),
-            _ => Err(()),
+            _ => Err(ParseError::at(s, 0, &["A", "B", "C", "D", "E", "F", "G"])),
         }
     }
 }
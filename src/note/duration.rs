@@ -1,5 +1,11 @@
-use crate::note::Subdivision;
+use crate::note::{Notation, Subdivision};
+use crate::ParseError;
 use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+use std::str::FromStr;
 
 #[derive(Clone, Copy, Debug, Eq)]
 pub struct Duration {
@@ -8,9 +14,136 @@ pub struct Duration {
 }
 
 impl Duration {
+    pub const ZERO: Self = Duration::new(0, Subdivision::WHOLE_NOTE);
     pub const fn new(length: usize, unit: Subdivision) -> Self {
         Duration { length, unit }
     }
+    pub const fn dotted(unit: Subdivision, dots: usize) -> Self {
+        Duration {
+            length: (2 << dots) - 1,
+            unit: Subdivision::new(unit.exponent() + dots),
+        }
+    }
+    pub fn quantize(whole_notes: f64, grid: Subdivision) -> Self {
+        Duration::new(
+            (whole_notes * grid.nth() as f64).round().max(0.0) as usize,
+            grid,
+        )
+        .reduce()
+    }
+    pub fn as_f64(&self) -> f64 {
+        self.length as f64 / self.unit.nth() as f64
+    }
+    pub const fn is_zero(&self) -> bool {
+        self.length == 0
+    }
+    pub const fn reduce(self) -> Self {
+        let (mut length, mut exponent) = (self.length, self.unit.exponent());
+        if length == 0 {
+            return Duration::ZERO;
+        }
+        while exponent > 0 && length.is_multiple_of(2) {
+            length /= 2;
+            exponent -= 1;
+        }
+        Duration::new(length, Subdivision::new(exponent))
+    }
+    pub const fn to_unit(self, unit: Subdivision) -> Option<Self> {
+        let (from, to) = (self.unit.exponent(), unit.exponent());
+        if to >= from {
+            Some(Duration::new(self.length << (to - from), unit))
+        } else if self.length.is_multiple_of(1 << (from - to)) {
+            Some(Duration::new(self.length >> (from - to), unit))
+        } else {
+            None
+        }
+    }
+    pub const fn as_dotted(&self) -> Option<(Subdivision, usize)> {
+        let reduced = self.reduce();
+        let (length, exponent) = (reduced.length, reduced.unit.exponent());
+        if length == 0 || (length + 1) & length != 0 {
+            return None;
+        }
+        let dots = (length + 1).trailing_zeros() as usize - 1;
+        if dots > exponent {
+            return None;
+        }
+        Some((Subdivision::new(exponent - dots), dots))
+    }
+    pub fn to_dotted_parts(&self) -> Vec<(Subdivision, usize)> {
+        let Duration { length, unit } = self.reduce();
+        let exponent = unit.exponent();
+        let mut parts = vec![(Subdivision::WHOLE_NOTE, 0); length >> exponent];
+        let mut rest = length & ((1 << exponent) - 1);
+        while rest != 0 {
+            let high = usize::BITS as usize - 1 - rest.leading_zeros() as usize;
+            let run = (!(rest << (usize::BITS as usize - 1 - high))).leading_zeros() as usize;
+            parts.push((Subdivision::new(exponent - high), run - 1));
+            rest &= (1 << (high + 1 - run)) - 1;
+        }
+        parts
+    }
+    pub const fn checked_sub(self, rhs: Self) -> Option<Self> {
+        let unit = Subdivision::finer(self.unit, rhs.unit);
+        let (lhs, rhs) = match (self.to_unit(unit), rhs.to_unit(unit)) {
+            (Some(lhs), Some(rhs)) => (lhs, rhs),
+            _ => unreachable!(),
+        };
+        match lhs.length.checked_sub(rhs.length) {
+            Some(length) => Some(Duration::new(length, unit).reduce()),
+            None => None,
+        }
+    }
+    pub const fn saturating_sub(self, rhs: Self) -> Self {
+        match self.checked_sub(rhs) {
+            Some(duration) => duration,
+            None => Duration::ZERO,
+        }
+    }
+    pub const fn scale(self, factor: usize) -> Self {
+        Duration::new(self.length * factor, self.unit).reduce()
+    }
+    pub const fn halve(self) -> Self {
+        Duration::new(self.length, Subdivision::new(self.unit.exponent() + 1)).reduce()
+    }
+}
+
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&Notation::of(f).duration(*self))
+    }
+}
+
+impl FromStr for Duration {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unit = s.trim_end_matches('.');
+        let dots = s.len() - unit.len();
+        if dots > 0 {
+            return Ok(Duration::dotted(unit.parse()?, dots));
+        }
+        if let Ok(unit) = Subdivision::from_str(s) {
+            return Ok(unit.to_duration());
+        }
+        let length = |digits: &str, at: usize| {
+            usize::from_str(digits)
+                .map_err(|_| ParseError::unexpected(s, at..at + digits.len(), &["length"]))
+        };
+        let unit = |unit: &str, at: usize| {
+            Subdivision::from_str(unit).map_err(|error: ParseError| error.offset(at))
+        };
+        if let Some((digits, nth)) = s.split_once('/') {
+            return Ok(Duration::new(
+                length(digits, 0)?,
+                unit(nth, digits.len() + 1)?,
+            ));
+        }
+        if let Some((nth, digits)) = s.split_once('*') {
+            return Ok(Duration::new(length(digits, nth.len() + 1)?, unit(nth, 0)?));
+        }
+        let at = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        Ok(Duration::new(length(&s[..at], 0)?, unit(&s[at..], at)?))
+    }
 }
 
 impl From<Duration> for (usize, Subdivision) {
@@ -25,12 +158,26 @@ impl From<(usize, Subdivision)> for Duration {
     }
 }
 
+impl From<Subdivision> for Duration {
+    fn from(unit: Subdivision) -> Self {
+        unit.to_duration()
+    }
+}
+
 impl PartialEq for Duration {
     fn eq(&self, other: &Self) -> bool {
         self.cmp(other) == Ordering::Equal
     }
 }
 
+impl Hash for Duration {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let Duration { length, unit } = self.reduce();
+        length.hash(state);
+        unit.hash(state);
+    }
+}
+
 impl PartialOrd for Duration {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -50,3 +197,46 @@ impl Ord for Duration {
         }
     }
 }
+
+impl Add for Duration {
+    type Output = Duration;
+    fn add(self, rhs: Self) -> Self::Output {
+        let unit = Subdivision::finer(self.unit, rhs.unit);
+        match (self.to_unit(unit), rhs.to_unit(unit)) {
+            (Some(lhs), Some(rhs)) => Duration::new(lhs.length + rhs.length, unit).reduce(),
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl Sub for Duration {
+    type Output = Duration;
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.checked_sub(rhs)
+            .expect("attempt to subtract with overflow")
+    }
+}
+
+impl AddAssign for Duration {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Duration {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Sum for Duration {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Duration::ZERO, Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Duration> for Duration {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
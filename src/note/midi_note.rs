@@ -1,62 +1,155 @@
-use crate::note::{Chromatic, Octave, Pitch, Tone};
+use crate::note::{Chromatic, HasPitchClass, OctaveConvention, Pitch, PitchLike, Tone, Transpose};
+use crate::ParseError;
+use std::error::Error;
 use std::fmt;
 use std::str::FromStr;
 
-#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
-pub struct MidiNote(i8);
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MidiNoteRangeError(pub isize);
+
+impl fmt::Display for MidiNoteRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "MIDI note {} is outside 0..=127", self.0)
+    }
+}
+
+impl Error for MidiNoteRangeError {}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct MidiNote(u8);
 
 impl MidiNote {
-    pub const MIDDLE_C: Self = Pitch::MIDDLE_C.to_midi_note();
-    pub const STUTTGART: Self = Pitch::STUTTGART.to_midi_note();
+    pub const MIDDLE_C: Self = MidiNote::saturating_from_pitch(Pitch::MIDDLE_C);
+    pub const STUTTGART: Self = MidiNote::saturating_from_pitch(Pitch::STUTTGART);
     pub const MIN: Self = MidiNote(0);
     pub const MAX: Self = MidiNote(127);
-    pub const fn new(note: i8) -> Self {
-        MidiNote(note)
+    pub const fn new(note: u8) -> Option<Self> {
+        match MidiNote::try_from_semitones(note as isize) {
+            Ok(note) => Some(note),
+            Err(_) => None,
+        }
     }
-    pub const fn from_pitch(pitch: Pitch) -> Self {
-        MidiNote(
-            pitch.pitch_class.base.ord() as i8
-                + pitch.pitch_class.accidental.pitch_shift() as i8
-                + pitch.octave.numeric() as i8 * 12
-                + 12,
-        )
+    pub const fn from_u7(byte: u8) -> Self {
+        MidiNote(byte & 0x7f)
     }
-    pub const fn from_tone(tone: Tone) -> Self {
-        MidiNote(tone.chromatic.ord() as i8 + tone.octave.numeric() as i8 * 12 + 12)
+    pub const fn try_from_semitones(semitones: isize) -> Result<Self, MidiNoteRangeError> {
+        if semitones >= MidiNote::MIN.0 as isize && semitones <= MidiNote::MAX.0 as isize {
+            Ok(MidiNote(semitones as u8))
+        } else {
+            Err(MidiNoteRangeError(semitones))
+        }
     }
-    pub const fn to_tone(self) -> Tone {
-        Tone {
-            chromatic: Chromatic::wrapping_chr(self.0 as isize % 12),
-            octave: Octave::new(self.0 as isize / 12 - 1),
+    pub const fn saturating_from_semitones(semitones: isize) -> Self {
+        if semitones < MidiNote::MIN.0 as isize {
+            MidiNote::MIN
+        } else if semitones > MidiNote::MAX.0 as isize {
+            MidiNote::MAX
+        } else {
+            MidiNote(semitones as u8)
         }
     }
-    pub const fn as_i8(&self) -> i8 {
+    pub const fn wrapping_from_semitones(semitones: isize) -> Self {
+        MidiNote(semitones.rem_euclid(MidiNote::MAX.0 as isize + 1) as u8)
+    }
+    pub const fn try_from_pitch(pitch: Pitch) -> Result<Self, MidiNoteRangeError> {
+        MidiNote::try_from_semitones(pitch.semitones())
+    }
+    pub const fn saturating_from_pitch(pitch: Pitch) -> Self {
+        MidiNote::saturating_from_semitones(pitch.semitones())
+    }
+    pub const fn wrapping_from_pitch(pitch: Pitch) -> Self {
+        MidiNote::wrapping_from_semitones(pitch.semitones())
+    }
+    pub const fn try_from_tone(tone: Tone) -> Result<Self, MidiNoteRangeError> {
+        MidiNote::try_from_semitones(tone.semitones())
+    }
+    pub const fn saturating_from_tone(tone: Tone) -> Self {
+        MidiNote::saturating_from_semitones(tone.semitones())
+    }
+    pub const fn wrapping_from_tone(tone: Tone) -> Self {
+        MidiNote::wrapping_from_semitones(tone.semitones())
+    }
+    pub const fn to_tone(self) -> Tone {
+        Tone::from_semitones(self.0 as isize)
+    }
+    pub fn name(&self, convention: OctaveConvention) -> String {
+        self.to_tone().to_string_with(convention)
+    }
+    pub fn parse_name(s: &str, convention: OctaveConvention) -> Result<Self, ParseError> {
+        let pitch = Pitch::parse_with(s, convention)?;
+        MidiNote::try_from_pitch(pitch)
+            .map_err(|_| ParseError::out_of_range(s, 0..s.len(), 0, MidiNote::MAX.0 as isize))
+    }
+    pub const fn as_u8(&self) -> u8 {
         self.0
     }
+    pub const fn as_i8(&self) -> i8 {
+        self.0 as i8
+    }
+    pub const fn checked_add(self, semitones: isize) -> Option<Self> {
+        match MidiNote::try_from_semitones(self.0 as isize + semitones) {
+            Ok(note) => Some(note),
+            Err(_) => None,
+        }
+    }
+    pub const fn saturating_add(self, semitones: isize) -> Self {
+        MidiNote::saturating_from_semitones(self.0 as isize + semitones)
+    }
+    pub const fn wrapping_add(self, semitones: isize) -> Self {
+        MidiNote::wrapping_from_semitones(self.0 as isize + semitones)
+    }
 }
 
 impl fmt::Display for MidiNote {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        self.as_i8().fmt(f)
+        self.as_u8().fmt(f)
     }
 }
 
 impl FromStr for MidiNote {
-    type Err = ();
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        i8::from_str(s).map(MidiNote::new).map_err(|_| ())
+        let note = isize::from_str(s).map_err(|_| ParseError::at(s, 0, &["MIDI note number"]))?;
+        MidiNote::try_from_semitones(note)
+            .map_err(|_| ParseError::out_of_range(s, 0..s.len(), 0, MidiNote::MAX.0 as isize))
+    }
+}
+
+impl HasPitchClass for MidiNote {
+    fn chromatic(&self) -> Chromatic {
+        self.to_tone().chromatic
+    }
+}
+
+impl Transpose for MidiNote {
+    fn checked_transpose(self, semitones: isize) -> Option<Self> {
+        self.checked_add(semitones)
     }
 }
 
-impl From<Pitch> for MidiNote {
-    fn from(pitch: Pitch) -> Self {
-        MidiNote::from_pitch(pitch)
+impl PitchLike for MidiNote {
+    fn semitones(&self) -> isize {
+        self.0 as isize
     }
 }
 
-impl From<Tone> for MidiNote {
-    fn from(tone: Tone) -> Self {
-        MidiNote::from_tone(tone)
+impl TryFrom<Pitch> for MidiNote {
+    type Error = MidiNoteRangeError;
+    fn try_from(pitch: Pitch) -> Result<Self, Self::Error> {
+        MidiNote::try_from_pitch(pitch)
+    }
+}
+
+impl TryFrom<Tone> for MidiNote {
+    type Error = MidiNoteRangeError;
+    fn try_from(tone: Tone) -> Result<Self, Self::Error> {
+        MidiNote::try_from_tone(tone)
+    }
+}
+
+impl From<MidiNote> for u8 {
+    fn from(note: MidiNote) -> Self {
+        note.as_u8()
     }
 }
 
@@ -66,8 +159,16 @@ impl From<MidiNote> for i8 {
     }
 }
 
-impl From<i8> for MidiNote {
-    fn from(note: i8) -> Self {
-        MidiNote::new(note)
+impl TryFrom<u8> for MidiNote {
+    type Error = MidiNoteRangeError;
+    fn try_from(note: u8) -> Result<Self, Self::Error> {
+        MidiNote::try_from_semitones(note as isize)
+    }
+}
+
+impl TryFrom<i8> for MidiNote {
+    type Error = MidiNoteRangeError;
+    fn try_from(note: i8) -> Result<Self, Self::Error> {
+        MidiNote::try_from_semitones(note as isize)
     }
 }
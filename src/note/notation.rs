@@ -0,0 +1,141 @@
+use crate::note::{
+    Accidental, Chromatic, Diatonic, Duration, Octave, Pitch, PitchClass, Subdivision, Tone,
+};
+use crate::ParseError;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Notation {
+    #[default]
+    Unicode,
+    Ascii,
+    LilyPond,
+}
+
+impl Notation {
+    pub fn of(f: &fmt::Formatter) -> Self {
+        if f.alternate() {
+            Notation::Ascii
+        } else {
+            Notation::Unicode
+        }
+    }
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Notation::Unicode => "unicode",
+            Notation::Ascii => "ascii",
+            Notation::LilyPond => "lilypond",
+        }
+    }
+    pub fn accidental(&self, accidental: Accidental) -> String {
+        let shift = accidental.pitch_shift();
+        let (single, double) = match (self, shift) {
+            (Notation::Unicode, 0) => return "\u{266e}".to_string(),
+            (Notation::Ascii, 0) => return "n".to_string(),
+            (Notation::LilyPond, _) if shift >= 0 => return "is".repeat(shift as usize),
+            (Notation::LilyPond, _) => return "es".repeat(-shift as usize),
+            (Notation::Unicode, 1..) => ("\u{266f}", "\u{1d12a}"),
+            (Notation::Unicode, _) => ("\u{266d}", "\u{1d12b}"),
+            (Notation::Ascii, 1..) => ("#", "x"),
+            (Notation::Ascii, _) => ("b", "bb"),
+        };
+        let count = shift.unsigned_abs();
+        format!(
+            "{}{}",
+            if count % 2 == 1 { single } else { "" },
+            double.repeat(count / 2)
+        )
+    }
+    pub fn pitch_class(&self, pitch_class: PitchClass) -> String {
+        let accidental = match pitch_class.accidental {
+            Accidental::NATURAL => String::new(),
+            accidental => self.accidental(accidental),
+        };
+        match self {
+            Notation::LilyPond => {
+                format!("{}{}", pitch_class.base.name().to_lowercase(), accidental)
+            }
+            _ => format!("{}{}", pitch_class.base, accidental),
+        }
+    }
+    pub fn pitch(&self, pitch: Pitch) -> String {
+        match self {
+            Notation::LilyPond => format!(
+                "{}{}",
+                self.pitch_class(pitch.pitch_class),
+                octave_marks(pitch.octave)
+            ),
+            _ => format!("{}{}", self.pitch_class(pitch.pitch_class), pitch.octave),
+        }
+    }
+    pub fn chromatic(&self, chromatic: Chromatic) -> String {
+        match chromatic.try_diatonic() {
+            Some(base) => self.pitch_class(PitchClass::new(base, Accidental::NATURAL)),
+            None => {
+                let spell = |ord: isize, accidental: Accidental| match Diatonic::checked_chr(ord) {
+                    Some(base) => self.pitch_class(PitchClass::new(base, accidental)),
+                    None => unreachable!(),
+                };
+                format!(
+                    "{}/{}",
+                    spell(chromatic.ord() - 1, Accidental::SHARP),
+                    spell(chromatic.ord() + 1, Accidental::FLAT)
+                )
+            }
+        }
+    }
+    pub fn tone(&self, tone: Tone) -> String {
+        match self {
+            Notation::LilyPond => format!(
+                "{}{}",
+                self.chromatic(tone.chromatic),
+                octave_marks(tone.octave)
+            ),
+            _ => format!("{}{}", self.chromatic(tone.chromatic), tone.octave),
+        }
+    }
+    pub fn subdivision(&self, subdivision: Subdivision) -> String {
+        match (self, subdivision.exponent()) {
+            (Notation::Unicode, ..=7) => subdivision.name().to_string(),
+            (Notation::LilyPond, _) => subdivision.nth().to_string(),
+            _ => format!("1/{}", subdivision.nth()),
+        }
+    }
+    pub fn duration(&self, duration: Duration) -> String {
+        if let Some((unit, dots)) = duration.as_dotted() {
+            return format!("{}{}", self.subdivision(unit), ".".repeat(dots));
+        }
+        let Duration { length, unit } = duration.reduce();
+        match self {
+            Notation::Unicode => format!("{}{}", length, self.subdivision(unit)),
+            Notation::Ascii => format!("{}/{}", length, unit.nth()),
+            Notation::LilyPond => format!("{}*{}", unit.nth(), length),
+        }
+    }
+}
+
+fn octave_marks(octave: Octave) -> String {
+    match octave.numeric() {
+        octave @ 3.. => "'".repeat((octave - 3) as usize),
+        octave => ",".repeat((3 - octave) as usize),
+    }
+}
+
+impl fmt::Display for Notation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Notation {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "unicode" => Ok(Notation::Unicode),
+            "ascii" => Ok(Notation::Ascii),
+            "lilypond" | "ly" => Ok(Notation::LilyPond),
+            _ => Err(ParseError::at(s, 0, &["unicode", "ascii", "lilypond"])),
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use crate::note::{Duration, Pitch};
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Note {
     pub pitch: Pitch,
     pub value: Duration,
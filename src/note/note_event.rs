@@ -0,0 +1,184 @@
+use crate::note::{Duration, Note, Pitch, Tuplet};
+use std::slice;
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Tie {
+    pub start: bool,
+    pub stop: bool,
+}
+
+impl Tie {
+    pub const NONE: Self = Tie::new(false, false);
+    pub const START: Self = Tie::new(true, false);
+    pub const STOP: Self = Tie::new(false, true);
+    pub const CONTINUE: Self = Tie::new(true, true);
+    pub const fn new(start: bool, stop: bool) -> Self {
+        Tie { start, stop }
+    }
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum NoteEvent {
+    Rest {
+        value: Duration,
+    },
+    Single {
+        pitch: Pitch,
+        value: Duration,
+        tie: Tie,
+    },
+    Chord {
+        pitches: Vec<Pitch>,
+        value: Duration,
+        tie: Tie,
+    },
+    Tuplet {
+        tuplet: Tuplet,
+        value: Duration,
+        events: Vec<NoteEvent>,
+    },
+}
+
+impl NoteEvent {
+    pub const fn rest(value: Duration) -> Self {
+        NoteEvent::Rest { value }
+    }
+    pub const fn single(pitch: Pitch, value: Duration) -> Self {
+        NoteEvent::Single {
+            pitch,
+            value,
+            tie: Tie::NONE,
+        }
+    }
+    pub fn chord(pitches: impl Into<Vec<Pitch>>, value: Duration) -> Self {
+        NoteEvent::Chord {
+            pitches: pitches.into(),
+            value,
+            tie: Tie::NONE,
+        }
+    }
+    pub fn tuplet(tuplet: Tuplet, events: impl Into<Vec<NoteEvent>>) -> Option<Self> {
+        let events = events.into();
+        Some(NoteEvent::Tuplet {
+            tuplet,
+            value: tuplet.sounding(NoteEvent::total_duration(&events))?,
+            events,
+        })
+    }
+    pub const fn value(&self) -> Duration {
+        match self {
+            NoteEvent::Rest { value }
+            | NoteEvent::Single { value, .. }
+            | NoteEvent::Chord { value, .. }
+            | NoteEvent::Tuplet { value, .. } => *value,
+        }
+    }
+    pub fn set_value(&mut self, duration: Duration) {
+        match self {
+            NoteEvent::Rest { value }
+            | NoteEvent::Single { value, .. }
+            | NoteEvent::Chord { value, .. }
+            | NoteEvent::Tuplet { value, .. } => *value = duration,
+        }
+    }
+    pub fn with_value(mut self, value: Duration) -> Self {
+        self.set_value(value);
+        self
+    }
+    pub const fn tie(&self) -> Tie {
+        match self {
+            NoteEvent::Rest { .. } | NoteEvent::Tuplet { .. } => Tie::NONE,
+            NoteEvent::Single { tie, .. } | NoteEvent::Chord { tie, .. } => *tie,
+        }
+    }
+    pub fn set_tie(&mut self, new: Tie) {
+        match self {
+            NoteEvent::Rest { .. } | NoteEvent::Tuplet { .. } => {}
+            NoteEvent::Single { tie, .. } | NoteEvent::Chord { tie, .. } => *tie = new,
+        }
+    }
+    pub fn with_tie(mut self, tie: Tie) -> Self {
+        self.set_tie(tie);
+        self
+    }
+    pub fn pitches(&self) -> &[Pitch] {
+        match self {
+            NoteEvent::Rest { .. } | NoteEvent::Tuplet { .. } => &[],
+            NoteEvent::Single { pitch, .. } => slice::from_ref(pitch),
+            NoteEvent::Chord { pitches, .. } => pitches,
+        }
+    }
+    pub const fn is_rest(&self) -> bool {
+        matches!(self, NoteEvent::Rest { .. })
+    }
+    pub const fn is_tuplet(&self) -> bool {
+        matches!(self, NoteEvent::Tuplet { .. })
+    }
+    pub fn leaves(&self) -> Vec<(f64, f64, &NoteEvent)> {
+        let NoteEvent::Tuplet { tuplet, events, .. } = self else {
+            return vec![(0.0, self.value().as_f64(), self)];
+        };
+        let ratio = tuplet.ratio();
+        let mut leaves = Vec::new();
+        let mut offset = 0.0;
+        for event in events {
+            for (start, length, leaf) in event.leaves() {
+                leaves.push((offset + start * ratio, length * ratio, leaf));
+            }
+            offset += event.value().as_f64() * ratio;
+        }
+        leaves
+    }
+    pub fn split_at(&self, at: Duration) -> Option<(NoteEvent, NoteEvent)> {
+        let rest = self.value().checked_sub(at)?;
+        if at.is_zero() || rest.is_zero() || self.is_tuplet() {
+            return None;
+        }
+        let tie = self.tie();
+        let head = self
+            .clone()
+            .with_value(at)
+            .with_tie(Tie::new(true, tie.stop));
+        let tail = self
+            .clone()
+            .with_value(rest)
+            .with_tie(Tie::new(tie.start, true));
+        Some((head, tail))
+    }
+    pub fn ties_into(&self, next: &NoteEvent) -> bool {
+        !self.is_rest() && self.tie().start && next.tie().stop && self.pitches() == next.pitches()
+    }
+    pub fn total_duration<'a>(events: impl IntoIterator<Item = &'a NoteEvent>) -> Duration {
+        events.into_iter().map(NoteEvent::value).sum()
+    }
+    pub fn merge_ties(events: impl IntoIterator<Item = NoteEvent>) -> Vec<NoteEvent> {
+        let mut merged: Vec<NoteEvent> = Vec::new();
+        for event in events {
+            match merged.last_mut() {
+                Some(last) if last.ties_into(&event) => {
+                    let tie = Tie::new(event.tie().start, last.tie().stop);
+                    last.set_value(last.value() + event.value());
+                    last.set_tie(tie);
+                }
+                _ => merged.push(event),
+            }
+        }
+        merged
+    }
+}
+
+impl From<Note> for NoteEvent {
+    fn from(Note { pitch, value }: Note) -> Self {
+        NoteEvent::single(pitch, value)
+    }
+}
+
+impl TryFrom<NoteEvent> for Note {
+    type Error = ();
+    fn try_from(event: NoteEvent) -> Result<Self, Self::Error> {
+        match event {
+            NoteEvent::Single { pitch, value, .. } => Ok(Note::new(pitch, value)),
+            _ => Err(()),
+        }
+    }
+}
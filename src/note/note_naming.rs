@@ -0,0 +1,318 @@
+use crate::note::{Accidental, Diatonic, Notation, PitchClass};
+use crate::scale::Key;
+use crate::ParseError;
+use std::fmt;
+use std::str::FromStr;
+
+const FIXED_DO: [&str; 7] = ["Do", "Ré", "Mi", "Fa", "Sol", "La", "Si"];
+const MOVABLE_DO: [&str; 7] = ["Do", "Re", "Mi", "Fa", "Sol", "La", "Ti"];
+const RAISED_DO: [Option<&str>; 7] = [
+    Some("Di"),
+    Some("Ri"),
+    None,
+    Some("Fi"),
+    Some("Si"),
+    Some("Li"),
+    None,
+];
+const LOWERED_DO: [Option<&str>; 7] = [
+    None,
+    Some("Ra"),
+    Some("Me"),
+    None,
+    Some("Se"),
+    Some("Le"),
+    Some("Te"),
+];
+const IROHA: [&str; 7] = ["ハ", "ニ", "ホ", "ヘ", "ト", "イ", "ロ"];
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum NoteNaming {
+    #[default]
+    English,
+    German,
+    FixedDo,
+    MovableDo(Key),
+    Iroha,
+}
+
+impl NoteNaming {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            NoteNaming::English => "english",
+            NoteNaming::German => "german",
+            NoteNaming::FixedDo => "fixed do",
+            NoteNaming::MovableDo(_) => "movable do",
+            NoteNaming::Iroha => "iroha",
+        }
+    }
+    pub fn format(&self, pitch_class: PitchClass) -> String {
+        let PitchClass { base, accidental } = pitch_class;
+        let shift = accidental.pitch_shift();
+        match self {
+            NoteNaming::English => format!("{}{}", base, suffix(accidental)),
+            NoteNaming::German => match (base, shift) {
+                (Diatonic::B, -1) => "B".to_string(),
+                (Diatonic::B, _) => format!("H{}", german_suffix(shift)),
+                (Diatonic::E | Diatonic::A, ..=-1) => {
+                    format!("{}s{}", base, "es".repeat((-shift - 1) as usize))
+                }
+                _ => format!("{}{}", base, german_suffix(shift)),
+            },
+            NoteNaming::FixedDo => {
+                format!("{}{}", FIXED_DO[base.index() as usize], suffix(accidental))
+            }
+            NoteNaming::MovableDo(key) => {
+                let (degree, alteration) = movable_degree(key, pitch_class);
+                let chromatic = match alteration {
+                    1 => RAISED_DO[degree],
+                    -1 => LOWERED_DO[degree],
+                    _ => None,
+                };
+                match (alteration, chromatic) {
+                    (_, Some(syllable)) => syllable.to_string(),
+                    (0, None) => MOVABLE_DO[degree].to_string(),
+                    (_, None) => format!("{}{}", MOVABLE_DO[degree], Accidental::new(alteration)),
+                }
+            }
+            NoteNaming::Iroha => {
+                let kana = IROHA[base.index() as usize];
+                match shift {
+                    0 => kana.to_string(),
+                    1 => format!("嬰{kana}"),
+                    -1 => format!("変{kana}"),
+                    2 => format!("重嬰{kana}"),
+                    -2 => format!("重変{kana}"),
+                    _ => format!("{kana}{accidental}"),
+                }
+            }
+        }
+    }
+    pub fn parse(&self, s: &str) -> Result<PitchClass, ParseError> {
+        match self {
+            NoteNaming::English => s.parse(),
+            NoteNaming::German => {
+                let (letter, mut rest) = s.split_at(s.chars().next().map_or(0, char::len_utf8));
+                let (base, mut shift) = match letter {
+                    "H" | "h" => (Diatonic::B, 0),
+                    "B" | "b" => (Diatonic::B, -1),
+                    "C" | "c" | "D" | "d" | "F" | "f" | "G" | "g" => (letter.parse()?, 0),
+                    "E" | "e" | "A" | "a" if rest.starts_with('s') => {
+                        rest = &rest[1..];
+                        (letter.parse()?, -1)
+                    }
+                    "E" | "e" | "A" | "a" => (letter.parse()?, 0),
+                    _ => {
+                        return Err(ParseError::char_at(
+                            s,
+                            0,
+                            &["A", "B", "C", "D", "E", "F", "G", "H"],
+                        ))
+                    }
+                };
+                while !rest.is_empty() {
+                    let at = s.len() - rest.len();
+                    match rest.get(..2).map(str::to_lowercase).as_deref() {
+                        Some("is") if shift >= 0 => shift += 1,
+                        Some("es") if shift <= 0 => shift -= 1,
+                        _ => {
+                            return Err(ParseError::at(
+                                s,
+                                at,
+                                match shift {
+                                    1.. => &["is"],
+                                    0 => &["is", "es"],
+                                    _ => &["es"],
+                                },
+                            ))
+                        }
+                    }
+                    rest = &rest[2..];
+                }
+                Ok(PitchClass::new(base, Accidental::new(shift)))
+            }
+            NoteNaming::FixedDo => {
+                let (index, at) = [
+                    ("sol", 4),
+                    ("do", 0),
+                    ("ut", 0),
+                    ("ré", 1),
+                    ("re", 1),
+                    ("mi", 2),
+                    ("fa", 3),
+                    ("so", 4),
+                    ("la", 5),
+                    ("si", 6),
+                    ("ti", 6),
+                ]
+                .into_iter()
+                .find(|(syllable, _)| starts_with_ignore_case(s, syllable))
+                .map(|(syllable, index)| (index, syllable.len()))
+                .ok_or(ParseError::at(
+                    s,
+                    0,
+                    &["Do", "Ré", "Mi", "Fa", "Sol", "La", "Si"],
+                ))?;
+                Ok(PitchClass::new(
+                    Diatonic::LETTERS[index],
+                    parse_accidental(s, at)?,
+                ))
+            }
+            NoteNaming::MovableDo(key) => {
+                let chromatic = (0..Diatonic::NOTES as usize).find_map(|degree| {
+                    let matches = |syllable: Option<&str>| {
+                        syllable.is_some_and(|syllable| syllable.eq_ignore_ascii_case(s))
+                    };
+                    if matches(RAISED_DO[degree]) {
+                        Some((degree, 1))
+                    } else if matches(LOWERED_DO[degree]) {
+                        Some((degree, -1))
+                    } else {
+                        None
+                    }
+                });
+                let (degree, alteration) = match chromatic {
+                    Some(found) => found,
+                    None => {
+                        let (degree, at) = [
+                            ("sol", 4),
+                            ("do", 0),
+                            ("re", 1),
+                            ("mi", 2),
+                            ("fa", 3),
+                            ("so", 4),
+                            ("la", 5),
+                            ("ti", 6),
+                        ]
+                        .into_iter()
+                        .find(|(syllable, _)| starts_with_ignore_case(s, syllable))
+                        .map(|(syllable, degree)| (degree, syllable.len()))
+                        .ok_or(ParseError::at(
+                            s,
+                            0,
+                            &[
+                                "Do", "Di", "Ra", "Re", "Ri", "Me", "Mi", "Fa", "Fi", "Se", "Sol",
+                                "Si", "Le", "La", "Li", "Te", "Ti",
+                            ],
+                        ))?;
+                        (degree, parse_accidental(s, at)?.pitch_shift())
+                    }
+                };
+                let expected = Key::major(tonic(key)).degree(degree as isize);
+                Ok(PitchClass::new(
+                    expected.base,
+                    expected.accidental.sharpen(alteration),
+                ))
+            }
+            NoteNaming::Iroha => {
+                let (shift, rest) = [("重嬰", 2), ("重変", -2), ("嬰", 1), ("変", -1)]
+                    .into_iter()
+                    .find_map(|(prefix, shift)| Some((shift, s.strip_prefix(prefix)?)))
+                    .unwrap_or((0, s));
+                let at = s.len() - rest.len();
+                let index = IROHA
+                    .iter()
+                    .position(|kana| rest.starts_with(kana))
+                    .ok_or(ParseError::char_at(s, at, &IROHA))?;
+                let at = at + IROHA[index].len();
+                let accidental = match shift {
+                    0 => parse_accidental(s, at)?,
+                    _ if at == s.len() => Accidental::new(shift),
+                    _ => return Err(ParseError::at(s, at, &[])),
+                };
+                Ok(PitchClass::new(Diatonic::LETTERS[index], accidental))
+            }
+        }
+    }
+}
+
+fn suffix(accidental: Accidental) -> String {
+    match accidental {
+        Accidental::NATURAL => String::new(),
+        accidental => Notation::Unicode.accidental(accidental),
+    }
+}
+
+fn german_suffix(shift: isize) -> String {
+    match shift {
+        0.. => "is".repeat(shift as usize),
+        _ => "es".repeat(-shift as usize),
+    }
+}
+
+fn starts_with_ignore_case(s: &str, prefix: &str) -> bool {
+    s.get(..prefix.len())
+        .is_some_and(|head| head.to_lowercase() == prefix)
+}
+
+fn parse_accidental(s: &str, at: usize) -> Result<Accidental, ParseError> {
+    let rest = s[at..].trim_start();
+    let at = s.len() - rest.len();
+    match rest.trim_end().to_lowercase().as_str() {
+        "dièse" | "diese" | "diesis" | "sostenido" => Ok(Accidental::SHARP),
+        "bémol" | "bemol" | "bemolle" => Ok(Accidental::FLAT),
+        "double dièse" | "doppio diesis" | "doble sostenido" => Ok(Accidental::DOUBLE_SHARP),
+        "double bémol" | "doppio bemolle" | "doble bemol" => Ok(Accidental::DOUBLE_FLAT),
+        _ => Accidental::from_str(rest.trim_end()).map_err(|error| error.offset(at)),
+    }
+}
+
+const fn tonic(key: &Key) -> PitchClass {
+    PitchClass::from_fifths(key.fifths())
+}
+
+const fn movable_degree(key: &Key, pitch_class: PitchClass) -> (usize, isize) {
+    let tonic = tonic(key);
+    let degree =
+        (pitch_class.base.index() - tonic.base.index()).rem_euclid(Diatonic::NOTES as isize);
+    let expected = Key::major(tonic).degree(degree);
+    let alteration = (pitch_class.fifths() - expected.fifths()) / Diatonic::NOTES as isize;
+    (degree as usize, alteration)
+}
+
+impl fmt::Display for NoteNaming {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NoteNaming::MovableDo(key) => write!(f, "{} in {}", self.name(), key),
+            _ => f.write_str(self.name()),
+        }
+    }
+}
+
+impl FromStr for NoteNaming {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.to_lowercase();
+        if let Some(prefix) = [
+            "movable do in ",
+            "movable-do in ",
+            "movable do ",
+            "movable-do ",
+        ]
+        .into_iter()
+        .find(|prefix| lower.starts_with(prefix))
+        {
+            let key = Key::from_str(&s[prefix.len()..]);
+            return Ok(NoteNaming::MovableDo(
+                key.map_err(|error| error.offset(prefix.len()))?,
+            ));
+        }
+        match lower.as_str() {
+            "english" => Ok(NoteNaming::English),
+            "german" | "deutsch" => Ok(NoteNaming::German),
+            "fixed do" | "fixed-do" | "solfege" | "solfège" => Ok(NoteNaming::FixedDo),
+            "iroha" | "japanese" => Ok(NoteNaming::Iroha),
+            _ => Err(ParseError::at(
+                s,
+                0,
+                &[
+                    "english",
+                    "german",
+                    "fixed do",
+                    "movable do in <key>",
+                    "iroha",
+                ],
+            )),
+        }
+    }
+}
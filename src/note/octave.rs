@@ -1,7 +1,8 @@
+use crate::ParseError;
 use std::fmt;
 use std::str::FromStr;
 
-#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Octave(isize);
 
 impl Octave {
@@ -20,9 +21,11 @@ impl fmt::Display for Octave {
 }
 
 impl FromStr for Octave {
-    type Err = ();
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        isize::from_str(s).map(Octave::new).map_err(|_| ())
+        isize::from_str(s)
+            .map(Octave::new)
+            .map_err(|_| ParseError::at(s, 0, &["octave number"]))
     }
 }
 
@@ -0,0 +1,110 @@
+use crate::note::Octave;
+use crate::ParseError;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum OctaveConvention {
+    #[default]
+    Scientific,
+    Yamaha,
+    Helmholtz,
+}
+
+impl OctaveConvention {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            OctaveConvention::Scientific => "scientific",
+            OctaveConvention::Yamaha => "yamaha",
+            OctaveConvention::Helmholtz => "helmholtz",
+        }
+    }
+    pub const fn middle_c(&self) -> &'static str {
+        match self {
+            OctaveConvention::Scientific => "C4",
+            OctaveConvention::Yamaha => "C3",
+            OctaveConvention::Helmholtz => "c'",
+        }
+    }
+    pub fn format(&self, name: &str, octave: Octave) -> String {
+        match self {
+            OctaveConvention::Scientific => format!("{name}{octave}"),
+            OctaveConvention::Yamaha => format!("{name}{}", octave.numeric() - 1),
+            OctaveConvention::Helmholtz => match octave.numeric() {
+                octave @ ..=2 => format!("{}{}", name, ",".repeat((2 - octave) as usize)),
+                octave => format!(
+                    "{}{}",
+                    name.to_lowercase(),
+                    "'".repeat((octave - 3) as usize)
+                ),
+            },
+        }
+    }
+    pub fn split<'a>(&self, s: &'a str) -> Result<(&'a str, Octave), ParseError> {
+        let first = s
+            .chars()
+            .next()
+            .ok_or(ParseError::at(s, 0, &["note name"]))?;
+        match self {
+            OctaveConvention::Scientific | OctaveConvention::Yamaha => {
+                let start = first.len_utf8();
+                let at = start
+                    + s[start..]
+                        .find(|c: char| c.is_ascii_digit() || c == '-')
+                        .ok_or(ParseError::UnexpectedEnd {
+                            at: s.len(),
+                            expected: &["octave number"],
+                        })?;
+                let (name, octave) = s.split_at(at);
+                let octave = Octave::from_str(octave).map_err(|error| error.offset(at))?;
+                match self {
+                    OctaveConvention::Yamaha => Ok((name, Octave::new(octave.numeric() + 1))),
+                    _ => Ok((name, octave)),
+                }
+            }
+            OctaveConvention::Helmholtz => {
+                let name = s.trim_end_matches([',', '\'', '\u{2032}']);
+                let marks = &s[name.len()..];
+                let (commas, primes) = (
+                    marks.matches(',').count() as isize,
+                    marks.matches(['\'', '\u{2032}']).count() as isize,
+                );
+                match first {
+                    c if c.is_ascii_uppercase() && primes == 0 => {
+                        Ok((name, Octave::new(2 - commas)))
+                    }
+                    c if c.is_ascii_lowercase() && commas == 0 => {
+                        Ok((name, Octave::new(3 + primes)))
+                    }
+                    c if c.is_ascii_uppercase() => Err(ParseError::at(s, name.len(), &[","])),
+                    c if c.is_ascii_lowercase() => {
+                        Err(ParseError::at(s, name.len(), &["'", "\u{2032}"]))
+                    }
+                    _ => Err(ParseError::char_at(
+                        s,
+                        0,
+                        &["uppercase note name", "lowercase note name"],
+                    )),
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for OctaveConvention {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for OctaveConvention {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "scientific" | "spn" | "c4" => Ok(OctaveConvention::Scientific),
+            "yamaha" | "roland" | "c3" => Ok(OctaveConvention::Yamaha),
+            "helmholtz" | "c'" => Ok(OctaveConvention::Helmholtz),
+            _ => Err(ParseError::at(s, 0, &["scientific", "yamaha", "helmholtz"])),
+        }
+    }
+}
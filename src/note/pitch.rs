@@ -1,9 +1,13 @@
-use crate::note::{Accidental, Diatonic, MidiNote, Octave, PitchClass, Tone};
+use crate::note::{
+    Accidental, Chromatic, Diatonic, HasPitchClass, MidiNote, MidiNoteRangeError, Notation,
+    NoteNaming, Octave, OctaveConvention, PitchClass, PitchLike, Tone, Transpose,
+};
+use crate::ParseError;
 use std::cmp::Ordering;
 use std::fmt;
 use std::str::FromStr;
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct Pitch {
     pub pitch_class: PitchClass,
     pub octave: Octave,
@@ -40,34 +44,77 @@ impl Pitch {
             octave,
         }
     }
+    pub const fn semitones(&self) -> isize {
+        self.pitch_class.base.ord()
+            + self.pitch_class.accidental.pitch_shift()
+            + (self.octave.numeric() + 1) * Chromatic::NOTES as isize
+    }
     pub const fn to_tone(self) -> Tone {
-        self.to_midi_note().to_tone()
+        Tone::from_semitones(self.semitones())
     }
     pub const fn to_midi_note(self) -> MidiNote {
-        MidiNote::from_pitch(self)
+        MidiNote::saturating_from_pitch(self)
+    }
+    pub const fn try_to_midi_note(self) -> Result<MidiNote, MidiNoteRangeError> {
+        MidiNote::try_from_pitch(self)
     }
     pub const fn enharmonic(&self, other: &Self) -> bool {
-        self.to_midi_note().as_i8() == other.to_midi_note().as_i8()
+        self.semitones() == other.semitones()
+    }
+    pub fn to_string_with(&self, convention: OctaveConvention) -> String {
+        convention.format(&self.pitch_class.to_string(), self.octave)
+    }
+    pub fn parse_with(s: &str, convention: OctaveConvention) -> Result<Self, ParseError> {
+        let (pitch_class, octave) = convention.split(s)?;
+        Ok(Pitch::new(pitch_class.parse()?, octave))
+    }
+    pub fn to_string_in(&self, naming: NoteNaming, convention: OctaveConvention) -> String {
+        convention.format(&naming.format(self.pitch_class), self.octave)
+    }
+    pub fn parse_in(
+        s: &str,
+        naming: NoteNaming,
+        convention: OctaveConvention,
+    ) -> Result<Self, ParseError> {
+        let (pitch_class, octave) = convention.split(s)?;
+        Ok(Pitch::new(naming.parse(pitch_class)?, octave))
     }
 }
 
 impl fmt::Display for Pitch {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}{}", self.pitch_class, self.octave)
+        f.write_str(&Notation::of(f).pitch(*self))
     }
 }
 
 impl FromStr for Pitch {
-    type Err = ();
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (pitch_class, octave) = s
-            .split_once(|c: char| c.is_ascii_digit() || c == '-')
-            .ok_or(())?;
-        let (pitch_class, octave) = (pitch_class.parse()?, octave.parse()?);
-        Ok(Pitch {
-            pitch_class,
-            octave,
-        })
+        Pitch::parse_with(s, OctaveConvention::Scientific)
+    }
+}
+
+impl HasPitchClass for Pitch {
+    fn chromatic(&self) -> Chromatic {
+        self.pitch_class.chromatic()
+    }
+}
+
+impl Transpose for Pitch {
+    fn checked_transpose(self, semitones: isize) -> Option<Self> {
+        let pitch_class = self.pitch_class.transpose(semitones);
+        let offset = pitch_class.base.ord() + pitch_class.accidental.pitch_shift();
+        let octave = (self.semitones() + semitones - offset).div_euclid(Chromatic::NOTES as isize);
+        Some(Pitch::new(pitch_class, Octave::new(octave - 1)))
+    }
+}
+
+impl PitchLike for Pitch {
+    fn semitones(&self) -> isize {
+        Pitch::semitones(self)
+    }
+    fn octave(&self) -> Octave {
+        self.octave
     }
 }
 
@@ -105,6 +152,8 @@ impl PartialOrd for Pitch {
 
 impl Ord for Pitch {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.to_midi_note().cmp(&other.to_midi_note())
+        self.semitones()
+            .cmp(&other.semitones())
+            .then_with(|| self.pitch_class.fifths().cmp(&other.pitch_class.fifths()))
     }
 }
@@ -1,8 +1,11 @@
-use crate::note::{Accidental, Chromatic, Diatonic};
+use crate::note::{
+    Accidental, Chromatic, Diatonic, HasPitchClass, Notation, NoteNaming, Transpose,
+};
+use crate::ParseError;
 use std::fmt;
 use std::str::FromStr;
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct PitchClass {
     pub base: Diatonic,
     pub accidental: Accidental,
@@ -15,29 +18,70 @@ impl PitchClass {
     pub const fn chromatic(self) -> Chromatic {
         self.base.with(self.accidental)
     }
+    pub const fn from_fifths(fifths: isize) -> Self {
+        let base = Diatonic::wrapping_index((fifths + 1).rem_euclid(7) * 4 + 3);
+        PitchClass::new(base, Accidental::new((fifths + 1).div_euclid(7)))
+    }
+    pub const fn fifths(&self) -> isize {
+        self.base.fifths() + self.accidental.pitch_shift() * 7
+    }
+    pub const fn simplify(self) -> Self {
+        let mut fifths = self.fifths();
+        while fifths > 12 {
+            fifths -= Chromatic::NOTES as isize;
+        }
+        while fifths < -8 {
+            fifths += Chromatic::NOTES as isize;
+        }
+        PitchClass::from_fifths(fifths)
+    }
     pub fn enharmonic(&self, rhs: &Self) -> bool {
         self.chromatic() == rhs.chromatic()
     }
+    pub fn to_string_in(&self, naming: NoteNaming) -> String {
+        naming.format(*self)
+    }
+    pub fn parse_in(s: &str, naming: NoteNaming) -> Result<Self, ParseError> {
+        naming.parse(s)
+    }
 }
 
 impl fmt::Display for PitchClass {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}{}", self.base, self.accidental)
+        f.write_str(&Notation::of(f).pitch_class(*self))
     }
 }
 
 impl FromStr for PitchClass {
-    type Err = ();
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.is_empty() {
-            return Err(());
-        }
-        let (base, accidental) = s.split_at(1);
-        let (base, accidental) = (base.parse()?, accidental.parse()?);
+        let (base, accidental) = s.split_at(s.chars().next().map_or(0, char::len_utf8));
+        let base = base.parse()?;
+        let accidental = accidental
+            .parse()
+            .map_err(|error: ParseError| error.offset(s.len() - accidental.len()))?;
         Ok(PitchClass { base, accidental })
     }
 }
 
+impl HasPitchClass for PitchClass {
+    fn chromatic(&self) -> Chromatic {
+        PitchClass::chromatic(*self)
+    }
+}
+
+impl Transpose for PitchClass {
+    fn checked_transpose(self, semitones: isize) -> Option<Self> {
+        let fifths = (semitones * 7 + 6).rem_euclid(Chromatic::NOTES as isize) - 6;
+        let transposed = PitchClass::from_fifths(self.fifths() + fifths);
+        let limit = self.accidental.pitch_shift().abs().max(1);
+        if transposed.accidental.pitch_shift().abs() > limit {
+            return Some(transposed.simplify());
+        }
+        Some(transposed)
+    }
+}
+
 impl From<PitchClass> for Chromatic {
     fn from(pitch_class: PitchClass) -> Self {
         pitch_class.chromatic()
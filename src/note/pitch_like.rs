@@ -0,0 +1,34 @@
+use crate::note::{Chromatic, MidiNote, MidiNoteRangeError, Octave, Tone};
+
+pub trait HasPitchClass {
+    fn chromatic(&self) -> Chromatic;
+    fn is_enharmonic_class(&self, other: &impl HasPitchClass) -> bool {
+        self.chromatic() == other.chromatic()
+    }
+}
+
+pub trait Transpose: Sized {
+    fn checked_transpose(self, semitones: isize) -> Option<Self>;
+    fn transpose(self, semitones: isize) -> Self {
+        match self.checked_transpose(semitones) {
+            Some(transposed) => transposed,
+            None => panic!("transposition is out of range"),
+        }
+    }
+}
+
+pub trait PitchLike: HasPitchClass + Transpose {
+    fn semitones(&self) -> isize;
+    fn octave(&self) -> Octave {
+        Octave::new(self.semitones().div_euclid(Chromatic::NOTES as isize) - 1)
+    }
+    fn to_tone(&self) -> Tone {
+        Tone::from_semitones(self.semitones())
+    }
+    fn try_to_midi_note(&self) -> Result<MidiNote, MidiNoteRangeError> {
+        MidiNote::try_from_semitones(self.semitones())
+    }
+    fn interval_to(&self, other: &impl PitchLike) -> isize {
+        other.semitones() - self.semitones()
+    }
+}
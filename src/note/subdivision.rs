@@ -1,9 +1,10 @@
-use crate::note::Duration;
+use crate::note::{Duration, Notation};
+use crate::ParseError;
 use std::cmp::Ordering;
 use std::fmt;
 use std::str::FromStr;
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct Subdivision(usize);
 
 impl Subdivision {
@@ -46,6 +47,13 @@ impl Subdivision {
     pub const fn nth(&self) -> usize {
         1 << self.0
     }
+    pub const fn finer(self, other: Self) -> Self {
+        if self.exponent() >= other.exponent() {
+            self
+        } else {
+            other
+        }
+    }
     pub const fn to_duration(self) -> Duration {
         Duration {
             length: 1,
@@ -56,12 +64,12 @@ impl Subdivision {
 
 impl fmt::Display for Subdivision {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.write_str(self.name())
+        f.write_str(&Notation::of(f).subdivision(*self))
     }
 }
 
 impl FromStr for Subdivision {
-    type Err = ();
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "\u{1d15d}" => Ok(Self::SEMIBREVE),
@@ -72,7 +80,32 @@ impl FromStr for Subdivision {
             "\u{1d162}" => Ok(Self::DEMISEMIQUAVER),
             "\u{1d163}" => Ok(Self::HEMIDEMISEMIQUAVER),
             "\u{1d164}" => Ok(Self::SEMIHEMIDEMISEMIQUAVER),
-            _ => Err(()),
+            s => {
+                let digits = s.strip_prefix("1/").unwrap_or(s);
+                let at = s.len() - digits.len();
+                match usize::from_str(digits) {
+                    Ok(nth) if nth.is_power_of_two() => {
+                        Ok(Subdivision::new(nth.trailing_zeros() as usize))
+                    }
+                    Ok(_) => Err(ParseError::at(s, at, &["power of two"])),
+                    Err(_) => Err(ParseError::at(
+                        s,
+                        at,
+                        &[
+                            "\u{1d15d}",
+                            "\u{1d15e}",
+                            "\u{1d15f}",
+                            "\u{1d160}",
+                            "\u{1d161}",
+                            "\u{1d162}",
+                            "\u{1d163}",
+                            "\u{1d164}",
+                            "1/n",
+                            "n",
+                        ],
+                    )),
+                }
+            }
         }
     }
 }
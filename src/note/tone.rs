@@ -1,9 +1,13 @@
-use crate::note::{Chromatic, MidiNote, Octave, Pitch};
+use crate::note::{
+    Chromatic, HasPitchClass, MidiNote, MidiNoteRangeError, Notation, Octave, OctaveConvention,
+    Pitch, PitchLike, Transpose,
+};
+use crate::ParseError;
 use std::cmp::Ordering;
 use std::fmt;
 use std::str::FromStr;
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct Tone {
     pub chromatic: Chromatic,
     pub octave: Octave,
@@ -19,21 +23,74 @@ impl Tone {
     pub const fn new(chromatic: Chromatic, octave: Octave) -> Self {
         Tone { chromatic, octave }
     }
+    pub const fn from_semitones(semitones: isize) -> Self {
+        Tone {
+            chromatic: Chromatic::wrapping_chr(semitones.rem_euclid(Chromatic::NOTES as isize)),
+            octave: Octave::new(semitones.div_euclid(Chromatic::NOTES as isize) - 1),
+        }
+    }
+    pub const fn semitones(&self) -> isize {
+        self.chromatic.ord() + (self.octave.numeric() + 1) * Chromatic::NOTES as isize
+    }
     pub const fn to_midi_note(self) -> MidiNote {
-        MidiNote::from_tone(self)
+        MidiNote::saturating_from_tone(self)
+    }
+    pub const fn try_to_midi_note(self) -> Result<MidiNote, MidiNoteRangeError> {
+        MidiNote::try_from_tone(self)
+    }
+    pub fn to_string_with(&self, convention: OctaveConvention) -> String {
+        convention.format(self.chromatic.name(), self.octave)
+    }
+    pub fn parse_with(s: &str, convention: OctaveConvention) -> Result<Self, ParseError> {
+        let Some((alias, rest)) = s.split_once('/') else {
+            return Pitch::parse_with(s, convention).map(Pitch::to_tone);
+        };
+        let at = alias.len() + 1;
+        let tone = Pitch::parse_with(rest, convention)
+            .map_err(|error| error.offset(at))?
+            .to_tone();
+        if alias.parse::<Chromatic>()? != tone.chromatic {
+            return Err(ParseError::unexpected(
+                s,
+                0..alias.len(),
+                &["enharmonic spelling"],
+            ));
+        }
+        Ok(tone)
     }
 }
 
 impl fmt::Display for Tone {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}{}", self.chromatic, self.octave)
+        f.write_str(&Notation::of(f).tone(*self))
     }
 }
 
 impl FromStr for Tone {
-    type Err = ();
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Pitch::from_str(s).map(Pitch::to_tone)
+        Tone::parse_with(s, OctaveConvention::Scientific)
+    }
+}
+
+impl HasPitchClass for Tone {
+    fn chromatic(&self) -> Chromatic {
+        self.chromatic
+    }
+}
+
+impl Transpose for Tone {
+    fn checked_transpose(self, semitones: isize) -> Option<Self> {
+        Some(Tone::from_semitones(self.semitones() + semitones))
+    }
+}
+
+impl PitchLike for Tone {
+    fn semitones(&self) -> isize {
+        Tone::semitones(self)
+    }
+    fn octave(&self) -> Octave {
+        self.octave
     }
 }
 
@@ -57,6 +114,6 @@ impl PartialOrd for Tone {
 
 impl Ord for Tone {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.to_midi_note().cmp(&other.to_midi_note())
+        self.semitones().cmp(&other.semitones())
     }
 }
@@ -0,0 +1,92 @@
+use crate::note::{Duration, Subdivision};
+use crate::ParseError;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Tuplet {
+    pub actual: usize,
+    pub normal: usize,
+}
+
+impl Tuplet {
+    pub const DUPLET: Self = Tuplet::new(2, 3);
+    pub const TRIPLET: Self = Tuplet::new(3, 2);
+    pub const QUINTUPLET: Self = Tuplet::new(5, 4);
+    pub const SEXTUPLET: Self = Tuplet::new(6, 4);
+    pub const SEPTUPLET: Self = Tuplet::new(7, 4);
+    pub const fn new(actual: usize, normal: usize) -> Self {
+        Tuplet { actual, normal }
+    }
+    pub const fn common(actual: usize) -> Self {
+        match actual {
+            0 | 1 => Tuplet::new(actual, 1),
+            2 => Tuplet::DUPLET,
+            actual => Tuplet::new(actual, 1 << (usize::BITS - 1 - actual.leading_zeros())),
+        }
+    }
+    pub fn ratio(&self) -> f64 {
+        self.normal as f64 / self.actual as f64
+    }
+    pub const fn nest(self, inner: Self) -> Self {
+        Tuplet::new(self.actual * inner.actual, self.normal * inner.normal)
+    }
+    pub const fn sounding(&self, written: Duration) -> Option<Duration> {
+        let written = written.reduce();
+        let length = written.length * self.normal;
+        if self.actual == 0 || !length.is_multiple_of(self.actual >> self.actual.trailing_zeros()) {
+            return None;
+        }
+        let exponent = written.unit.exponent() + self.actual.trailing_zeros() as usize;
+        let length = (length << self.actual.trailing_zeros()) / self.actual;
+        Some(Duration::new(length, Subdivision::new(exponent)).reduce())
+    }
+    pub const fn written(&self, sounding: Duration) -> Option<Duration> {
+        Tuplet::new(self.normal, self.actual).sounding(sounding)
+    }
+}
+
+impl Default for Tuplet {
+    fn default() -> Self {
+        Tuplet::TRIPLET
+    }
+}
+
+impl fmt::Display for Tuplet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.actual, self.normal)
+    }
+}
+
+impl FromStr for Tuplet {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = |digits: &str, at: usize| match usize::from_str(digits) {
+            Ok(0) | Err(_) => Err(ParseError::unexpected(
+                s,
+                at..at + digits.len(),
+                &["number"],
+            )),
+            Ok(number) => Ok(number),
+        };
+        match s.split_once(':') {
+            Some((actual, normal)) => Ok(Tuplet::new(
+                number(actual, 0)?,
+                number(normal, actual.len() + 1)?,
+            )),
+            None => Ok(Tuplet::common(number(s, 0)?)),
+        }
+    }
+}
+
+impl From<Tuplet> for (usize, usize) {
+    fn from(Tuplet { actual, normal }: Tuplet) -> Self {
+        (actual, normal)
+    }
+}
+
+impl From<(usize, usize)> for Tuplet {
+    fn from((actual, normal): (usize, usize)) -> Self {
+        Tuplet { actual, normal }
+    }
+}
@@ -0,0 +1,152 @@
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    UnexpectedEnd {
+        at: usize,
+        expected: &'static [&'static str],
+    },
+    Unexpected {
+        span: Range<usize>,
+        found: String,
+        expected: &'static [&'static str],
+    },
+    OutOfRange {
+        span: Range<usize>,
+        found: String,
+        min: isize,
+        max: isize,
+    },
+}
+
+impl ParseError {
+    pub fn unexpected(s: &str, span: Range<usize>, expected: &'static [&'static str]) -> Self {
+        if span.start >= s.len() {
+            return ParseError::UnexpectedEnd {
+                at: s.len(),
+                expected,
+            };
+        }
+        ParseError::Unexpected {
+            found: s[span.clone()].to_string(),
+            span,
+            expected,
+        }
+    }
+    pub fn at(s: &str, start: usize, expected: &'static [&'static str]) -> Self {
+        ParseError::unexpected(s, start..s.len(), expected)
+    }
+    pub fn char_at(s: &str, start: usize, expected: &'static [&'static str]) -> Self {
+        let len = s[start..].chars().next().map_or(0, char::len_utf8);
+        ParseError::unexpected(s, start..start + len, expected)
+    }
+    pub fn out_of_range(s: &str, span: Range<usize>, min: isize, max: isize) -> Self {
+        ParseError::OutOfRange {
+            found: s[span.clone()].to_string(),
+            span,
+            min,
+            max,
+        }
+    }
+    pub fn span(&self) -> Range<usize> {
+        match self {
+            ParseError::UnexpectedEnd { at, .. } => *at..*at,
+            ParseError::Unexpected { span, .. } | ParseError::OutOfRange { span, .. } => {
+                span.clone()
+            }
+        }
+    }
+    pub const fn expected(&self) -> &'static [&'static str] {
+        match self {
+            ParseError::UnexpectedEnd { expected, .. }
+            | ParseError::Unexpected { expected, .. } => expected,
+            ParseError::OutOfRange { .. } => &[],
+        }
+    }
+    pub fn offset(self, by: usize) -> Self {
+        match self {
+            ParseError::UnexpectedEnd { at, expected } => ParseError::UnexpectedEnd {
+                at: at + by,
+                expected,
+            },
+            ParseError::Unexpected {
+                span,
+                found,
+                expected,
+            } => ParseError::Unexpected {
+                span: span.start + by..span.end + by,
+                found,
+                expected,
+            },
+            ParseError::OutOfRange {
+                span,
+                found,
+                min,
+                max,
+            } => ParseError::OutOfRange {
+                span: span.start + by..span.end + by,
+                found,
+                min,
+                max,
+            },
+        }
+    }
+}
+
+struct Expected(&'static [&'static str]);
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            [] => f.write_str("end of input"),
+            [token] => write!(f, "`{token}`"),
+            tokens => {
+                f.write_str("one of ")?;
+                for (i, token) in tokens.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "`{token}`")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::UnexpectedEnd { at, expected } => write!(
+                f,
+                "unexpected end of input at {at}, expected {}",
+                Expected(expected)
+            ),
+            ParseError::Unexpected {
+                span,
+                found,
+                expected,
+            } => write!(
+                f,
+                "unexpected `{found}` at {}..{}, expected {}",
+                span.start,
+                span.end,
+                Expected(expected)
+            ),
+            ParseError::OutOfRange {
+                span,
+                found,
+                min,
+                max,
+            } => write!(
+                f,
+                "`{found}` at {}..{} is outside {min}..={max}",
+                span.start, span.end
+            ),
+        }
+    }
+}
+
+impl Error for ParseError {}
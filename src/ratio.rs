@@ -0,0 +1,118 @@
+use crate::note::{Duration, Subdivision};
+use std::cmp::Ordering;
+
+const GRID: Subdivision = Subdivision::new(10);
+const LIMIT: u128 = u32::MAX as u128;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct Ratio {
+    pub(crate) numerator: u64,
+    pub(crate) denominator: u64,
+}
+
+const fn gcd(a: u128, b: u128) -> u128 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+impl Ratio {
+    pub(crate) const ZERO: Self = Ratio::new(0, 1);
+    pub(crate) const fn new(numerator: u64, denominator: u64) -> Self {
+        let (numerator, denominator) = Ratio::reduce(numerator as u128, denominator as u128);
+        Ratio {
+            numerator: numerator as u64,
+            denominator: denominator as u64,
+        }
+    }
+    const fn reduce(numerator: u128, denominator: u128) -> (u128, u128) {
+        let divisor = match gcd(numerator, denominator) {
+            0 => 1,
+            divisor => divisor,
+        };
+        (numerator / divisor, denominator / divisor)
+    }
+    const fn checked(numerator: u128, denominator: u128) -> Option<Self> {
+        let (numerator, denominator) = Ratio::reduce(numerator, denominator);
+        if numerator > LIMIT || denominator > LIMIT {
+            return None;
+        }
+        Some(Ratio {
+            numerator: numerator as u64,
+            denominator: denominator as u64,
+        })
+    }
+    pub(crate) const fn checked_new(numerator: u64, denominator: u64) -> Option<Self> {
+        Ratio::checked(numerator as u128, denominator as u128)
+    }
+    pub(crate) const fn add(self, rhs: Self) -> Self {
+        Ratio::new(
+            self.numerator * rhs.denominator + rhs.numerator * self.denominator,
+            self.denominator * rhs.denominator,
+        )
+    }
+    pub(crate) const fn mul(self, rhs: Self) -> Self {
+        Ratio::new(
+            self.numerator * rhs.numerator,
+            self.denominator * rhs.denominator,
+        )
+    }
+    pub(crate) const fn checked_add(self, rhs: Self) -> Option<Self> {
+        let lhs = self.numerator as u128 * rhs.denominator as u128;
+        let Some(numerator) = lhs.checked_add(rhs.numerator as u128 * self.denominator as u128)
+        else {
+            return None;
+        };
+        Ratio::checked(
+            numerator,
+            self.denominator as u128 * rhs.denominator as u128,
+        )
+    }
+    pub(crate) const fn checked_saturating_sub(self, rhs: Self) -> Option<Self> {
+        let lhs = self.numerator as u128 * rhs.denominator as u128;
+        Ratio::checked(
+            lhs.saturating_sub(rhs.numerator as u128 * self.denominator as u128),
+            self.denominator as u128 * rhs.denominator as u128,
+        )
+    }
+    pub(crate) const fn checked_mul(self, rhs: Self) -> Option<Self> {
+        Ratio::checked(
+            self.numerator as u128 * rhs.numerator as u128,
+            self.denominator as u128 * rhs.denominator as u128,
+        )
+    }
+    pub(crate) const fn is_dyadic(&self) -> bool {
+        self.denominator.is_power_of_two()
+    }
+    pub(crate) fn to_duration(self) -> Duration {
+        if self.is_dyadic() {
+            Duration::new(
+                self.numerator as usize,
+                Subdivision::new(self.denominator.trailing_zeros() as usize),
+            )
+        } else {
+            Duration::quantize(self.numerator as f64 / self.denominator as f64, GRID)
+        }
+    }
+}
+
+impl From<Duration> for Ratio {
+    fn from(duration: Duration) -> Self {
+        Ratio::new(duration.length as u64, duration.unit.nth() as u64)
+    }
+}
+
+impl Ord for Ratio {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.numerator as u128 * other.denominator as u128)
+            .cmp(&(other.numerator as u128 * self.denominator as u128))
+    }
+}
+
+impl PartialOrd for Ratio {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
@@ -0,0 +1,5 @@
+mod key;
+mod mode;
+
+pub use key::Key;
+pub use mode::Mode;
@@ -0,0 +1,163 @@
+use crate::note::{
+    Accidental, Chromatic, Diatonic, MidiNote, Octave, Pitch, PitchClass, Transpose,
+};
+use crate::scale::Mode;
+use crate::ParseError;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Key {
+    pub tonic: PitchClass,
+    pub mode: Mode,
+}
+
+impl Key {
+    pub const C_MAJOR: Self = Key::major(PitchClass::new(Diatonic::C, Accidental::NATURAL));
+    pub const A_MINOR: Self = Key::minor(PitchClass::new(Diatonic::A, Accidental::NATURAL));
+    pub const fn new(tonic: PitchClass, mode: Mode) -> Self {
+        Key { tonic, mode }
+    }
+    pub const fn major(tonic: PitchClass) -> Self {
+        Key::new(tonic, Mode::MAJOR)
+    }
+    pub const fn minor(tonic: PitchClass) -> Self {
+        Key::new(tonic, Mode::MINOR)
+    }
+    pub const fn from_fifths(fifths: isize, mode: Mode) -> Self {
+        Key::new(PitchClass::from_fifths(fifths - mode.fifths()), mode)
+    }
+    pub const fn fifths(&self) -> isize {
+        self.tonic.fifths() + self.mode.fifths()
+    }
+    pub const fn accidental(&self, base: Diatonic) -> Accidental {
+        Accidental::new((self.fifths() + 5 - base.fifths()).div_euclid(7))
+    }
+    pub fn signature(&self) -> Vec<PitchClass> {
+        let fifths = self.fifths();
+        if fifths >= 0 {
+            (0..fifths)
+                .map(|i| PitchClass::from_fifths(i + 6))
+                .collect()
+        } else {
+            (0..-fifths)
+                .map(|i| PitchClass::from_fifths(-2 - i))
+                .collect()
+        }
+    }
+    pub const fn degree(&self, degree: isize) -> PitchClass {
+        let base = self.tonic.base.step(degree);
+        PitchClass::new(base, self.accidental(base))
+    }
+    pub fn scale(&self) -> [PitchClass; 7] {
+        std::array::from_fn(|i| self.degree(i as isize))
+    }
+    pub const fn spell(&self, chromatic: Chromatic) -> PitchClass {
+        let center = self.fifths() + 2;
+        let mut best = PitchClass::new(Diatonic::C, Accidental::NATURAL);
+        let mut best_distance = isize::MAX;
+        let mut i = 0;
+        while i < Diatonic::NOTES as usize {
+            let base = Diatonic::LETTERS[i];
+            let shift = (chromatic.ord() - base.ord() + 6).rem_euclid(12) - 6;
+            let candidate = PitchClass::new(base, Accidental::new(shift));
+            let distance = (candidate.fifths() - center).abs()
+                + if shift.abs() > 1 && shift != self.accidental(base).pitch_shift() {
+                    Chromatic::NOTES as isize
+                } else {
+                    0
+                };
+            if distance < best_distance
+                || (distance == best_distance && (candidate.fifths() > center) == (center > 2))
+            {
+                best = candidate;
+                best_distance = distance;
+            }
+            i += 1;
+        }
+        best
+    }
+    pub const fn spell_midi_note(&self, note: MidiNote) -> Pitch {
+        let pitch_class = self.spell(note.to_tone().chromatic);
+        let base = pitch_class.base.ord() + pitch_class.accidental.pitch_shift();
+        let octave = (note.as_u8() as isize - base).div_euclid(12) - 1;
+        Pitch::new(pitch_class, Octave::new(octave))
+    }
+    pub const fn relative(&self, mode: Mode) -> Self {
+        Key::from_fifths(self.fifths(), mode)
+    }
+    pub const fn parallel(&self, mode: Mode) -> Self {
+        Key::new(self.tonic, mode)
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            return match self.mode {
+                Mode::Ionian => write!(f, "{:#}", self.tonic),
+                Mode::Aeolian => write!(f, "{:#}m", self.tonic),
+                mode => write!(f, "{:#} {}", self.tonic, mode.name()),
+            };
+        }
+        write!(f, "{}", self.tonic.base)?;
+        if self.tonic.accidental != Accidental::NATURAL {
+            write!(f, "{}", self.tonic.accidental)?;
+        }
+        write!(f, " {}", self.mode)
+    }
+}
+
+impl Transpose for Key {
+    fn checked_transpose(self, semitones: isize) -> Option<Self> {
+        let key = Key::new(self.tonic.checked_transpose(semitones)?, self.mode);
+        match key.fifths() {
+            fifths if fifths > 6 => Some(Key::from_fifths(fifths - 12, self.mode)),
+            fifths if fifths < -6 => Some(Key::from_fifths(fifths + 12, self.mode)),
+            _ => Some(key),
+        }
+    }
+}
+
+impl FromStr for Key {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        let start = s.len() - s.trim_start().len();
+        let letter = trimmed.chars().next().map_or(0, char::len_utf8);
+        let base = Diatonic::from_str(&trimmed[..letter]).map_err(|error| error.offset(start))?;
+        let rest = &trimmed[letter..];
+        let mut error = None;
+        for i in rest
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain([rest.len()])
+            .rev()
+        {
+            let Ok(accidental) = Accidental::from_str(&rest[..i]) else {
+                continue;
+            };
+            let mode = rest[i..].trim_start();
+            match Mode::from_str(mode.trim_end()) {
+                Ok(mode) => return Ok(Key::new(PitchClass::new(base, accidental), mode)),
+                Err(mode_error) => {
+                    let at = start + trimmed.len() - mode.len();
+                    error.get_or_insert(mode_error.offset(at));
+                }
+            }
+        }
+        Err(error.unwrap_or_else(|| ParseError::at(s, start + letter, &["accidental"])))
+    }
+}
+
+impl From<Key> for (PitchClass, Mode) {
+    fn from(Key { tonic, mode }: Key) -> Self {
+        (tonic, mode)
+    }
+}
+
+impl From<(PitchClass, Mode)> for Key {
+    fn from((tonic, mode): (PitchClass, Mode)) -> Self {
+        Key { tonic, mode }
+    }
+}
@@ -0,0 +1,96 @@
+use crate::ParseError;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Mode {
+    Ionian,
+    Dorian,
+    Phrygian,
+    Lydian,
+    Mixolydian,
+    Aeolian,
+    Locrian,
+}
+
+impl Mode {
+    pub const MAJOR: Self = Mode::Ionian;
+    pub const MINOR: Self = Mode::Aeolian;
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Mode::Ionian => "major",
+            Mode::Dorian => "dorian",
+            Mode::Phrygian => "phrygian",
+            Mode::Lydian => "lydian",
+            Mode::Mixolydian => "mixolydian",
+            Mode::Aeolian => "minor",
+            Mode::Locrian => "locrian",
+        }
+    }
+    pub const fn fifths(self) -> isize {
+        match self {
+            Mode::Lydian => 1,
+            Mode::Ionian => 0,
+            Mode::Mixolydian => -1,
+            Mode::Dorian => -2,
+            Mode::Aeolian => -3,
+            Mode::Phrygian => -4,
+            Mode::Locrian => -5,
+        }
+    }
+    pub const fn steps(self) -> [isize; 7] {
+        let ionian: [isize; 7] = [0, 2, 4, 5, 7, 9, 11];
+        let rotation = match self {
+            Mode::Ionian => 0,
+            Mode::Dorian => 1,
+            Mode::Phrygian => 2,
+            Mode::Lydian => 3,
+            Mode::Mixolydian => 4,
+            Mode::Aeolian => 5,
+            Mode::Locrian => 6,
+        };
+        let mut steps = [0; 7];
+        let mut i = 0;
+        while i < 7 {
+            steps[i] = (ionian[(i + rotation) % 7] - ionian[rotation]).rem_euclid(12);
+            i += 1;
+        }
+        steps
+    }
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Mode {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "" | "maj" | "major" | "ion" | "ionian" => Ok(Mode::Ionian),
+            "dor" | "dorian" => Ok(Mode::Dorian),
+            "phr" | "phrygian" => Ok(Mode::Phrygian),
+            "lyd" | "lydian" => Ok(Mode::Lydian),
+            "mix" | "mixolydian" => Ok(Mode::Mixolydian),
+            "m" | "min" | "minor" | "aeo" | "aeolian" => Ok(Mode::Aeolian),
+            "loc" | "locrian" => Ok(Mode::Locrian),
+            _ => Err(ParseError::at(
+                s,
+                0,
+                &[
+                    "major",
+                    "minor",
+                    "ionian",
+                    "dorian",
+                    "phrygian",
+                    "lydian",
+                    "mixolydian",
+                    "aeolian",
+                    "locrian",
+                ],
+            )),
+        }
+    }
+}
@@ -0,0 +1,20 @@
+mod clef;
+mod instrument;
+mod measure;
+mod part;
+#[allow(clippy::module_inception)]
+mod score;
+mod tempo;
+mod tempo_map;
+mod time_signature;
+mod voice;
+
+pub use clef::Clef;
+pub use instrument::Instrument;
+pub use measure::Measure;
+pub use part::{Part, Staff};
+pub use score::Score;
+pub use tempo::Tempo;
+pub use tempo_map::{TempoChange, TempoMap, Transition};
+pub use time_signature::TimeSignature;
+pub use voice::Voice;
@@ -0,0 +1,111 @@
+use crate::ParseError;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Clef {
+    #[default]
+    Treble,
+    TrebleOctaveDown,
+    Soprano,
+    MezzoSoprano,
+    Alto,
+    Tenor,
+    Baritone,
+    Bass,
+    Percussion,
+}
+
+impl Clef {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Clef::Treble => "treble",
+            Clef::TrebleOctaveDown => "treble_8",
+            Clef::Soprano => "soprano",
+            Clef::MezzoSoprano => "mezzosoprano",
+            Clef::Alto => "alto",
+            Clef::Tenor => "tenor",
+            Clef::Baritone => "baritone",
+            Clef::Bass => "bass",
+            Clef::Percussion => "percussion",
+        }
+    }
+    pub const fn sign(&self) -> char {
+        match self {
+            Clef::Treble | Clef::TrebleOctaveDown => 'G',
+            Clef::Soprano | Clef::MezzoSoprano | Clef::Alto | Clef::Tenor => 'C',
+            Clef::Baritone | Clef::Bass => 'F',
+            Clef::Percussion => 'P',
+        }
+    }
+    pub const fn line(&self) -> u8 {
+        match self {
+            Clef::Treble | Clef::TrebleOctaveDown => 2,
+            Clef::Soprano => 1,
+            Clef::MezzoSoprano => 2,
+            Clef::Alto => 3,
+            Clef::Tenor => 4,
+            Clef::Baritone => 3,
+            Clef::Bass => 4,
+            Clef::Percussion => 3,
+        }
+    }
+    pub const fn octave_change(&self) -> isize {
+        match self {
+            Clef::TrebleOctaveDown => -1,
+            _ => 0,
+        }
+    }
+    pub const fn from_sign(sign: char, line: u8, octave_change: isize) -> Option<Self> {
+        match (sign, line, octave_change) {
+            ('G', 2, 0) => Some(Clef::Treble),
+            ('G', 2, -1) => Some(Clef::TrebleOctaveDown),
+            ('C', 1, 0) => Some(Clef::Soprano),
+            ('C', 2, 0) => Some(Clef::MezzoSoprano),
+            ('C', 3, 0) => Some(Clef::Alto),
+            ('C', 4, 0) => Some(Clef::Tenor),
+            ('F', 3, 0) => Some(Clef::Baritone),
+            ('F', 4, 0) => Some(Clef::Bass),
+            ('P', _, _) => Some(Clef::Percussion),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Clef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Clef {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "treble" | "violin" | "G" => Ok(Clef::Treble),
+            "treble_8" | "tenorG" | "G_8" => Ok(Clef::TrebleOctaveDown),
+            "soprano" => Ok(Clef::Soprano),
+            "mezzosoprano" => Ok(Clef::MezzoSoprano),
+            "alto" | "C" => Ok(Clef::Alto),
+            "tenor" => Ok(Clef::Tenor),
+            "baritone" => Ok(Clef::Baritone),
+            "bass" | "F" => Ok(Clef::Bass),
+            "percussion" => Ok(Clef::Percussion),
+            _ => Err(ParseError::at(
+                s,
+                0,
+                &[
+                    "treble",
+                    "treble_8",
+                    "soprano",
+                    "mezzosoprano",
+                    "alto",
+                    "tenor",
+                    "baritone",
+                    "bass",
+                    "percussion",
+                ],
+            )),
+        }
+    }
+}
@@ -0,0 +1,14 @@
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Instrument {
+    pub name: String,
+    pub program: u8,
+}
+
+impl Instrument {
+    pub fn new(name: impl Into<String>, program: u8) -> Self {
+        Instrument {
+            name: name.into(),
+            program,
+        }
+    }
+}
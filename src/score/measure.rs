@@ -0,0 +1,112 @@
+use crate::note::{Duration, NoteEvent};
+use crate::score::TimeSignature;
+use std::cmp::Ordering;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Measure {
+    pub time_signature: TimeSignature,
+    pub events: Vec<NoteEvent>,
+}
+
+impl Measure {
+    pub const fn new(time_signature: TimeSignature) -> Self {
+        Measure {
+            time_signature,
+            events: Vec::new(),
+        }
+    }
+    pub fn try_from_events(
+        time_signature: TimeSignature,
+        events: impl Into<Vec<NoteEvent>>,
+    ) -> Result<Self, Ordering> {
+        let measure = Measure {
+            events: events.into(),
+            ..Measure::new(time_signature)
+        };
+        match measure.fill() {
+            Ordering::Equal => Ok(measure),
+            fill => Err(fill),
+        }
+    }
+    pub fn split(
+        time_signature: &TimeSignature,
+        events: impl IntoIterator<Item = NoteEvent>,
+    ) -> Vec<Measure> {
+        Measure::split_with_anacrusis(time_signature, time_signature.bar_duration(), events)
+    }
+    pub fn split_with_anacrusis(
+        time_signature: &TimeSignature,
+        anacrusis: Duration,
+        events: impl IntoIterator<Item = NoteEvent>,
+    ) -> Vec<Measure> {
+        let mut measures = Vec::new();
+        let mut current = Measure::new(time_signature.clone());
+        let mut room = if anacrusis.is_zero() {
+            time_signature.bar_duration()
+        } else {
+            anacrusis
+        };
+        for mut event in events {
+            loop {
+                if room.is_zero() {
+                    measures.push(current);
+                    current = Measure::new(time_signature.clone());
+                    room = time_signature.bar_duration();
+                }
+                if event.value().is_zero() {
+                    current.events.push(event);
+                    break;
+                }
+                match event.split_at(room) {
+                    Some((head, tail)) if !event.is_rest() => {
+                        current.events.push(head);
+                        event = tail;
+                        room = Duration::ZERO;
+                    }
+                    Some(_) => {
+                        current.events.push(event.clone().with_value(room));
+                        event.set_value(event.value() - room);
+                        room = Duration::ZERO;
+                    }
+                    None => {
+                        room = room.saturating_sub(event.value());
+                        current.events.push(event);
+                        break;
+                    }
+                }
+            }
+        }
+        if !current.events.is_empty() {
+            measures.push(current);
+        }
+        measures
+    }
+    pub fn capacity(&self) -> Duration {
+        self.time_signature.bar_duration()
+    }
+    pub fn duration(&self) -> Duration {
+        NoteEvent::total_duration(&self.events)
+    }
+    pub fn remaining(&self) -> Duration {
+        self.capacity().saturating_sub(self.duration())
+    }
+    pub fn fill(&self) -> Ordering {
+        self.duration().cmp(&self.capacity())
+    }
+    pub fn is_full(&self) -> bool {
+        self.fill() == Ordering::Equal
+    }
+    pub fn is_tied_from_previous(&self) -> bool {
+        self.events.first().is_some_and(|event| event.tie().stop)
+    }
+    pub fn is_tied_to_next(&self) -> bool {
+        self.events.last().is_some_and(|event| event.tie().start)
+    }
+    pub fn push(&mut self, event: NoteEvent) -> Result<(), NoteEvent> {
+        if event.value() > self.remaining() {
+            return Err(event);
+        }
+        self.events.push(event);
+        Ok(())
+    }
+}
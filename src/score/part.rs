@@ -0,0 +1,67 @@
+use crate::chord::ChordSymbol;
+use crate::note::Duration;
+use crate::scale::Key;
+use crate::score::{Clef, Instrument, Voice};
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Staff {
+    pub clef: Clef,
+    pub voices: Vec<Voice>,
+}
+
+impl Staff {
+    pub const fn new(clef: Clef) -> Self {
+        Staff {
+            clef,
+            voices: Vec::new(),
+        }
+    }
+    pub fn with_voice(mut self, voice: Voice) -> Self {
+        self.voices.push(voice);
+        self
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Part {
+    pub name: String,
+    pub instrument: Instrument,
+    pub key: Key,
+    pub staves: Vec<Staff>,
+    pub chords: Vec<(Duration, ChordSymbol)>,
+}
+
+impl Part {
+    pub fn new(name: impl Into<String>, instrument: Instrument, key: Key) -> Self {
+        Part {
+            name: name.into(),
+            instrument,
+            key,
+            staves: Vec::new(),
+            chords: Vec::new(),
+        }
+    }
+    pub fn with_staff(mut self, staff: Staff) -> Self {
+        self.staves.push(staff);
+        self
+    }
+    pub fn set_chord(&mut self, position: Duration, chord: ChordSymbol) {
+        match self
+            .chords
+            .binary_search_by(|(other, _)| other.cmp(&position))
+        {
+            Ok(i) => self.chords[i].1 = chord,
+            Err(i) => self.chords.insert(i, (position, chord)),
+        }
+    }
+    pub fn chord_at(&self, position: Duration) -> Option<&ChordSymbol> {
+        self.chords
+            .iter()
+            .take_while(|(start, _)| *start <= position)
+            .last()
+            .map(|(_, chord)| chord)
+    }
+    pub fn voices(&self) -> impl Iterator<Item = &Voice> {
+        self.staves.iter().flat_map(|staff| &staff.voices)
+    }
+}
@@ -0,0 +1,119 @@
+use crate::note::{Duration, Pitch};
+use crate::score::{Measure, Part, Tempo, TempoMap, TimeSignature, Voice};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Score {
+    pub title: Option<String>,
+    pub composer: Option<String>,
+    pub tempo: TempoMap,
+    pub time_signatures: Vec<(Duration, TimeSignature)>,
+    pub parts: Vec<Part>,
+}
+
+impl Score {
+    pub fn new(time_signature: TimeSignature, tempo: Tempo) -> Self {
+        Score {
+            title: None,
+            composer: None,
+            tempo: TempoMap::new(tempo),
+            time_signatures: vec![(Duration::ZERO, time_signature)],
+            parts: Vec::new(),
+        }
+    }
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+    pub fn with_composer(mut self, composer: impl Into<String>) -> Self {
+        self.composer = Some(composer.into());
+        self
+    }
+    pub fn with_part(mut self, part: Part) -> Self {
+        self.parts.push(part);
+        self
+    }
+    pub fn set_time_signature(&mut self, position: Duration, time_signature: TimeSignature) {
+        match self
+            .time_signatures
+            .binary_search_by(|(other, _)| other.cmp(&position))
+        {
+            Ok(i) => self.time_signatures[i].1 = time_signature,
+            Err(i) => self.time_signatures.insert(i, (position, time_signature)),
+        }
+    }
+    pub fn time_signature_at(&self, position: Duration) -> Option<&TimeSignature> {
+        self.time_signatures
+            .iter()
+            .take_while(|(start, _)| *start <= position)
+            .last()
+            .map(|(_, time_signature)| time_signature)
+    }
+    pub fn voices(&self) -> impl Iterator<Item = &Voice> {
+        self.parts.iter().flat_map(Part::voices)
+    }
+    pub fn duration(&self) -> Duration {
+        self.voices()
+            .map(Voice::duration)
+            .max()
+            .unwrap_or(Duration::ZERO)
+    }
+    pub fn barlines(&self) -> Vec<Duration> {
+        let end = self.duration();
+        let mut barlines = Vec::new();
+        for (i, (start, time_signature)) in self.time_signatures.iter().enumerate() {
+            let until = self
+                .time_signatures
+                .get(i + 1)
+                .map_or(end, |&(next, _)| next.min(end));
+            let bar = time_signature.bar_duration();
+            let mut position = *start;
+            while position < until {
+                barlines.push(position);
+                if bar.is_zero() {
+                    break;
+                }
+                position += bar;
+            }
+        }
+        barlines
+    }
+    pub fn measures(&self, voice: &Voice) -> Vec<Measure> {
+        let barlines = self.barlines();
+        let end = self.duration();
+        barlines
+            .iter()
+            .enumerate()
+            .map(|(i, &start)| {
+                let until = barlines.get(i + 1).copied().unwrap_or(end);
+                Measure {
+                    events: voice.slice(start..until).into(),
+                    ..Measure::new(
+                        self.time_signature_at(start)
+                            .cloned()
+                            .unwrap_or_else(TimeSignature::common_time),
+                    )
+                }
+            })
+            .collect()
+    }
+    pub fn onsets(&self) -> Vec<Duration> {
+        let mut onsets: Vec<Duration> = self
+            .voices()
+            .flat_map(|voice| voice.offsets().iter().copied())
+            .collect();
+        onsets.sort();
+        onsets.dedup();
+        onsets
+    }
+    pub fn sounding_at(&self, position: Duration) -> Vec<Pitch> {
+        self.voices()
+            .flat_map(|voice| voice.sounding_at(position).iter().copied())
+            .collect()
+    }
+    pub fn vertical_slices(&self) -> Vec<(Duration, Vec<Pitch>)> {
+        self.onsets()
+            .into_iter()
+            .map(|onset| (onset, self.sounding_at(onset)))
+            .collect()
+    }
+}
@@ -0,0 +1,82 @@
+use crate::note::{Duration, Subdivision};
+use crate::ParseError;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tempo {
+    pub bpm: f64,
+    pub beat: Duration,
+}
+
+impl Tempo {
+    pub const fn new(bpm: f64, beat: Duration) -> Self {
+        Tempo { bpm, beat }
+    }
+    pub const fn quarter(bpm: f64) -> Self {
+        Tempo::new(bpm, Subdivision::QUARTER_NOTE.to_duration())
+    }
+    pub fn whole_notes_per_second(&self) -> f64 {
+        self.bpm * self.beat.as_f64() / 60.0
+    }
+    pub fn from_whole_notes_per_second(rate: f64, beat: Duration) -> Self {
+        Tempo::new(rate * 60.0 / beat.as_f64(), beat)
+    }
+    pub fn with_beat(self, beat: Duration) -> Self {
+        Tempo::from_whole_notes_per_second(self.whole_notes_per_second(), beat)
+    }
+    pub fn beats(&self, duration: Duration) -> f64 {
+        duration.as_f64() / self.beat.as_f64()
+    }
+    pub fn seconds(&self, duration: Duration) -> f64 {
+        duration.as_f64() / self.whole_notes_per_second()
+    }
+    pub fn duration(&self, seconds: f64, grid: Subdivision) -> Duration {
+        Duration::quantize(seconds * self.whole_notes_per_second(), grid)
+    }
+}
+
+impl Default for Tempo {
+    fn default() -> Self {
+        Tempo::quarter(120.0)
+    }
+}
+
+impl fmt::Display for Tempo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "{:#} = {}", self.beat, self.bpm)
+        } else {
+            write!(f, "{} = {}", self.beat, self.bpm)
+        }
+    }
+}
+
+impl FromStr for Tempo {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (beat, bpm, at) = match s.split_once('=') {
+            Some((beat, bpm)) => (Some(beat), bpm, beat.len() + 1),
+            None => (None, s, 0),
+        };
+        let bpm = f64::from_str(bpm.trim()).map_err(|_| ParseError::at(s, at, &["bpm"]))?;
+        let Some(beat) = beat else {
+            return Ok(Tempo::quarter(bpm));
+        };
+        let start = beat.len() - beat.trim_start().len();
+        let beat = Duration::from_str(beat.trim()).map_err(|error| error.offset(start))?;
+        Ok(Tempo::new(bpm, beat))
+    }
+}
+
+impl From<Tempo> for (f64, Duration) {
+    fn from(Tempo { bpm, beat }: Tempo) -> Self {
+        (bpm, beat)
+    }
+}
+
+impl From<(f64, Duration)> for Tempo {
+    fn from((bpm, beat): (f64, Duration)) -> Self {
+        Tempo { bpm, beat }
+    }
+}
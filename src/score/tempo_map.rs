@@ -0,0 +1,171 @@
+use crate::note::{Duration, Subdivision};
+use crate::score::Tempo;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Transition {
+    Immediate,
+    Gradual,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TempoChange {
+    pub position: Duration,
+    pub tempo: Tempo,
+    pub transition: Transition,
+}
+
+impl TempoChange {
+    pub const fn new(position: Duration, tempo: Tempo, transition: Transition) -> Self {
+        TempoChange {
+            position,
+            tempo,
+            transition,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TempoMap {
+    changes: Vec<TempoChange>,
+}
+
+struct Segment {
+    start: f64,
+    length: f64,
+    from: f64,
+    to: f64,
+}
+
+impl Segment {
+    fn seconds(&self, offset: f64) -> f64 {
+        let rate = self.rate(offset);
+        if (self.to - self.from).abs() < f64::EPSILON {
+            offset / self.from
+        } else {
+            self.length / (self.to - self.from) * (rate / self.from).ln()
+        }
+    }
+    fn offset(&self, seconds: f64) -> f64 {
+        if (self.to - self.from).abs() < f64::EPSILON {
+            seconds * self.from
+        } else {
+            let rate = self.from * (seconds * (self.to - self.from) / self.length).exp();
+            (rate - self.from) * self.length / (self.to - self.from)
+        }
+    }
+    fn rate(&self, offset: f64) -> f64 {
+        if self.length.is_infinite() {
+            self.from
+        } else {
+            self.from + (self.to - self.from) * offset / self.length
+        }
+    }
+}
+
+impl TempoMap {
+    pub fn new(initial: Tempo) -> Self {
+        TempoMap {
+            changes: vec![TempoChange::new(
+                Duration::ZERO,
+                initial,
+                Transition::Immediate,
+            )],
+        }
+    }
+    pub fn changes(&self) -> &[TempoChange] {
+        &self.changes
+    }
+    pub fn insert(&mut self, change: TempoChange) {
+        match self
+            .changes
+            .binary_search_by(|other| other.position.cmp(&change.position))
+        {
+            Ok(i) => self.changes[i] = change,
+            Err(i) => self.changes.insert(i, change),
+        }
+    }
+    pub fn set_tempo(&mut self, position: Duration, tempo: Tempo) {
+        self.insert(TempoChange::new(position, tempo, Transition::Immediate));
+    }
+    pub fn ramp_to(&mut self, position: Duration, tempo: Tempo) {
+        self.insert(TempoChange::new(position, tempo, Transition::Gradual));
+    }
+    fn segments(&self) -> impl Iterator<Item = Segment> + '_ {
+        self.changes.iter().enumerate().map(|(i, change)| {
+            let start = change.position.as_f64();
+            let from = change.tempo.whole_notes_per_second();
+            match self.changes.get(i + 1) {
+                Some(next) => Segment {
+                    start,
+                    length: next.position.as_f64() - start,
+                    from,
+                    to: match next.transition {
+                        Transition::Immediate => from,
+                        Transition::Gradual => next.tempo.whole_notes_per_second(),
+                    },
+                },
+                None => Segment {
+                    start,
+                    length: f64::INFINITY,
+                    from,
+                    to: from,
+                },
+            }
+        })
+    }
+    pub fn tempo_at(&self, position: Duration) -> Tempo {
+        let i = match self
+            .changes
+            .binary_search_by(|change| change.position.cmp(&position))
+        {
+            Ok(i) => i,
+            Err(i) => i.saturating_sub(1),
+        };
+        let segment = self.segments().nth(i).expect("tempo map is never empty");
+        let beat = match self.changes.get(i + 1) {
+            Some(next) if next.transition == Transition::Gradual => next.tempo.beat,
+            _ => self.changes[i].tempo.beat,
+        };
+        Tempo::from_whole_notes_per_second(segment.rate(position.as_f64() - segment.start), beat)
+    }
+    pub fn seconds_at(&self, position: Duration) -> f64 {
+        let position = position.as_f64();
+        let mut elapsed = 0.0;
+        for segment in self.segments() {
+            if position <= segment.start + segment.length {
+                return elapsed + segment.seconds(position - segment.start);
+            }
+            elapsed += segment.seconds(segment.length);
+        }
+        unreachable!()
+    }
+    pub fn seconds(&self, position: Duration, duration: Duration) -> f64 {
+        self.seconds_at(position + duration) - self.seconds_at(position)
+    }
+    pub fn position_at(&self, seconds: f64) -> f64 {
+        let mut elapsed = 0.0;
+        for segment in self.segments() {
+            let length = segment.seconds(segment.length);
+            if seconds <= elapsed + length {
+                return segment.start + segment.offset(seconds - elapsed);
+            }
+            elapsed += length;
+        }
+        unreachable!()
+    }
+    pub fn duration_at(&self, seconds: f64, grid: Subdivision) -> Duration {
+        Duration::quantize(self.position_at(seconds), grid)
+    }
+}
+
+impl Default for TempoMap {
+    fn default() -> Self {
+        TempoMap::new(Tempo::default())
+    }
+}
+
+impl From<Tempo> for TempoMap {
+    fn from(tempo: Tempo) -> Self {
+        TempoMap::new(tempo)
+    }
+}
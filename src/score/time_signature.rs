@@ -0,0 +1,128 @@
+use crate::note::{Duration, Subdivision};
+use crate::ParseError;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TimeSignature {
+    groups: Vec<usize>,
+    unit: Subdivision,
+}
+
+impl TimeSignature {
+    pub fn new(beats: usize, unit: Subdivision) -> Option<Self> {
+        TimeSignature::additive([beats], unit)
+    }
+    pub fn additive(groups: impl Into<Vec<usize>>, unit: Subdivision) -> Option<Self> {
+        let groups = groups.into();
+        match groups.is_empty() || groups.contains(&0) {
+            true => None,
+            false => Some(TimeSignature { groups, unit }),
+        }
+    }
+    pub fn common_time() -> Self {
+        TimeSignature {
+            groups: vec![4],
+            unit: Subdivision::QUARTER_NOTE,
+        }
+    }
+    pub fn cut_time() -> Self {
+        TimeSignature {
+            groups: vec![2],
+            unit: Subdivision::HALF_NOTE,
+        }
+    }
+    pub fn groups(&self) -> &[usize] {
+        &self.groups
+    }
+    pub const fn unit(&self) -> Subdivision {
+        self.unit
+    }
+    pub fn numerator(&self) -> usize {
+        self.groups.iter().sum()
+    }
+    pub const fn denominator(&self) -> usize {
+        self.unit.nth()
+    }
+    pub fn is_additive(&self) -> bool {
+        self.groups.len() > 1
+    }
+    pub fn is_compound(&self) -> bool {
+        !self.is_additive() && self.numerator() > 3 && self.numerator().is_multiple_of(3)
+    }
+    pub fn is_simple(&self) -> bool {
+        !self.is_additive() && !self.is_compound()
+    }
+    pub fn beats(&self) -> Vec<Duration> {
+        if self.is_additive() {
+            self.groups
+                .iter()
+                .map(|&group| Duration::new(group, self.unit).reduce())
+                .collect()
+        } else if self.is_compound() {
+            vec![Duration::new(3, self.unit).reduce(); self.numerator() / 3]
+        } else {
+            vec![self.unit.to_duration(); self.numerator()]
+        }
+    }
+    pub fn bar_duration(&self) -> Duration {
+        Duration::new(self.numerator(), self.unit).reduce()
+    }
+}
+
+impl fmt::Display for TimeSignature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, group) in self.groups.iter().enumerate() {
+            if i > 0 {
+                f.write_str("+")?;
+            }
+            group.fmt(f)?;
+        }
+        write!(f, "/{}", self.denominator())
+    }
+}
+
+impl FromStr for TimeSignature {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "C" => return Ok(TimeSignature::common_time()),
+            "C|" | "\u{a2}" => return Ok(TimeSignature::cut_time()),
+            _ => {}
+        }
+        let (groups, denominator) = s.split_once('/').ok_or(ParseError::UnexpectedEnd {
+            at: s.len(),
+            expected: &["/"],
+        })?;
+        let mut at = 0;
+        let mut beats = Vec::new();
+        for group in groups.split('+') {
+            match group.trim().parse() {
+                Ok(0) | Err(_) => {
+                    return Err(ParseError::unexpected(
+                        s,
+                        at..at + group.len(),
+                        &["beat count"],
+                    ))
+                }
+                Ok(group) => beats.push(group),
+            }
+            at += group.len() + 1;
+        }
+        let denominator = match usize::from_str(denominator.trim()) {
+            Ok(denominator) if denominator.is_power_of_two() => denominator,
+            _ => return Err(ParseError::at(s, groups.len() + 1, &["power of two"])),
+        };
+        Ok(TimeSignature {
+            groups: beats,
+            unit: Subdivision::new(denominator.trailing_zeros() as usize),
+        })
+    }
+}
+
+impl TryFrom<(usize, Subdivision)> for TimeSignature {
+    type Error = ();
+    fn try_from((beats, unit): (usize, Subdivision)) -> Result<Self, Self::Error> {
+        TimeSignature::new(beats, unit).ok_or(())
+    }
+}
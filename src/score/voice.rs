@@ -0,0 +1,187 @@
+use crate::note::{Duration, NoteEvent, Pitch};
+use std::ops::{Index, Range};
+
+fn split(event: &NoteEvent, at: Duration) -> Option<(NoteEvent, NoteEvent)> {
+    let NoteEvent::Tuplet { tuplet, events, .. } = event else {
+        return event.split_at(at);
+    };
+    let at = tuplet.written(at)?;
+    let mut head = Vec::new();
+    let mut tail = Vec::new();
+    let mut position = Duration::ZERO;
+    for event in events {
+        let end = position + event.value();
+        if end <= at {
+            head.push(event.clone());
+        } else if position >= at {
+            tail.push(event.clone());
+        } else {
+            let (first, second) = split(event, at - position)?;
+            head.push(first);
+            tail.push(second);
+        }
+        position = end;
+    }
+    if head.is_empty() || tail.is_empty() {
+        return None;
+    }
+    Some((
+        NoteEvent::tuplet(*tuplet, head)?,
+        NoteEvent::tuplet(*tuplet, tail)?,
+    ))
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Voice {
+    events: Vec<NoteEvent>,
+    offsets: Vec<Duration>,
+}
+
+impl Voice {
+    pub const fn new() -> Self {
+        Voice {
+            events: Vec::new(),
+            offsets: Vec::new(),
+        }
+    }
+    pub fn events(&self) -> &[NoteEvent] {
+        &self.events
+    }
+    pub fn offsets(&self) -> &[Duration] {
+        &self.offsets
+    }
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+    pub fn get(&self, index: usize) -> Option<&NoteEvent> {
+        self.events.get(index)
+    }
+    pub fn offset(&self, index: usize) -> Option<Duration> {
+        self.offsets.get(index).copied()
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (Duration, &NoteEvent)> {
+        self.offsets.iter().copied().zip(&self.events)
+    }
+    pub fn duration(&self) -> Duration {
+        match (self.offsets.last(), self.events.last()) {
+            (Some(&offset), Some(event)) => offset + event.value(),
+            _ => Duration::ZERO,
+        }
+    }
+    fn reflow(&mut self, from: usize) {
+        self.offsets.truncate(from);
+        let mut offset = match from.checked_sub(1) {
+            Some(i) => self.offsets[i] + self.events[i].value(),
+            None => Duration::ZERO,
+        };
+        for event in &self.events[from..] {
+            self.offsets.push(offset);
+            offset += event.value();
+        }
+    }
+    pub fn push(&mut self, event: NoteEvent) {
+        self.offsets.push(self.duration());
+        self.events.push(event);
+    }
+    pub fn insert(&mut self, index: usize, event: NoteEvent) {
+        self.events.insert(index, event);
+        self.reflow(index);
+    }
+    pub fn remove(&mut self, index: usize) -> NoteEvent {
+        let event = self.events.remove(index);
+        self.reflow(index);
+        event
+    }
+    pub fn replace(&mut self, index: usize, event: NoteEvent) -> NoteEvent {
+        let old = std::mem::replace(&mut self.events[index], event);
+        self.reflow(index);
+        old
+    }
+    pub fn index_at(&self, position: Duration) -> Option<usize> {
+        let end = self.offsets.partition_point(|&offset| offset <= position);
+        (0..end)
+            .rev()
+            .take_while(|&i| self.offsets[i] == self.offsets[end - 1])
+            .find(|&i| self.offsets[i] + self.events[i].value() > position)
+    }
+    pub fn at(&self, position: Duration) -> Option<(Duration, &NoteEvent)> {
+        self.index_at(position)
+            .map(|i| (self.offsets[i], &self.events[i]))
+    }
+    pub fn sounding_at(&self, position: Duration) -> &[Pitch] {
+        self.at(position).map_or(&[], |(_, event)| event.pitches())
+    }
+    pub fn overlapping(
+        &self,
+        range: Range<Duration>,
+    ) -> impl Iterator<Item = (Duration, &NoteEvent)> {
+        self.iter().filter(move |&(offset, event)| {
+            offset < range.end && offset + event.value() > range.start
+        })
+    }
+    pub fn slice(&self, range: Range<Duration>) -> Voice {
+        self.overlapping(range.clone())
+            .filter_map(|(offset, event)| {
+                let mut event = event.clone();
+                if offset < range.start {
+                    event = split(&event, range.start - offset)?.1;
+                }
+                if let Some((head, _)) =
+                    split(&event, range.end.saturating_sub(offset.max(range.start)))
+                {
+                    event = head;
+                }
+                Some(event)
+            })
+            .collect()
+    }
+}
+
+impl From<Vec<NoteEvent>> for Voice {
+    fn from(events: Vec<NoteEvent>) -> Self {
+        let mut voice = Voice {
+            events,
+            offsets: Vec::new(),
+        };
+        voice.reflow(0);
+        voice
+    }
+}
+
+impl From<Voice> for Vec<NoteEvent> {
+    fn from(voice: Voice) -> Self {
+        voice.events
+    }
+}
+
+impl FromIterator<NoteEvent> for Voice {
+    fn from_iter<I: IntoIterator<Item = NoteEvent>>(iter: I) -> Self {
+        Voice::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl Extend<NoteEvent> for Voice {
+    fn extend<I: IntoIterator<Item = NoteEvent>>(&mut self, iter: I) {
+        for event in iter {
+            self.push(event);
+        }
+    }
+}
+
+impl Index<usize> for Voice {
+    type Output = NoteEvent;
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.events[index]
+    }
+}
+
+impl IntoIterator for Voice {
+    type Item = NoteEvent;
+    type IntoIter = std::vec::IntoIter<NoteEvent>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.events.into_iter()
+    }
+}
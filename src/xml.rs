@@ -0,0 +1,248 @@
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(crate) struct Element {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Element>,
+    pub text: String,
+    pub offset: usize,
+}
+
+impl Element {
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+    pub fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.name == name)
+    }
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+    pub fn child_text(&self, name: &str) -> Option<&str> {
+        self.child(name).map(|child| child.text.trim())
+    }
+}
+
+pub(crate) struct XmlParser<'a> {
+    text: &'a str,
+    offset: usize,
+}
+
+impl<'a> XmlParser<'a> {
+    pub fn parse(text: &'a str) -> Result<Element, (usize, String)> {
+        let mut parser = XmlParser { text, offset: 0 };
+        parser.skip_misc()?;
+        let root = parser.element()?;
+        parser.skip_misc()?;
+        match parser.offset < text.len() {
+            true => Err((parser.offset, "content after the root element".to_string())),
+            false => Ok(root),
+        }
+    }
+    fn rest(&self) -> &'a str {
+        &self.text[self.offset..]
+    }
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, (usize, String)> {
+        Err((self.offset, message.into()))
+    }
+    fn skip_until(&mut self, end: &str) -> Result<&'a str, (usize, String)> {
+        match self.rest().find(end) {
+            Some(i) => {
+                let skipped = &self.rest()[..i];
+                self.offset += i + end.len();
+                Ok(skipped)
+            }
+            None => self.error(format!("expected `{end}`")),
+        }
+    }
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.offset += rest.len() - rest.trim_start().len();
+    }
+    fn skip_misc(&mut self) -> Result<(), (usize, String)> {
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("\u{feff}") {
+                self.offset += 3;
+            } else if rest.starts_with("<?") {
+                self.skip_until("?>")?;
+            } else if rest.starts_with("<!--") {
+                self.skip_until("-->")?;
+            } else if rest.starts_with("<!DOCTYPE") {
+                let end = rest
+                    .find(['[', '>'])
+                    .ok_or((self.offset, "unterminated doctype".to_string()))?;
+                self.offset += end;
+                if self.rest().starts_with('[') {
+                    self.skip_until("]")?;
+                }
+                self.skip_until(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+    fn name(&mut self) -> Result<String, (usize, String)> {
+        let rest = self.rest();
+        let end = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '=' | '/' | '>'))
+            .unwrap_or(rest.len());
+        if end == 0 {
+            return self.error("expected a name");
+        }
+        self.offset += end;
+        Ok(rest[..end].to_string())
+    }
+    fn element(&mut self) -> Result<Element, (usize, String)> {
+        let offset = self.offset;
+        if !self.rest().starts_with('<') {
+            return self.error("expected `<`");
+        }
+        self.offset += 1;
+        let mut element = Element {
+            name: self.name()?,
+            offset,
+            ..Element::default()
+        };
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("/>") {
+                self.offset += 2;
+                return Ok(element);
+            }
+            if rest.starts_with('>') {
+                self.offset += 1;
+                break;
+            }
+            let key = self.name()?;
+            self.skip_whitespace();
+            if !self.rest().starts_with('=') {
+                return self.error("expected `=`");
+            }
+            self.offset += 1;
+            self.skip_whitespace();
+            let quote = match self.rest().chars().next() {
+                Some(quote @ ('"' | '\'')) => quote,
+                _ => return self.error("expected a quoted attribute value"),
+            };
+            self.offset += 1;
+            let start = self.offset;
+            let value = self.skip_until(&quote.to_string())?;
+            element.attributes.push((key, unescape(value, start)?));
+        }
+        loop {
+            let rest = self.rest();
+            if rest.starts_with("</") {
+                self.offset += 2;
+                let name = self.name()?;
+                if name != element.name {
+                    return Err((
+                        self.offset - name.len(),
+                        format!("expected `</{}>`", element.name),
+                    ));
+                }
+                self.skip_whitespace();
+                self.skip_until(">")?;
+                return Ok(element);
+            } else if rest.starts_with("<!--") {
+                self.skip_until("-->")?;
+            } else if rest.starts_with("<![CDATA[") {
+                self.offset += 9;
+                element.text.push_str(self.skip_until("]]>")?);
+            } else if rest.starts_with("<?") {
+                self.skip_until("?>")?;
+            } else if rest.starts_with('<') {
+                element.children.push(self.element()?);
+            } else if rest.is_empty() {
+                return self.error(format!("expected `</{}>`", element.name));
+            } else {
+                let end = rest.find('<').unwrap_or(rest.len());
+                element.text.push_str(&unescape(&rest[..end], self.offset)?);
+                self.offset += end;
+            }
+        }
+    }
+}
+
+fn unescape(text: &str, offset: usize) -> Result<String, (usize, String)> {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(i) = rest.find('&') {
+        unescaped.push_str(&rest[..i]);
+        rest = &rest[i..];
+        let at = offset + text.len() - rest.len();
+        let end = rest
+            .find(';')
+            .ok_or((at, "unterminated entity".to_string()))?;
+        let c = match &rest[1..end] {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            entity => match entity.strip_prefix('#') {
+                Some(hex) if hex.starts_with(['x', 'X']) => u32::from_str_radix(&hex[1..], 16)
+                    .ok()
+                    .and_then(char::from_u32),
+                Some(decimal) => decimal.parse().ok().and_then(char::from_u32),
+                None => None,
+            },
+        };
+        unescaped.push(c.ok_or((at, format!("unknown entity `{}`", &rest[..=end])))?);
+        rest = &rest[end + 1..];
+    }
+    unescaped.push_str(rest);
+    Ok(unescaped)
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+pub(crate) struct XmlWriter {
+    pub out: String,
+    depth: usize,
+}
+
+impl XmlWriter {
+    pub const fn new() -> Self {
+        XmlWriter {
+            out: String::new(),
+            depth: 0,
+        }
+    }
+    pub fn line(&mut self, line: &str) {
+        self.out.push_str(&"  ".repeat(self.depth));
+        self.out.push_str(line);
+        self.out.push('\n');
+    }
+    pub fn open(&mut self, tag: &str) {
+        self.line(&format!("<{tag}>"));
+        self.depth += 1;
+    }
+    pub fn close(&mut self, name: &str) {
+        self.depth -= 1;
+        self.line(&format!("</{name}>"));
+    }
+    pub fn empty(&mut self, tag: &str) {
+        self.line(&format!("<{tag}/>"));
+    }
+    pub fn text(&mut self, name: &str, text: impl ToString) {
+        self.line(&format!("<{name}>{}</{name}>", escape(&text.to_string())));
+    }
+}